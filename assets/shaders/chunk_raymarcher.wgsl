@@ -34,6 +34,7 @@ const MATERIAL_FIRE: u32 = 5u;
 const MATERIAL_SMOKE: u32 = 6u;
 const MATERIAL_WATER: u32 = 7u;
 const MATERIAL_DEBRIS: u32 = 8u;
+const MATERIAL_GLASS: u32 = 9u;
 
 // Voxel flags
 const FLAG_COLLISION: u32 = 1u;
@@ -110,9 +111,28 @@ fn calculate_normal(pos: vec3<f32>) -> vec3<f32> {
     }
 }
 
-// Get color for a material type
-fn get_material_color(material: u32) -> vec4<f32> {
-    switch (material) {
+// Blackbody-style fire ramp: dull red when cool, orange, yellow, then near white when hottest
+fn fire_color(temperature: u32) -> vec4<f32> {
+    let t = f32(temperature) / 255.0 * 3.0;
+    let dull_red = vec3<f32>(0.5, 0.05, 0.0);
+    let orange = vec3<f32>(1.0, 0.45, 0.0);
+    let yellow = vec3<f32>(1.0, 0.85, 0.2);
+    let white = vec3<f32>(1.0, 1.0, 0.9);
+
+    var rgb: vec3<f32>;
+    if (t < 1.0) {
+        rgb = mix(dull_red, orange, t);
+    } else if (t < 2.0) {
+        rgb = mix(orange, yellow, t - 1.0);
+    } else {
+        rgb = mix(yellow, white, t - 2.0);
+    }
+    return vec4<f32>(rgb, 1.0);
+}
+
+// Get color for a voxel (material palette, fire tinted by temperature)
+fn get_material_color(voxel: VoxelData) -> vec4<f32> {
+    switch (voxel.material) {
         case MATERIAL_ROCK: {
             return vec4<f32>(0.5, 0.5, 0.5, 1.0);
         }
@@ -126,7 +146,7 @@ fn get_material_color(material: u32) -> vec4<f32> {
             return vec4<f32>(0.7, 0.7, 0.8, 1.0);
         }
         case MATERIAL_FIRE: {
-            return vec4<f32>(1.0, 0.5, 0.1, 1.0);
+            return fire_color(voxel.temperature);
         }
         case MATERIAL_SMOKE: {
             return vec4<f32>(0.2, 0.2, 0.2, 0.5);
@@ -137,6 +157,9 @@ fn get_material_color(material: u32) -> vec4<f32> {
         case MATERIAL_DEBRIS: {
             return vec4<f32>(0.6, 0.5, 0.4, 1.0);
         }
+        case MATERIAL_GLASS: {
+            return vec4<f32>(0.75, 0.9, 1.0, 0.3);
+        }
         default: {
             return vec4<f32>(0.0, 0.0, 0.0, 0.0); // Air is transparent
         }
@@ -217,7 +240,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         textureStore(normal_output, pixel_coords, vec4<f32>(normal_encoded, 1.0));
         
         // Diffuse map (material color)
-        let color = get_material_color(hit_voxel.material);
+        let color = get_material_color(hit_voxel);
         textureStore(diffuse_output, pixel_coords, color);
     } else {
         // No hit - write transparent/empty
@@ -26,6 +26,7 @@ const MATERIAL_FIRE: u32 = 5u;
 const MATERIAL_SMOKE: u32 = 6u;
 const MATERIAL_WATER: u32 = 7u;
 const MATERIAL_DEBRIS: u32 = 8u;
+const MATERIAL_GLASS: u32 = 9u;
 
 // Voxel flags
 const FLAG_COLLISION: u32 = 1u;
@@ -33,6 +34,8 @@ const FLAG_EMITS_LIGHT: u32 = 2u;
 const FLAG_TEMPORARY: u32 = 4u;
 const FLAG_STATIC: u32 = 8u;
 const FLAG_TRANSPARENT: u32 = 16u;
+const FLAG_SOURCE: u32 = 32u;
+const FLAG_DRAIN: u32 = 64u;
 
 // Unpack voxel data from u32
 struct VoxelData {
@@ -105,7 +108,8 @@ fn is_solid(voxel: VoxelData) -> bool {
            voxel.material == MATERIAL_ROCK ||
            voxel.material == MATERIAL_DIRT ||
            voxel.material == MATERIAL_WOOD ||
-           voxel.material == MATERIAL_METAL;
+           voxel.material == MATERIAL_METAL ||
+           voxel.material == MATERIAL_GLASS;
 }
 
 // ============================================================================
@@ -281,6 +285,12 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
     
+    // Sources and drains stay in place (they are anchors, not moving elements)
+    if ((current.flags & (FLAG_SOURCE | FLAG_DRAIN)) != 0u) {
+        write_voxel(pos, current);
+        return;
+    }
+    
     // Skip air
     if (is_empty(current)) {
         write_voxel(pos, current);
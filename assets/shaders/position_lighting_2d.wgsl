@@ -18,11 +18,25 @@ struct MaterialUniforms {
     light_pos_world_3d: vec3<f32>,  // XY = ground position, Z = virtual height in game world
     sprite_world_pos: vec2<f32>,    // Sprite's position on the ground (XY plane)
     light_color: vec4<f32>,         // Light's color and intensity
-    ambient_light_color: vec4<f32>, // Ambient light color and intensity
+    sky_ambient_color: vec4<f32>,   // Ambient for up-facing normals, color and intensity
+    ground_ambient_color: vec4<f32>, // Ambient for down-facing normals, color and intensity
     light_radius: f32,              // Light radius (in 3D space)
     light_falloff: f32,             // Light falloff exponent
     position_scale: f32,            // Scale factor to convert position map units to world units
-    debug_mode: u32,                // 0=normal, 1=positions, 2=normals, 3=distance, 4=ground level, 5=3D coords
+    debug_mode: u32,                // DebugMode: 0=normal, 1=positions, 2=normals, 3=3D distance, 4=ground level, 5=3D coords
+    shading_bands: u32,             // 0=smooth, otherwise number of toon shading bands
+    lighting_source: u32,           // 0=normal+position maps, 1=normal map only, 2=position map only
+}
+
+const LIGHTING_NORMAL_ONLY: u32 = 1u;
+const LIGHTING_POSITION_ONLY: u32 = 2u;
+
+// Hemisphere ambient: sky light from above fading to ground bounce below,
+// so tops and undersides read differently before any point light reaches them
+fn hemisphere_ambient(normal: vec3<f32>) -> vec3<f32> {
+    let sky = material_uniforms.sky_ambient_color.rgb * material_uniforms.sky_ambient_color.a;
+    let ground = material_uniforms.ground_ambient_color.rgb * material_uniforms.ground_ambient_color.a;
+    return mix(ground, sky, normal.z * 0.5 + 0.5);
 }
 
 struct VertexInput {
@@ -72,20 +86,46 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Sample diffuse color
     let diffuse_color = textureSample(diffuse_texture, diffuse_sampler, in.uv);
 
+    // Sample the position map - this contains local 3D positions relative to the sprite origin
+    let position_sample = textureSample(position_texture, position_sampler, in.uv);
+
+    // Surface orientation implied by the position map, used when there is no normal map.
+    // Derivatives need uniform control flow, so take them before any early return.
+    let position_normal = cross(dpdx(position_sample.rgb), dpdy(position_sample.rgb));
+    let uses_position_map = material_uniforms.lighting_source != LIGHTING_NORMAL_ONLY;
+
     // Skip fully transparent pixels
     if (diffuse_color.a < 0.01) {
         return vec4<f32>(0.0, 0.0, 0.0, 0.0);
     }
 
-    // Sample the position map - this contains local 3D positions relative to the sprite origin
-    let position_sample = textureSample(position_texture, position_sampler, in.uv);
-
     // Ignore black pixels (no geometry) FIRST - these represent empty space
     // Black pixels should always be transparent regardless of debug mode
-    if (length(position_sample.rgb) < 0.01) {
+    // (without a position map the diffuse alpha alone defines coverage)
+    if (uses_position_map && length(position_sample.rgb) < 0.01) {
         return vec4<f32>(0.0, 0.0, 0.0, 0.0);  // Fully transparent
     }
 
+    // Sample and process normal map for surface-angle-based lighting
+    let normal_sample = textureSample(normal_texture, normal_sampler, in.uv).rgb;
+
+    // Convert from [0,1] range to [-1,1] range
+    // The normal map from Blender should already be in tangent space
+    // For 2D sprites, we can use it directly or transform it to world space
+    // Since we're working with pre-rendered sprites, the normals are baked in the correct space
+    var surface_normal = normalize(normal_sample * 2.0 - 1.0);
+
+    // Without a normal map, light the surface implied by the position map (facing up/out)
+    if (material_uniforms.lighting_source == LIGHTING_POSITION_ONLY) {
+        surface_normal = vec3<f32>(0.0, 0.0, 1.0);
+        if (length(position_normal) > 1e-6) {
+            surface_normal = normalize(position_normal);
+            if (surface_normal.z < 0.0) {
+                surface_normal = -surface_normal;
+            }
+        }
+    }
+
     // Debug mode 1: Show raw position map
     if (material_uniforms.debug_mode == 1u) {
         return vec4<f32>(position_sample.rgb, diffuse_color.a);
@@ -110,8 +150,7 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // For normal rendering, apply only ambient lighting to non-geometry pixels
     // For normal rendering, apply only ambient lighting to non-geometry pixels
     if (length(position_sample.rgb) < 0.01) {
-        let ambient_contribution = material_uniforms.ambient_light_color.rgb * 
-                                  material_uniforms.ambient_light_color.a;
+        let ambient_contribution = hemisphere_ambient(surface_normal);
         let final_color = diffuse_color.rgb * ambient_contribution;
         return vec4<f32>(final_color, diffuse_color.a);
     }
@@ -131,12 +170,17 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Convert position map to world 3D coordinates:
     // X and Y: offset from sprite's ground position
     // Z: absolute virtual height (0 = ground level)
-    let pixel_world_pos_3d = vec3<f32>(
+    var pixel_world_pos_3d = vec3<f32>(
         material_uniforms.sprite_world_pos.x + (normalized_pos.r - 0.5) * material_uniforms.position_scale,
         material_uniforms.sprite_world_pos.y + (normalized_pos.g - 0.5) * material_uniforms.position_scale,
         normalized_pos.b * material_uniforms.position_scale  // Z is absolute height from ground
     );
 
+    // Classic 2D normal mapping: the fragment lies flat on the ground where it is drawn
+    if (!uses_position_map) {
+        pixel_world_pos_3d = vec3<f32>(in.world_position.xy, 0.0);
+    }
+
     // Debug mode 5: Show computed 3D world positions
     if (material_uniforms.debug_mode == 5u) {
         // Normalize the world position for visualization
@@ -151,12 +195,18 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let light_vector = material_uniforms.light_pos_world_3d - pixel_world_pos_3d;
     let distance_to_light_3d = length(light_vector);
 
-    // Debug mode 3: Show distance as grayscale
+    // Debug mode 3: Show the full 3D distance (virtual height included) as grayscale
+    // White at the light fading to black at light_radius, with a darker ring every 10%
+    // of the radius so raising or lowering the light visibly moves the rings.
+    // Pixels past the radius get no light at all and are tinted dark red.
     if (material_uniforms.debug_mode == 3u) {
-        let normalized_distance = clamp(distance_to_light_3d / material_uniforms.light_radius, 0.0, 1.0);
-        // Show in color: red=too far, yellow=medium, green=close
-        let close_factor = 1.0 - normalized_distance;
-        return vec4<f32>(normalized_distance, close_factor, 0.0, diffuse_color.a);
+        let normalized_distance = distance_to_light_3d / material_uniforms.light_radius;
+        if (normalized_distance > 1.0) {
+            return vec4<f32>(0.3, 0.0, 0.0, diffuse_color.a);
+        }
+        let ring = step(0.95, fract(normalized_distance * 10.0));
+        let brightness = (1.0 - normalized_distance) * (1.0 - 0.5 * ring);
+        return vec4<f32>(vec3<f32>(brightness), diffuse_color.a);
     }
 
     // Calculate light attenuation based on 3D distance
@@ -166,28 +216,16 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Hard cutoff: if outside radius, no light at all
     if (distance_to_light_3d > material_uniforms.light_radius) {
         // Only ambient lighting
-        let ambient_contribution = material_uniforms.ambient_light_color.rgb * 
-                                  material_uniforms.ambient_light_color.a;
+        let ambient_contribution = hemisphere_ambient(surface_normal);
         let final_color = diffuse_color.rgb * ambient_contribution;
         return vec4<f32>(final_color, diffuse_color.a);
     }
 
-    // Sample and process normal map for surface-angle-based lighting
-    var normal_sample = textureSample(normal_texture, normal_sampler, in.uv).rgb;
-
     // Debug mode 2: Show normal map
     if (material_uniforms.debug_mode == 2u) {
         return vec4<f32>(normal_sample, diffuse_color.a);
     }
 
-    // Convert from [0,1] range to [-1,1] range
-    normal_sample = (normal_sample * 2.0 - 1.0);
-
-    // The normal map from Blender should already be in tangent space
-    // For 2D sprites, we can use it directly or transform it to world space
-    // Since we're working with pre-rendered sprites, the normals are baked in the correct space
-    let surface_normal = normalize(normal_sample);
-
     // Calculate the light direction (normalized)
     let light_direction = normalize(light_vector);
 
@@ -196,15 +234,20 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let n_dot_l = max(dot(surface_normal, light_direction), 0.0);
 
     // Combine distance attenuation with surface angle
-    let final_light_intensity = light_attenuation * n_dot_l;
+    var final_light_intensity = light_attenuation * n_dot_l;
+
+    // Toon shading: quantize the diffuse term into discrete bands
+    if (material_uniforms.shading_bands > 0u) {
+        let bands = f32(material_uniforms.shading_bands);
+        final_light_intensity = min(floor(final_light_intensity * bands) / (bands - 1.0), 1.0);
+    }
 
     // Apply light color and intensity
     let light_contribution = material_uniforms.light_color.rgb * 
                             material_uniforms.light_color.a * final_light_intensity;
 
     // Add ambient lighting
-    let ambient_contribution = material_uniforms.ambient_light_color.rgb * 
-                              material_uniforms.ambient_light_color.a;
+    let ambient_contribution = hemisphere_ambient(surface_normal);
 
     // Final color
     let final_color = diffuse_color.rgb * (light_contribution + ambient_contribution);
@@ -9,7 +9,9 @@
 @group(0) @binding(4) var diffuse_output: texture_storage_2d<rgba8unorm, write>;
 
 struct VolumeParams {
-    rotation_matrix: mat3x3<f32>, // Rotation transformation
+    rotation_matrix: mat3x3<f32>, // Volume to view space, for normals
+    inverse_rotation_matrix: mat3x3<f32>, // View to volume space, for rays
+    ray_origin: vec3<f32>,         // Volume-space start of the ray through the output's center
     volume_size: f32,              // Size of the volume
     threshold: f32,                // Density threshold for hit detection
     output_width: u32,             // Output texture width
@@ -68,27 +70,20 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     }
     
     let vol_size = params.volume_size;
-    let center = vol_size * 0.5;
     let inv_vol_size = 1.0 / vol_size;
     
-    // Precompute reciprocals for division optimization
-    let inv_output_width = 1.0 / f32(params.output_width);
-    let inv_output_height = 1.0 / f32(params.output_height);
+    // Square pixels: the longer output side spans the whole volume
+    let output_size = vec2<f32>(f32(params.output_width), f32(params.output_height));
+    let pixel_size = vol_size / max(output_size.x, output_size.y);
     
     // Map pixel to screen space (centered)
-    let screen_x = (f32(pixel_coords.x) * inv_output_width - 0.5) * vol_size;
-    let screen_y = (f32(pixel_coords.y) * inv_output_height - 0.5) * vol_size;
+    let screen_x = (f32(pixel_coords.x) - output_size.x * 0.5) * pixel_size;
+    let screen_y = (f32(pixel_coords.y) - output_size.y * 0.5) * pixel_size;
     
     // Ray in screen space
-    let ray_start = vec3<f32>(screen_x, screen_y, -vol_size);
+    let ray_start = vec3<f32>(screen_x, screen_y, 0.0);
     let ray_dir = vec3<f32>(0.0, 0.0, 1.0);
     
-    // Create inverse rotation matrix (transpose for orthogonal matrices)
-    let inv_rotation = transpose(params.rotation_matrix);
-    
-    // Precompute center offset
-    let center_offset = vec3<f32>(center);
-    
     // Raymarch through the volume
     let max_steps = u32(vol_size * 1.5);
     let step_size = 0.75;
@@ -101,7 +96,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         let ray_pos = ray_start + ray_dir * t;
         
         // Rotate ray position to volume space
-        let rotated_pos = rotate_point(ray_pos, inv_rotation) + center_offset;
+        let rotated_pos = rotate_point(ray_pos, params.inverse_rotation_matrix) + params.ray_origin;
         
         // Sample the volume
         let density = sample_volume(rotated_pos);
@@ -0,0 +1,23 @@
+// Weighted-blended order-independent transparency, one draw per buffer
+// The accumulate pass adds up weighted premultiplied color; the revealage pass
+// (OIT_REVEALAGE) multiplies the target by (1 - alpha) and keeps the nearest
+// draw depth in alpha. Blending is set up on the Rust side, see `OitPass::blend_state`.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var<uniform> color: vec4<f32>;
+
+// Growth of a fragment's weight per unit of isometric depth toward the viewer
+const DEPTH_WEIGHT_FALLOFF: f32 = 0.1;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let alpha = color.a;
+#ifdef OIT_REVEALAGE
+    return vec4<f32>(vec3<f32>(alpha), in.world_position.z);
+#else
+    // Nearer smoke (larger depth) weighs more, so the front of a cloud sets its color
+    let weight = alpha * clamp(exp(in.world_position.z * DEPTH_WEIGHT_FALLOFF), 1e-2, 3e3);
+    return vec4<f32>(color.rgb * weight, weight);
+#endif
+}
@@ -0,0 +1,32 @@
+// Composite the OIT buffers over the scene
+// The buffers match the window's resolution, so they're read per pixel. Each pixel
+// takes the depth of its nearest smoke, so opaque voxels in front of it fail the
+// depth test and hide it.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import bevy_sprite::mesh2d_view_bindings::view
+
+@group(2) @binding(0) var accum_texture: texture_2d<f32>;
+@group(2) @binding(1) var revealage_texture: texture_2d<f32>;
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> FragmentOutput {
+    let pixel = vec2<i32>(in.position.xy);
+    let revealage = textureLoad(revealage_texture, pixel, 0);
+    if (revealage.r >= 1.0) {
+        discard;
+    }
+
+    // Weighted average color, covering all but the share of the scene still revealed
+    let accum = textureLoad(accum_texture, pixel, 0);
+    var out: FragmentOutput;
+    out.color = vec4<f32>(accum.rgb / max(accum.a, 1e-5), 1.0 - revealage.r);
+    let nearest = view.clip_from_world * vec4<f32>(in.world_position.xy, revealage.a, 1.0);
+    out.depth = nearest.z / nearest.w;
+    return out;
+}
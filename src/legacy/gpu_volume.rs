@@ -7,7 +7,7 @@ use bevy::render::renderer::{RenderContext, RenderDevice};
 use bevy::render::{Render, RenderApp, RenderSet};
 use bevy::render::texture::GpuImage;
 
-use crate::volume::Volume;
+use crate::volume::{camera_rays, euler_camera, Volume};
 
 /// Component for entities that use GPU volume rendering
 #[derive(Component, Clone)]
@@ -17,8 +17,11 @@ pub struct GpuVolumeRenderer {
     pub normal_output: Handle<Image>,
     pub diffuse_output: Handle<Image>,
     pub rotation: Vec3,
+    /// Camera placed in volume space, overriding `rotation`; see `camera_rays`
+    pub camera: Option<Transform>,
     pub volume_size: f32,
-    pub output_size: u32,
+    /// Output map width and height in pixels
+    pub output_size: UVec2,
 }
 
 impl ExtractComponent for GpuVolumeRenderer {
@@ -34,7 +37,12 @@ impl ExtractComponent for GpuVolumeRenderer {
 /// Shader uniform for volume rendering parameters
 #[derive(ShaderType, Clone, Copy)]
 struct VolumeParamsUniform {
+    /// Volume space to view space, for normals
     rotation_matrix: Mat3,
+    /// View space to volume space, for rays
+    inverse_rotation_matrix: Mat3,
+    /// Volume-space start of the ray through the output's center
+    ray_origin: Vec3,
     volume_size: f32,
     threshold: f32,
     output_width: u32,
@@ -55,7 +63,7 @@ struct VolumeRenderLabel;
 /// Upload volume data to GPU as a 3D texture
 pub fn create_volume_texture(
     volume: &Volume,
-    images: &mut ResMut<Assets<Image>>,
+    images: &mut Assets<Image>,
 ) -> Handle<Image> {
     let size = volume.dimensions.x;
     
@@ -92,31 +100,32 @@ pub fn create_volume_texture(
     images.add(image)
 }
 
+/// Blank map the compute shader can write into
+pub fn output_texture(size: UVec2) -> Image {
+    let mut img = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        vec![0u8; (size.x * size.y * 4) as usize],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    // Mark as storage texture for GPU compute shader writes
+    img.texture_descriptor.usage = TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
+    img
+}
+
 /// Create output textures for position, normal, and diffuse maps
 pub fn create_output_textures(
-    size: u32,
-    images: &mut ResMut<Assets<Image>>,
+    size: UVec2,
+    images: &mut Assets<Image>,
 ) -> (Handle<Image>, Handle<Image>, Handle<Image>) {
-    let create_texture = |format: TextureFormat| {
-        let mut img = Image::new(
-            Extent3d {
-                width: size,
-                height: size,
-                depth_or_array_layers: 1,
-            },
-            TextureDimension::D2,
-            vec![0u8; (size * size * 4) as usize],
-            format,
-            RenderAssetUsages::RENDER_WORLD,
-        );
-        // Mark as storage texture for GPU compute shader writes
-        img.texture_descriptor.usage = TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
-        img
-    };
-    
-    let position = images.add(create_texture(TextureFormat::Rgba8Unorm));
-    let normal = images.add(create_texture(TextureFormat::Rgba8Unorm));
-    let diffuse = images.add(create_texture(TextureFormat::Rgba8Unorm));
+    let position = images.add(output_texture(size));
+    let normal = images.add(output_texture(size));
+    let diffuse = images.add(output_texture(size));
     
     (position, normal, diffuse)
 }
@@ -257,6 +266,10 @@ impl render_graph::Node for VolumeRenderNode {
             return Ok(());
         }
         
+        // Prepare a bind group per renderer (each writes to its own output textures),
+        // then record every dispatch into a single compute pass
+        let mut dispatches: Vec<(BindGroup, u32, u32)> = Vec::with_capacity(entity_renderer_pairs.len());
+        
         for (_, renderer) in entity_renderer_pairs {
             // Get GPU textures
             let Some(volume_texture) = gpu_images.get(&renderer.volume_texture) else {
@@ -272,38 +285,21 @@ impl render_graph::Node for VolumeRenderNode {
                 continue;
             };
             
-            // Create rotation matrix
-            let (sx, cx) = renderer.rotation.x.sin_cos();
-            let (sy, cy) = renderer.rotation.y.sin_cos();
-            let (sz, cz) = renderer.rotation.z.sin_cos();
-            
-            let rx = Mat3::from_cols(
-                Vec3::new(1.0, 0.0, 0.0),
-                Vec3::new(0.0, cx, sx),
-                Vec3::new(0.0, -sx, cx),
-            );
-            
-            let ry = Mat3::from_cols(
-                Vec3::new(cy, 0.0, -sy),
-                Vec3::new(0.0, 1.0, 0.0),
-                Vec3::new(sy, 0.0, cy),
-            );
-            
-            let rz = Mat3::from_cols(
-                Vec3::new(cz, sz, 0.0),
-                Vec3::new(-sz, cz, 0.0),
-                Vec3::new(0.0, 0.0, 1.0),
-            );
-            
-            let rotation_matrix = rz * ry * rx;
+            // Same camera as the CPU renderer, so both modes show the same view
+            let camera = renderer
+                .camera
+                .unwrap_or_else(|| euler_camera(renderer.rotation, renderer.volume_size));
+            let (inverse_rotation_matrix, ray_origin) = camera_rays(&camera, Vec3::splat(renderer.volume_size));
             
             // Create uniform data
             let params = VolumeParamsUniform {
-                rotation_matrix,
+                rotation_matrix: inverse_rotation_matrix.transpose(),
+                inverse_rotation_matrix,
+                ray_origin,
                 volume_size: renderer.volume_size,
                 threshold: 0.3,
-                output_width: renderer.output_size,
-                output_height: renderer.output_size,
+                output_width: renderer.output_size.x,
+                output_height: renderer.output_size.y,
             };
             
             // Create uniform buffer
@@ -328,21 +324,29 @@ impl render_graph::Node for VolumeRenderNode {
                 )),
             );
             
-            // Dispatch compute shader
-            let mut pass = render_context
-                .command_encoder()
-                .begin_compute_pass(&ComputePassDescriptor {
-                    label: Some("volume_render_pass"),
-                    timestamp_writes: None,
-                });
-            
-            pass.set_pipeline(compute_pipeline);
-            pass.set_bind_group(0, &bind_group, &[]);
-            
             // Dispatch with 8x8 workgroups
-            let workgroup_count_x = renderer.output_size.div_ceil(8);
-            let workgroup_count_y = renderer.output_size.div_ceil(8);
-            pass.dispatch_workgroups(workgroup_count_x, workgroup_count_y, 1);
+            let workgroup_count_x = renderer.output_size.x.div_ceil(8);
+            let workgroup_count_y = renderer.output_size.y.div_ceil(8);
+            dispatches.push((bind_group, workgroup_count_x, workgroup_count_y));
+        }
+        
+        if dispatches.is_empty() {
+            return Ok(());
+        }
+        
+        // Dispatch compute shader for all renderers
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor {
+                label: Some("volume_render_pass"),
+                timestamp_writes: None,
+            });
+        
+        pass.set_pipeline(compute_pipeline);
+        
+        for (bind_group, workgroup_count_x, workgroup_count_y) in &dispatches {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(*workgroup_count_x, *workgroup_count_y, 1);
         }
         
         Ok(())
@@ -363,7 +367,7 @@ impl Plugin for GpuVolumeRenderPlugin {
         shaders.insert(
             &VOLUME_SHADER_HANDLE,
             Shader::from_wgsl(
-                include_str!("../assets/shaders/volume_raymarcher.wgsl"),
+                include_str!("../../assets/shaders/volume_raymarcher.wgsl"),
                 "volume_raymarcher.wgsl",
             ),
         );
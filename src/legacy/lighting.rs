@@ -4,13 +4,264 @@ use bevy::prelude::*;
 pub struct MovableLightMarker {
     pub color: Color,
     pub intensity: f32,
-    pub ambient_color: Color,
+    /// Ambient light on surfaces facing up (+Z, away from the ground)
+    pub sky_ambient_color: Color,
+    /// Ambient light on surfaces facing down; normals in between blend the two
+    pub ground_ambient_color: Color,
     pub ambient_intensity: f32,
     pub radius: f32,
     pub falloff: f32,
     pub position_scale: f32,
-    pub debug_mode: u32,
+    pub debug_mode: DebugMode,
     pub virtual_height: f32, // The virtual Z height in game world
+    pub shading_mode: ShadingMode,
+    pub lighting_source: LightingSource,
+}
+
+/// How the diffuse lighting term is shaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShadingMode {
+    /// Continuous lighting falloff
+    #[default]
+    Smooth,
+    /// Cel shading with the diffuse term quantized into `bands` steps
+    Toon { bands: u32 },
+}
+
+impl ShadingMode {
+    /// Band count for the shader uniform (0 = smooth)
+    pub fn bands(&self) -> u32 {
+        match self {
+            ShadingMode::Smooth => 0,
+            ShadingMode::Toon { bands } => (*bands).max(2),
+        }
+    }
+}
+
+/// Which maps the sprite shader lights from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LightingSource {
+    /// Position map for distance/direction and normal map for surface angle
+    #[default]
+    Both,
+    /// Classic 2D normal mapping: the fragment's ground position stands in for the position map
+    NormalOnly,
+    /// Surface angle derived from the position map, for assets without a normal map
+    PositionOnly,
+}
+
+impl LightingSource {
+    /// Value for the shader uniform
+    pub fn as_uniform(&self) -> u32 {
+        match self {
+            LightingSource::Both => 0,
+            LightingSource::NormalOnly => 1,
+            LightingSource::PositionOnly => 2,
+        }
+    }
+
+    /// Next source in the cycle order
+    pub fn next(&self) -> Self {
+        match self {
+            LightingSource::Both => LightingSource::NormalOnly,
+            LightingSource::NormalOnly => LightingSource::PositionOnly,
+            LightingSource::PositionOnly => LightingSource::Both,
+        }
+    }
+
+    /// Label for the status panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightingSource::Both => "Normal + Position",
+            LightingSource::NormalOnly => "Normal Map Only",
+            LightingSource::PositionOnly => "Position Map Only",
+        }
+    }
+}
+
+/// Debug view the sprite shader draws instead of normal lighting
+/// The shader's `debug_mode` branches match `as_uniform`; add new views here and there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DebugMode {
+    /// Normal lighting
+    #[default]
+    Off,
+    PositionMap,
+    NormalMap,
+    LightDistance,
+    GroundLevel,
+    WorldPositions,
+}
+
+impl DebugMode {
+    /// Every mode in cycle order, starting with `Off`
+    pub const ALL: [DebugMode; 6] = [
+        DebugMode::Off,
+        DebugMode::PositionMap,
+        DebugMode::NormalMap,
+        DebugMode::LightDistance,
+        DebugMode::GroundLevel,
+        DebugMode::WorldPositions,
+    ];
+
+    /// Value for the shader uniform
+    pub fn as_uniform(&self) -> u32 {
+        match self {
+            DebugMode::Off => 0,
+            DebugMode::PositionMap => 1,
+            DebugMode::NormalMap => 2,
+            DebugMode::LightDistance => 3,
+            DebugMode::GroundLevel => 4,
+            DebugMode::WorldPositions => 5,
+        }
+    }
+
+    /// Next mode in the cycle order, wrapping back to `Off`
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Label for the status panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            DebugMode::Off => "Normal Lighting",
+            DebugMode::PositionMap => "Position Map (RGB = XYZ)",
+            DebugMode::NormalMap => "Normal Map",
+            DebugMode::LightDistance => "Distance to Light (3D)",
+            DebugMode::GroundLevel => "Ground Level Only",
+            DebugMode::WorldPositions => "3D World Positions",
+        }
+    }
+}
+
+/// How a `LightPath` continues after its last waypoint
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LightPathMode {
+    /// Wrap back to the first waypoint (the path is closed)
+    #[default]
+    Loop,
+    /// Reverse direction at each end
+    PingPong,
+}
+
+/// Moves a `MovableLightMarker` along a Catmull-Rom spline through `waypoints`
+/// Waypoint XY is the ground position and Z the virtual height. One pass over
+/// the path (one direction for `PingPong`) takes `duration` seconds.
+#[derive(Component, Clone)]
+pub struct LightPath {
+    pub waypoints: Vec<Vec3>,
+    pub duration: f32,
+    pub mode: LightPathMode,
+    pub elapsed: f32,
+}
+
+impl LightPath {
+    pub fn new(waypoints: Vec<Vec3>, duration: f32, mode: LightPathMode) -> Self {
+        Self {
+            waypoints,
+            duration,
+            mode,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Position on the spline at the current elapsed time
+    pub fn current_position(&self) -> Option<Vec3> {
+        let phase = self.elapsed / self.duration.max(f32::EPSILON);
+        let t = match self.mode {
+            LightPathMode::Loop => phase.fract(),
+            LightPathMode::PingPong => {
+                let bounce = phase % 2.0;
+                if bounce > 1.0 { 2.0 - bounce } else { bounce }
+            }
+        };
+        self.sample(t)
+    }
+
+    /// Position on the spline at `t` in 0..=1 along the whole path
+    pub fn sample(&self, t: f32) -> Option<Vec3> {
+        let points = &self.waypoints;
+        let count = points.len();
+        if count < 2 {
+            return points.first().copied();
+        }
+
+        let t = t.clamp(0.0, 1.0);
+        let (p0, p1, p2, p3, local_t) = match self.mode {
+            LightPathMode::Loop => {
+                // Closed path: one segment per waypoint, neighbours wrap around
+                let s = t * count as f32;
+                let i = (s as usize).min(count - 1);
+                let at = |offset: usize| points[(i + offset) % count];
+                (at(count - 1), at(0), at(1), at(2), s - i as f32)
+            }
+            LightPathMode::PingPong => {
+                // Open path: end points are repeated so the curve stops on them
+                let s = t * (count - 1) as f32;
+                let i = (s as usize).min(count - 2);
+                let p0 = points[i.saturating_sub(1)];
+                let p3 = points[(i + 2).min(count - 1)];
+                (p0, points[i], points[i + 1], p3, s - i as f32)
+            }
+        };
+
+        Some(catmull_rom(p0, p1, p2, p3, local_t))
+    }
+}
+
+/// Uniform Catmull-Rom interpolation between `p1` and `p2`
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Advance each light along its `LightPath`
+pub fn follow_light_path(
+    time: Res<Time>,
+    mut query: Query<(&mut LightPath, &mut Transform, &mut MovableLightMarker)>,
+) {
+    for (mut path, mut transform, mut light) in query.iter_mut() {
+        path.elapsed += time.delta_secs();
+        let Some(position) = path.current_position() else {
+            continue;
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        light.virtual_height = position.z;
+    }
+}
+
+/// Number of lights the sprite lighting shader takes per material
+pub const MAX_LIGHTS_PER_SPRITE: usize = 1;
+
+/// Lights whose radius reaches a sprite's ground-plane bounds, strongest first
+/// The test is conservative: it ignores virtual height, which only moves a light further
+/// away. At most `MAX_LIGHTS_PER_SPRITE` lights are returned; none means ambient only.
+pub fn cull_lights_for_sprite<'a>(
+    sprite_bounds: Rect,
+    lights: impl IntoIterator<Item = (Vec2, &'a MovableLightMarker)>,
+) -> Vec<(Vec2, &'a MovableLightMarker)> {
+    let mut in_range: Vec<(f32, Vec2, &MovableLightMarker)> = lights
+        .into_iter()
+        .filter_map(|(position, light)| {
+            let closest = position.clamp(sprite_bounds.min, sprite_bounds.max);
+            let distance = position.distance(closest);
+            (distance < light.radius)
+                .then(|| (light.intensity * (1.0 - distance / light.radius), position, light))
+        })
+        .collect();
+
+    in_range.sort_by(|a, b| b.0.total_cmp(&a.0));
+    in_range
+        .into_iter()
+        .take(MAX_LIGHTS_PER_SPRITE)
+        .map(|(_, position, light)| (position, light))
+        .collect()
 }
 
 impl Default for MovableLightMarker {
@@ -18,13 +269,16 @@ impl Default for MovableLightMarker {
         Self {
             color: Color::WHITE,
             intensity: 1.0,
-            ambient_color: Color::srgb(0.1, 0.1, 0.15),
+            sky_ambient_color: Color::srgb(0.1, 0.1, 0.15),
+            ground_ambient_color: Color::srgb(0.06, 0.05, 0.04),
             ambient_intensity: 0.2,
             radius: 300.0,
             falloff: 1.5,
             position_scale: 1.0,
-            debug_mode: 0,
+            debug_mode: DebugMode::Off,
             virtual_height: 0.0,
+            shading_mode: ShadingMode::Smooth,
+            lighting_source: LightingSource::Both,
         }
     }
 }
@@ -1,11 +1,16 @@
 use bevy::{
     color::palettes::css,
+    ecs::system::SystemParam,
     math::primitives::Rectangle,
     prelude::*,
     reflect::TypePath,
-    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{AsBindGroup, ShaderRef, ShaderType},
+    },
     sprite::{AlphaMode2d, Material2d, Material2dPlugin},
 };
+use std::ops::RangeInclusive;
 
 mod volume;
 use volume::*;
@@ -27,11 +32,14 @@ pub struct LightUniformData {
     light_pos_world_3d: Vec3, // XY = ground position, Z = virtual height
     sprite_world_pos: Vec2,   // Sprite's position on the ground (XY)
     light_color: LinearRgba,
-    ambient_light_color: LinearRgba,
+    sky_ambient_color: LinearRgba,    // Ambient for up-facing normals, intensity premultiplied
+    ground_ambient_color: LinearRgba, // Ambient for down-facing normals
     light_radius: f32,
     light_falloff: f32,
     position_scale: f32,
-    debug_mode: u32,
+    debug_mode: u32, // See DebugMode::as_uniform
+    shading_bands: u32, // 0 = smooth, otherwise toon band count
+    lighting_source: u32, // See LightingSource::as_uniform
 }
 
 #[derive(AsBindGroup, Debug, Clone, Asset, TypePath)]
@@ -66,13 +74,72 @@ impl Material2d for PositionMappedMaterial {
     }
 }
 
+/// Volume edge, output size and run count `--bench-render` uses when not given
+const BENCH_VOLUME_SIZE: u32 = 64;
+const BENCH_DEFAULT_OUTPUT: u32 = 256;
+const BENCH_DEFAULT_RUNS: usize = 20;
+
+/// Deterministic volume `--bench-render` times, by name
+/// `torus` comes from an SDF, so its surface is soft where the test patterns are hard-edged.
+fn bench_volume(shape: &str) -> Option<Volume> {
+    let dims = UVec3::splat(BENCH_VOLUME_SIZE);
+    match shape {
+        "sphere" => Some(generate_test_volume(dims, TestPattern::Sphere)),
+        "cube" => Some(generate_test_volume(dims, TestPattern::SolidCube)),
+        "checkerboard" => Some(generate_test_volume(dims, TestPattern::Checkerboard)),
+        "gradient" => Some(generate_test_volume(dims, TestPattern::Gradient)),
+        "torus" => {
+            let major = BENCH_VOLUME_SIZE as f32 * 0.3;
+            let minor = BENCH_VOLUME_SIZE as f32 * 0.1;
+            Some(Volume::from_sdf(dims, |p| Vec2::new(p.xz().length() - major, p.y).length() - minor))
+        }
+        _ => None,
+    }
+}
+
+/// `--bench-render [OUTPUT_SIZE] [RUNS] [SHAPE]`: time the CPU raymarcher without opening a window
+/// Renders a deterministic shape (`sphere` by default, `cube`, `checkerboard`, `gradient` or
+/// `torus`) at a fixed tilt so numbers compare across builds.
+/// Run it with `cargo run --release --bin legacy_demo -- --bench-render`.
+fn run_render_benchmark(args: &[String]) {
+    let output = args.first().and_then(|arg| arg.parse().ok()).unwrap_or(BENCH_DEFAULT_OUTPUT);
+    let runs = args.get(1).and_then(|arg| arg.parse().ok()).unwrap_or(BENCH_DEFAULT_RUNS).max(1);
+    let shape = args.get(2).map_or("sphere", String::as_str);
+
+    let Some(volume) = bench_volume(shape) else {
+        eprintln!("unknown bench shape '{}', expected sphere, cube, checkerboard, gradient or torus", shape);
+        return;
+    };
+    let rotation = Vec3::new(0.4, 0.7, 0.0);
+    println!(
+        "render_volume_to_maps: {}^3 {} -> {}x{} maps, {} runs",
+        BENCH_VOLUME_SIZE, shape, output, output, runs
+    );
+
+    let timings = time_render_volume_to_maps(&volume, UVec2::splat(output), rotation, runs);
+    println!(
+        "mean {:.2} ms, median {:.2} ms (min {:.2}, max {:.2})",
+        timings.mean_ms, timings.median_ms, timings.min_ms, timings.max_ms
+    );
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(flag) = args.iter().position(|arg| arg == "--bench-render") {
+        run_render_benchmark(&args[flag + 1..]);
+        return;
+    }
+
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(Material2dPlugin::<PositionMappedMaterial>::default())
         .add_plugins(GpuVolumeRenderPlugin)
         .init_resource::<CurrentScene>()
         .init_resource::<VolumeRenderMode>()
+        .init_resource::<NormalGizmos>()
+        .init_resource::<GroundGrid>()
+        .init_resource::<LightLimitFlash>()
+        .init_resource::<VolumeCache>()
         .add_systems(Startup, (setup_texture_mapped_scene, setup_camera))
         .add_systems(
             Update,
@@ -80,22 +147,132 @@ fn main() {
                 handle_input,
                 handle_scene_switching,
                 control_light_properties,
-                control_volume_rotation,
+                flash_status_panel.after(control_light_properties),
+                cycle_selected_volume,
+                control_volume_rotation.after(cycle_selected_volume),
                 toggle_render_mode,
                 update_procedural_volume,
+                sculpt_selected_volume.after(update_procedural_volume),
                 update_gpu_volume,
                 update_material_light_info,
                 update_debug_mode_display,
+                (bake_selected_turntable, animate_turntable).chain(),
+                inspect_volume_slice,
+                toggle_light_path,
+                update_ground_shadow,
+                draw_normal_gizmos,
+                draw_ground_grid,
+                fit_sprites_to_diffuse,
+                follow_light_path.after(handle_input),
             ),
         )
         .run();
 }
 
+/// Normal map precision and channel layout used by the CPU procedural renderer
+const PROCEDURAL_NORMAL_OPTIONS: NormalMapOptions = NormalMapOptions {
+    format: NormalMapFormat::Rgba16,
+    channel_layout: ChannelLayout {
+        r: ChannelSource::X,
+        g: ChannelSource::Y,
+        b: ChannelSource::Z,
+        a: ChannelSource::Constant(1.0),
+    },
+};
+
+/// Resolution of the procedural scene's maps; the sprite mesh is sized to match
+const PROCEDURAL_OUTPUT_SIZE: UVec2 = UVec2::splat(256);
+
+/// Plays a turntable atlas (see `bake_turntable_atlas`) by stepping the sprite rect
+#[derive(Component)]
+pub struct TurntableAnimation {
+    pub frames: u32,
+    pub frame_size: u32,
+    pub frames_per_second: f32,
+    pub elapsed: f32,
+}
+
+/// Frames, frame size and playback rate of the turntable baked with O
+const TURNTABLE_FRAMES: u32 = 16;
+const TURNTABLE_FRAME_SIZE: u32 = 128;
+const TURNTABLE_FPS: f32 = 12.0;
+
+/// Bake the selected rock into a turntable atlas with O and play it beside the rock
+/// Uses the rock's latest render settings, so a camera view turns as well; re-baking
+/// replaces the previous turntable.
+fn bake_selected_turntable(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected: Query<(&ProceduralVolume, &Transform), With<SelectedVolume>>,
+    turntables: Query<Entity, With<TurntableAnimation>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyO) {
+        return;
+    }
+    let Ok((proc_volume, transform)) = selected.single() else {
+        return;
+    };
+
+    let settings = proc_volume
+        .last_render
+        .as_ref()
+        .map_or(RenderSettings { rotation: proc_volume.rotation, ..default() }, |(settings, _)| *settings);
+    let bake_start = std::time::Instant::now();
+    let atlas = bake_turntable_atlas(&proc_volume.volume, TURNTABLE_FRAMES, TURNTABLE_FRAME_SIZE, &settings);
+    info!(
+        "Baked a {}-frame turntable in {:.1} ms",
+        TURNTABLE_FRAMES,
+        bake_start.elapsed().as_secs_f32() * 1000.0
+    );
+
+    for entity in turntables.iter() {
+        commands.entity(entity).despawn();
+    }
+    let size = TURNTABLE_FRAME_SIZE as f32;
+    let above = (proc_volume.output_size.y as f32 + size) * 0.5;
+    commands.spawn((
+        Sprite {
+            image: images.add(atlas),
+            rect: Some(Rect::new(0.0, 0.0, size, size)),
+            ..default()
+        },
+        Transform::from_translation(transform.translation + Vec3::new(0.0, above, 1.0)),
+        TurntableAnimation {
+            frames: TURNTABLE_FRAMES,
+            frame_size: TURNTABLE_FRAME_SIZE,
+            frames_per_second: TURNTABLE_FPS,
+            elapsed: 0.0,
+        },
+        ProceduralSceneEntity,
+    ));
+}
+
+fn animate_turntable(time: Res<Time>, mut query: Query<(&mut TurntableAnimation, &mut Sprite)>) {
+    for (mut animation, mut sprite) in query.iter_mut() {
+        animation.elapsed += time.delta_secs();
+        let frame = (animation.elapsed * animation.frames_per_second) as u32 % animation.frames.max(1);
+        let size = animation.frame_size as f32;
+        sprite.rect = Some(Rect::new(frame as f32 * size, 0.0, (frame + 1) as f32 * size, size));
+    }
+}
+
+/// Number key toggling each debug overlay; 0 turns overlays off
+const DEBUG_OVERLAY_KEYS: [(KeyCode, DebugMode); 5] = [
+    (KeyCode::Digit1, DebugMode::PositionMap),
+    (KeyCode::Digit2, DebugMode::NormalMap),
+    (KeyCode::Digit3, DebugMode::LightDistance),
+    (KeyCode::Digit4, DebugMode::GroundLevel),
+    (KeyCode::Digit5, DebugMode::WorldPositions),
+];
+
 fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
 /// Component to store the procedural volume and rotation state
+/// Present in both render modes; the CPU systems skip rocks that also have a
+/// `GpuVolumeRenderer`, whose rotation is then the one that counts.
 #[derive(Component)]
 struct ProceduralVolume {
     pub volume: Volume,
@@ -104,14 +281,197 @@ struct ProceduralVolume {
     pub params: RockGenerationParams,
     pub needs_update: bool,
     pub update_timer: f32, // Debounce timer to prevent constant updates
+    pub output_size: UVec2, // Width/height of the rendered maps in pixels
+    pub dither: DitherMode, // Dithering of the 8-bit position/diffuse maps
+    pub quality: RenderQuality, // Step size, filtering and supersampling preset
+    pub self_shadow: SelfShadow, // Light direction is refreshed from the light on each render
+    pub normal_alpha: ChannelSource, // Normal map alpha: constant, or height for tools that pack it there
+    pub normal_samples: Vec<(Vec2, Vec3)>, // Rendered normals on a coarse grid, for the F3 overlay
+    pub stats: VolumeStats, // Density statistics at the render threshold, for the status panel
+    pub last_render: Option<(RenderSettings, VolumeRenderResult)>, // Latest full render, patched in place by sculpting
 }
 
+/// Pixels between the normal samples drawn by the F3 overlay
+const NORMAL_GIZMO_SPACING: u32 = 8;
+/// Length of a normal arrow in world units
+const NORMAL_GIZMO_LENGTH: f32 = 6.0;
+
+/// Ground-plane cell size in position map units; scaled by `position_scale` on screen
+const GROUND_GRID_CELL: f32 = 32.0;
+/// Cells along each side of the ground grid
+const GROUND_GRID_CELLS: UVec2 = UVec2::splat(40);
+
+/// Whether the F4 ground grid is shown
+#[derive(Resource, Default)]
+struct GroundGrid {
+    enabled: bool,
+}
+
+/// F4 toggles a reference grid on the ground plane
+/// Each sprite's ground position gets a cross, and each light a circle at its ground
+/// position with a stalk up to its `virtual_height`, so the numbers behind the lighting
+/// can be read off the grid.
+fn draw_ground_grid(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut grid: ResMut<GroundGrid>,
+    mut gizmos: Gizmos,
+    light_query: Query<(&Transform, &MovableLightMarker)>,
+    sprite_query: Query<&Transform, With<PositionMappedSprite>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        grid.enabled = !grid.enabled;
+        info!("Ground grid: {}", if grid.enabled { "on" } else { "off" });
+    }
+    if !grid.enabled {
+        return;
+    }
+
+    // Scene-wide position scale comes from the first light, as in `update_material_light_info`
+    let position_scale = light_query
+        .iter()
+        .next()
+        .map_or(1.0, |(_, light)| light.position_scale);
+    gizmos.grid_2d(
+        Isometry2d::IDENTITY,
+        GROUND_GRID_CELLS,
+        Vec2::splat(GROUND_GRID_CELL * position_scale.max(0.01)),
+        Color::srgba(1.0, 1.0, 1.0, 0.15),
+    );
+
+    for transform in sprite_query.iter() {
+        let ground = transform.translation.truncate();
+        gizmos.cross_2d(Isometry2d::from_translation(ground), 12.0, css::YELLOW);
+    }
+
+    for (transform, light) in light_query.iter() {
+        let ground = transform.translation.truncate();
+        let top = ground + Vec2::Y * light.virtual_height;
+        gizmos.circle_2d(Isometry2d::from_translation(ground), 10.0, light.color);
+        gizmos.line_2d(ground, top, light.color);
+        gizmos.circle_2d(Isometry2d::from_translation(top), 4.0, light.color);
+    }
+}
+
+/// Whether the F3 normal arrow overlay is shown
+#[derive(Resource, Default)]
+struct NormalGizmos {
+    enabled: bool,
+}
+
+/// Hit pixels of a CPU render on a coarse grid, as (offset from the sprite centre, normal)
+fn sample_normal_grid(result: &VolumeRenderResult) -> Vec<(Vec2, Vec3)> {
+    let half_size = Vec2::new(result.width as f32, result.height as f32) * 0.5;
+    let spacing = NORMAL_GIZMO_SPACING as usize;
+    let mut samples = Vec::new();
+    for py in (spacing as u32 / 2..result.height).step_by(spacing) {
+        for px in (spacing as u32 / 2..result.width).step_by(spacing) {
+            let Some(normal) = result.normal_at(px, py, &PROCEDURAL_NORMAL_OPTIONS.channel_layout) else {
+                continue;
+            };
+            // Map rows run down the screen while world Y runs up it
+            let offset = Vec2::new(px as f32 + 0.5 - half_size.x, half_size.y - py as f32 - 0.5);
+            samples.push((offset, normal));
+        }
+    }
+    samples
+}
+
+/// F3 toggles arrows showing the rendered normals of each CPU procedural rock
+/// Arrows show the normal's on-screen direction, colored like the normal map itself.
+fn draw_normal_gizmos(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<NormalGizmos>,
+    mut gizmos: Gizmos,
+    volume_query: Query<(&Transform, &ProceduralVolume), Without<GpuVolumeRenderer>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        overlay.enabled = !overlay.enabled;
+        info!("Normal gizmos: {}", if overlay.enabled { "on" } else { "off" });
+    }
+    if !overlay.enabled {
+        return;
+    }
+
+    for (transform, proc_volume) in volume_query.iter() {
+        let center = transform.translation.truncate();
+        for &(offset, normal) in &proc_volume.normal_samples {
+            let start = center + offset;
+            let end = start + Vec2::new(normal.x, -normal.y) * NORMAL_GIZMO_LENGTH;
+            let encoded = normal * 0.5 + 0.5;
+            gizmos.arrow_2d(start, end, Color::srgb(encoded.x, encoded.y, encoded.z));
+        }
+    }
+}
+
+/// Self-shadow strength the Z key toggles on
+const PROCEDURAL_SELF_SHADOW_STRENGTH: f32 = 0.7;
+
+/// Sprite showing a single Z slice of the procedural volume's density
+#[derive(Component)]
+struct VolumeSliceInspector {
+    z: u32,
+}
+
+/// F2 toggles the volume slice inspector, PageUp/PageDown scrub through Z
+fn inspect_volume_slice(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut images: ResMut<Assets<Image>>,
+    volume_query: Query<&ProceduralVolume, With<SelectedVolume>>,
+    mut inspector_query: Query<(Entity, &mut VolumeSliceInspector, &mut Sprite)>,
+) {
+    let Ok(proc_volume) = volume_query.single() else {
+        return;
+    };
+    let depth = proc_volume.volume.dimensions.z;
+
+    if keyboard_input.just_pressed(KeyCode::F2) {
+        if let Ok((entity, _, _)) = inspector_query.single() {
+            commands.entity(entity).despawn();
+        } else {
+            let z = depth / 2;
+            commands.spawn((
+                Sprite {
+                    image: images.add(volume_slice_to_image(&proc_volume.volume, z)),
+                    custom_size: Some(Vec2::splat(256.0)),
+                    ..default()
+                },
+                Transform::from_xyz(-320.0, 0.0, 1.0),
+                VolumeSliceInspector { z },
+                ProceduralSceneEntity,
+            ));
+            info!("Volume slice inspector: z = {}", z);
+        }
+        return;
+    }
+
+    let Ok((_, mut inspector, mut sprite)) = inspector_query.single_mut() else {
+        return;
+    };
+
+    let mut z = inspector.z;
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        z = (z + 1).min(depth.saturating_sub(1));
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        z = z.saturating_sub(1);
+    }
+
+    if z != inspector.z {
+        inspector.z = z;
+        sprite.image = images.add(volume_slice_to_image(&proc_volume.volume, z));
+        info!("Volume slice inspector: z = {}", z);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn setup_initial_scene(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     meshes: ResMut<Assets<Mesh>>,
     custom_materials: ResMut<Assets<PositionMappedMaterial>>,
     images: ResMut<Assets<Image>>,
+    mut volume_cache: ResMut<VolumeCache>,
     current_scene: Res<CurrentScene>,
     render_mode: Res<VolumeRenderMode>,
 ) {
@@ -121,10 +481,10 @@ fn setup_initial_scene(
     // Setup the initial scene based on current scene resource
     match *current_scene {
         CurrentScene::TextureMapped => {
-            setup_texture_mapped_scene(commands, asset_server, meshes, custom_materials);
+            setup_texture_mapped_scene(commands, asset_server, meshes, custom_materials, images);
         }
         CurrentScene::Procedural => {
-            setup_procedural_scene(commands, asset_server, meshes, custom_materials, images, *render_mode);
+            setup_procedural_scene(commands, asset_server, meshes, custom_materials, images, &mut volume_cache, *render_mode, PROCEDURAL_OUTPUT_SIZE);
         }
     }
 }
@@ -134,61 +494,41 @@ fn setup_texture_mapped_scene(
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut custom_materials: ResMut<Assets<PositionMappedMaterial>>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    let diffuse_handle: Handle<Image> = asset_server.load("tree_diffuse_color.png");
-    let position_handle: Handle<Image> = asset_server.load("tree_position2.png");
-    let normal_handle: Handle<Image> = asset_server.load("tree_normal.png");
-
     // Define initial light properties
     let initial_light_props = MovableLightMarker {
         color: css::WHITE.into(),
         intensity: 1.0,
-        ambient_color: css::DARK_SLATE_GRAY.into(),
+        sky_ambient_color: css::DARK_SLATE_GRAY.into(),
+        ground_ambient_color: Color::srgb(0.12, 0.1, 0.08),
         ambient_intensity: 0.2,
         radius: 300.0,
         falloff: 1.5,
         // This scale converts Blender units to Bevy world units
         // Adjust based on your Blender scene scale (typically 0.01 to 1.0)
         position_scale: 1.0,
-        debug_mode: 0, // 0=normal, 1=show position map, 2=show normals, 3=show distance, 4=show ground level, 5=show 3D positions
+        debug_mode: DebugMode::Off,
         virtual_height: 0.0, // Start at ground level (virtual Z = 0)
+        shading_mode: ShadingMode::Smooth,
+        lighting_source: LightingSource::Both,
     };
     // Light starts at same XY as sprite (center), at ground level (virtual height = 0)
     let initial_light_pos_xy = Vec2::new(0.0, 100.0);
 
-    // Create the material instance
-    let tree_material = custom_materials.add(PositionMappedMaterial {
-        diffuse_texture: diffuse_handle,
-        position_texture: position_handle,
-        normal_texture: normal_handle,
-        uniform_data: LightUniformData {
-            light_pos_world_3d: Vec3::new(
-                initial_light_pos_xy.x,
-                initial_light_pos_xy.y,
-                initial_light_props.virtual_height,
-            ),
-            sprite_world_pos: Vec2::new(0.0, 100.0), // Sprite is at this XY position
-            light_color: LinearRgba::from(initial_light_props.color)
-                * initial_light_props.intensity,
-            ambient_light_color: LinearRgba::from(initial_light_props.ambient_color)
-                * initial_light_props.ambient_intensity,
-            light_radius: initial_light_props.radius,
-            light_falloff: initial_light_props.falloff,
-            position_scale: initial_light_props.position_scale,
-            debug_mode: initial_light_props.debug_mode,
-        },
-    });
-
-    let sprite_width = 1024.0;
-    let sprite_height = 1024.0;
-
-    commands.spawn((
-        Mesh2d(meshes.add(Rectangle::new(sprite_width, sprite_height))),
-        MeshMaterial2d(tree_material),
-        Transform::from_xyz(0.0, 100.0, 0.0),
-        PositionMappedSprite,
-        TextureMappedSceneEntity,
-    ));
+    let tree = load_mapped_sprite(
+        &mut commands,
+        &asset_server,
+        &mut meshes,
+        &mut custom_materials,
+        &mut images,
+        "tree_diffuse_color.png",
+        Some("tree_position2.png"),
+        Some("tree_normal.png"),
+    );
+    commands
+        .entity(tree)
+        .insert((Transform::from_xyz(0.0, 100.0, 0.0), TextureMappedSceneEntity));
 
     // Spawn a visible marker for the light source
     commands.spawn((
@@ -206,36 +546,288 @@ fn setup_texture_mapped_scene(
     spawn_texture_mapped_ui(&mut commands);
 }
 
+/// Flat normal facing the viewer, for map sets without a normal map
+const FLAT_NORMAL_RGBA: [u8; 4] = [128, 128, 255, 255];
+/// Every pixel on the ground at the sprite's position, for map sets without a position map
+const FLAT_POSITION_RGBA: [u8; 4] = [128, 128, 0, 255];
+
+/// Keeps a mapped sprite's mesh the size of its diffuse map, in pixels times `scale`
+/// The mesh follows the image whenever its size changes: once it loads, when it's hot
+/// reloaded, or when a procedural render switches resolution.
+#[derive(Component)]
+struct FitToDiffuse {
+    scale: f32,
+    /// Diffuse size the mesh was last built for
+    size: Option<UVec2>,
+}
+
+impl FitToDiffuse {
+    fn new(scale: f32) -> Self {
+        Self { scale, size: None }
+    }
+}
+
+/// 1x1 map filled with a single value
+fn flat_map(images: &mut Assets<Image>, rgba: [u8; 4]) -> Handle<Image> {
+    images.add(Image::new_fill(
+        bevy::render::render_resource::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        bevy::render::render_resource::TextureDimension::D2,
+        &rgba,
+        bevy::render::render_resource::TextureFormat::Rgba8Unorm,
+        bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
+    ))
+}
+
+/// Spawn a lit sprite from an externally authored diffuse/position/normal map set
+/// Paths are asset paths. A position or normal map that is `None` (or fails to load) is
+/// replaced by a flat one, so partial sets still render. The sprite is sized to the diffuse
+/// image by `FitToDiffuse`; the caller adds its `Transform` and scene marker.
+#[allow(clippy::too_many_arguments)]
+fn load_mapped_sprite(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    meshes: &mut Assets<Mesh>,
+    custom_materials: &mut Assets<PositionMappedMaterial>,
+    images: &mut Assets<Image>,
+    diffuse: &str,
+    position: Option<&str>,
+    normal: Option<&str>,
+) -> Entity {
+    let position_texture = match position {
+        Some(path) => asset_server.load(path),
+        None => flat_map(images, FLAT_POSITION_RGBA),
+    };
+    let normal_texture = match normal {
+        Some(path) => asset_server.load(path),
+        None => flat_map(images, FLAT_NORMAL_RGBA),
+    };
+
+    // Light uniforms are filled in every frame by `update_material_light_info`
+    let material = custom_materials.add(PositionMappedMaterial {
+        diffuse_texture: asset_server.load(diffuse),
+        position_texture,
+        normal_texture,
+        uniform_data: LightUniformData::default(),
+    });
+
+    commands
+        .spawn((
+            Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+            MeshMaterial2d(material),
+            PositionMappedSprite,
+            FitToDiffuse::new(1.0),
+        ))
+        .id()
+}
+
+/// Resize `FitToDiffuse` sprites to their diffuse maps, and swap in flat maps for position
+/// and normal maps that failed to load
+fn fit_sprites_to_diffuse(
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut custom_materials: ResMut<Assets<PositionMappedMaterial>>,
+    mut sprite_query: Query<(&Mesh2d, &MeshMaterial2d<PositionMappedMaterial>, &mut FitToDiffuse)>,
+) {
+    for (mesh, material_handle, mut fit) in sprite_query.iter_mut() {
+        let Some(material) = custom_materials.get(material_handle) else {
+            continue;
+        };
+
+        // Only borrow the material mutably when a map has to be replaced, since that
+        // re-uploads it
+        let position_failed = asset_server.load_state(&material.position_texture).is_failed();
+        let normal_failed = asset_server.load_state(&material.normal_texture).is_failed();
+        if position_failed || normal_failed {
+            let Some(material) = custom_materials.get_mut(material_handle) else {
+                continue;
+            };
+            if position_failed {
+                warn!("Position map failed to load, using a flat one");
+                material.position_texture = flat_map(&mut images, FLAT_POSITION_RGBA);
+            }
+            if normal_failed {
+                warn!("Normal map failed to load, using a flat one");
+                material.normal_texture = flat_map(&mut images, FLAT_NORMAL_RGBA);
+            }
+            continue;
+        }
+
+        let Some(size) = images.get(&material.diffuse_texture).map(|image| image.size()) else {
+            continue;
+        };
+        if fit.size != Some(size) {
+            fit.size = Some(size);
+            meshes.insert(&mesh.0, Rectangle::from_size(size.as_vec2() * fit.scale).into());
+        }
+    }
+}
+
+/// Where each procedural rock sits and how its volume is seeded
+struct ProceduralRock {
+    position: Vec2,
+    seed: u32,
+    scale: f32,
+    noise: NoiseKind,
+    /// Volume material id threaded through the rock as veins
+    vein_material: u8,
+}
+
+/// Rocks spawned by the procedural scene; the first starts selected
+const PROCEDURAL_ROCKS: [ProceduralRock; 3] = [
+    ProceduralRock {
+        position: Vec2::new(0.0, 0.0),
+        seed: 42,
+        scale: 3.0,
+        noise: NoiseKind::Fbm,
+        vein_material: VOLUME_MATERIAL_ORE,
+    },
+    ProceduralRock {
+        position: Vec2::new(-320.0, -140.0),
+        seed: 7,
+        scale: 2.5,
+        noise: NoiseKind::Fbm,
+        vein_material: VOLUME_MATERIAL_CRYSTAL,
+    },
+    ProceduralRock {
+        position: Vec2::new(320.0, -120.0),
+        seed: 1234,
+        scale: 3.5,
+        noise: NoiseKind::RidgedMulti,
+        vein_material: VOLUME_MATERIAL_ORE,
+    },
+];
+
+#[allow(clippy::too_many_arguments)]
 fn setup_procedural_scene(
     mut commands: Commands,
     _asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut custom_materials: ResMut<Assets<PositionMappedMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    volume_cache: &mut VolumeCache,
     render_mode: VolumeRenderMode,
+    output_size: UVec2,
 ) {
+    // Setup lighting
+    let initial_light_props = MovableLightMarker {
+        color: css::AQUA.into(),
+        intensity: 1.0,
+        sky_ambient_color: Color::srgb(0.1, 0.1, 0.15),
+        ground_ambient_color: Color::srgb(0.06, 0.05, 0.04),
+        ambient_intensity: 0.3,
+        radius: 400.0,
+        falloff: 2.0,
+        position_scale: 1.0,
+        debug_mode: DebugMode::Off,
+        virtual_height: 50.0,
+        shading_mode: ShadingMode::Smooth,
+        lighting_source: LightingSource::Both,
+    };
+
+    let initial_light_pos_xy = Vec2::new(0.0, 0.0);
+
+    // Every rock shares the one light; per-sprite culling picks it up in `update_material_light_info`
+    let first_build = volume_cache.is_empty();
+    for (index, rock) in PROCEDURAL_ROCKS.iter().enumerate() {
+        let entity = spawn_procedural_rock(
+            &mut commands,
+            &mut meshes,
+            &mut custom_materials,
+            &mut images,
+            volume_cache,
+            render_mode,
+            output_size,
+            rock,
+            &initial_light_props,
+            initial_light_pos_xy,
+        );
+        if index == 0 {
+            commands.entity(entity).insert(SelectedVolume);
+        }
+    }
+    if first_build {
+        info!("Generated {} rock volumes", volume_cache.len());
+    } else {
+        info!("Reused {} cached rock volumes", volume_cache.len());
+    }
+
+    // Spawn a visible marker for the light source
+    commands.spawn((
+        initial_light_props,
+        Sprite {
+            color: css::ORANGE.into(),
+            custom_size: Some(Vec2::splat(16.0)),
+            ..default()
+        },
+        Transform::from_xyz(initial_light_pos_xy.x, initial_light_pos_xy.y, 10.0),
+        ProceduralSceneEntity,
+    ));
+
+    // Spawn UI for this scene
+    spawn_procedural_ui(&mut commands, render_mode.as_str());
+}
+
+/// Generate, render and spawn one procedural rock sprite with its ground shadow
+#[allow(clippy::too_many_arguments)]
+fn spawn_procedural_rock(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    custom_materials: &mut Assets<PositionMappedMaterial>,
+    images: &mut Assets<Image>,
+    volume_cache: &mut VolumeCache,
+    render_mode: VolumeRenderMode,
+    output_size: UVec2,
+    rock: &ProceduralRock,
+    light_props: &MovableLightMarker,
+    light_pos_xy: Vec2,
+) -> Entity {
     // Generate the rock volume
     let params = RockGenerationParams {
         size: 64,
-        scale: 3.0,
+        scale: rock.scale,
         octaves: 4,
         lacunarity: 2.0,
         persistence: 0.5,
         threshold: 0.0,
-        seed: 42,
+        seed: rock.seed,
+        detail_scale: 14.0,
+        detail_amplitude: 0.08,
+        noise: rock.noise,
     };
     
-    let volume = generate_rock_volume(&params);
+    // Rebuilding the scene asks for the same rocks again; only the first build runs the noise
+    let mut volume = volume_cache
+        .get_or_generate(&params)
+        .expect("procedural rock params are valid")
+        .clone();
+    add_mineral_veins(&mut volume, &VeinParams {
+        seed: VeinParams::default().seed.wrapping_add(rock.seed),
+        material: rock.vein_material,
+        ..default()
+    });
     
     // Initial rotation
     let initial_rotation = Vec3::ZERO;
     
     // Create textures based on render mode
+    let mut normal_samples = Vec::new();
+    let mut last_render = None;
     let (position_handle, normal_handle, diffuse_handle, volume_texture_handle) = match render_mode {
         VolumeRenderMode::Cpu => {
             // CPU path: Render volume to 2D maps using software raymarching
-            let output_size = 256;
-            let render_result = render_volume_to_maps(&volume, output_size, initial_rotation);
+            let settings = RenderSettings {
+                output_size,
+                rotation: initial_rotation,
+                normal: PROCEDURAL_NORMAL_OPTIONS,
+                ..default()
+            };
+            let render_result = render_volume_with_settings(&volume, &settings);
+            normal_samples = sample_normal_grid(&render_result);
             
             // Create Bevy Image assets from the generated data
             let position_image = Image::new(
@@ -245,7 +837,7 @@ fn setup_procedural_scene(
                     depth_or_array_layers: 1,
                 },
                 bevy::render::render_resource::TextureDimension::D2,
-                render_result.position_map,
+                render_result.position_map.clone(),
                 bevy::render::render_resource::TextureFormat::Rgba8Unorm,
                 bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
             );
@@ -257,8 +849,8 @@ fn setup_procedural_scene(
                     depth_or_array_layers: 1,
                 },
                 bevy::render::render_resource::TextureDimension::D2,
-                render_result.normal_map,
-                bevy::render::render_resource::TextureFormat::Rgba8Unorm,
+                render_result.normal_map.clone(),
+                render_result.normal_format.texture_format(),
                 bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
             );
             
@@ -269,7 +861,7 @@ fn setup_procedural_scene(
                     depth_or_array_layers: 1,
                 },
                 bevy::render::render_resource::TextureDimension::D2,
-                render_result.diffuse_map,
+                render_result.diffuse_map.clone(),
                 bevy::render::render_resource::TextureFormat::Rgba8Unorm,
                 bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
             );
@@ -277,41 +869,22 @@ fn setup_procedural_scene(
             let position_handle = images.add(position_image);
             let normal_handle = images.add(normal_image);
             let diffuse_handle = images.add(diffuse_image);
+            last_render = Some((settings, render_result));
             
             (position_handle, normal_handle, diffuse_handle, None)
         }
         VolumeRenderMode::Gpu => {
             // GPU path: Create empty output textures and upload volume to GPU
-            let output_size = 256;
-            
             // Create volume texture for GPU
-            let volume_handle = create_volume_texture(&volume, &mut images);
+            let volume_handle = create_volume_texture(&volume, images);
             
             // Create output textures
-            let (pos_handle, norm_handle, diff_handle) = create_output_textures(output_size, &mut images);
+            let (pos_handle, norm_handle, diff_handle) = create_output_textures(output_size, images);
             
             (pos_handle, norm_handle, diff_handle, Some(volume_handle))
         }
     };
     
-    let output_size = 256; // Fixed size for sprite display
-    
-    // Setup lighting
-    let initial_light_props = MovableLightMarker {
-        color: css::AQUA.into(),
-        intensity: 1.0,
-        ambient_color: Color::srgb(0.1, 0.1, 0.15),
-        ambient_intensity: 0.3,
-        radius: 400.0,
-        falloff: 2.0,
-        position_scale: 1.0,
-        debug_mode: 0,
-        virtual_height: 50.0,
-    };
-
-    let initial_light_pos_xy = Vec2::new(0.0, 0.0);
-    let sprite_pos = Vec2::new(0.0, 0.0);
-
     // Create the material with procedurally generated textures
     let rock_material = custom_materials.add(PositionMappedMaterial {
         diffuse_texture: diffuse_handle.clone(),
@@ -319,92 +892,112 @@ fn setup_procedural_scene(
         normal_texture: normal_handle.clone(),
         uniform_data: LightUniformData {
             light_pos_world_3d: Vec3::new(
-                initial_light_pos_xy.x,
-                initial_light_pos_xy.y,
-                initial_light_props.virtual_height,
+                light_pos_xy.x,
+                light_pos_xy.y,
+                light_props.virtual_height,
             ),
-            sprite_world_pos: sprite_pos,
-            light_color: LinearRgba::from(initial_light_props.color)
-                * initial_light_props.intensity,
-            ambient_light_color: LinearRgba::from(initial_light_props.ambient_color)
-                * initial_light_props.ambient_intensity,
-            light_radius: initial_light_props.radius,
-            light_falloff: initial_light_props.falloff,
-            position_scale: initial_light_props.position_scale,
-            debug_mode: initial_light_props.debug_mode,
+            sprite_world_pos: rock.position,
+            light_color: LinearRgba::from(light_props.color)
+                * light_props.intensity,
+            sky_ambient_color: LinearRgba::from(light_props.sky_ambient_color)
+                * light_props.ambient_intensity,
+            ground_ambient_color: LinearRgba::from(light_props.ground_ambient_color)
+                * light_props.ambient_intensity,
+            light_radius: light_props.radius,
+            light_falloff: light_props.falloff,
+            position_scale: light_props.position_scale,
+            debug_mode: light_props.debug_mode.as_uniform(),
+            shading_bands: light_props.shading_mode.bands(),
+            lighting_source: light_props.lighting_source.as_uniform(),
         },
     });
 
-    // Spawn the procedural rock sprite with appropriate components based on render mode
-    let sprite_size = output_size as f32;
-    
-    match render_mode {
-        VolumeRenderMode::Cpu => {
-            // CPU mode: Use ProceduralVolume component for manual updates
-            commands.spawn((
-                Mesh2d(meshes.add(Rectangle::new(sprite_size, sprite_size))),
-                MeshMaterial2d(rock_material),
-                Transform::from_xyz(sprite_pos.x, sprite_pos.y, 0.0),
-                PositionMappedSprite,
-                ProceduralSceneEntity,
-                ProceduralVolume {
-                    volume: volume.clone(),
-                    rotation: initial_rotation,
-                    target_rotation: initial_rotation,
-                    params: params.clone(),
-                    needs_update: false,
-                    update_timer: 0.0,
-                },
-            ));
-        }
-        VolumeRenderMode::Gpu => {
-            // GPU mode: Use GpuVolumeRenderer component for automatic GPU rendering
-            commands.spawn((
-                Mesh2d(meshes.add(Rectangle::new(sprite_size, sprite_size))),
-                MeshMaterial2d(rock_material),
-                Transform::from_xyz(sprite_pos.x, sprite_pos.y, 0.0),
-                PositionMappedSprite,
-                ProceduralSceneEntity,
-                GpuVolumeRenderer {
-                    volume_texture: volume_texture_handle.unwrap(),
-                    position_output: position_handle.clone(),
-                    normal_output: normal_handle.clone(),
-                    diffuse_output: diffuse_handle.clone(),
-                    rotation: initial_rotation,
-                    volume_size: params.size as f32,
-                    output_size,
-                },
-            ));
-        }
+    // Spawn the procedural rock sprite; it keeps its volume in either render mode so
+    // `toggle_render_mode` can swap renderers without losing it
+    let sprite_size = output_size.as_vec2();
+
+    let rock_entity = commands.spawn((
+        Mesh2d(meshes.add(Rectangle::from_size(sprite_size))),
+        MeshMaterial2d(rock_material),
+        Transform::from_xyz(rock.position.x, rock.position.y, 0.0),
+        PositionMappedSprite,
+        ProceduralSceneEntity,
+        FitToDiffuse::new(1.0),
+        ProceduralVolume {
+            stats: volume.stats(RenderSettings::default().threshold),
+            volume,
+            rotation: initial_rotation,
+            target_rotation: initial_rotation,
+            params: params.clone(),
+            needs_update: false,
+            update_timer: 0.0,
+            output_size,
+            dither: DitherMode::default(),
+            quality: RenderQuality::default(),
+            self_shadow: SelfShadow::default(),
+            normal_alpha: PROCEDURAL_NORMAL_OPTIONS.channel_layout.a,
+            normal_samples,
+            last_render,
+        },
+    )).id();
+
+    // GPU mode: the compute pass renders into the maps every frame
+    if let Some(volume_texture) = volume_texture_handle {
+        commands.entity(rock_entity).insert(GpuVolumeRenderer {
+            volume_texture,
+            position_output: position_handle.clone(),
+            normal_output: normal_handle.clone(),
+            diffuse_output: diffuse_handle.clone(),
+            rotation: initial_rotation,
+            camera: None,
+            volume_size: params.size as f32,
+            output_size,
+        });
     }
 
-    // Spawn a visible marker for the light source
+    // Ground shadow: the rock's coverage, darkened and pushed away from the light
     commands.spawn((
-        initial_light_props,
         Sprite {
-            color: css::ORANGE.into(),
-            custom_size: Some(Vec2::splat(16.0)),
+            image: diffuse_handle.clone(),
+            color: Color::BLACK.with_alpha(DEFAULT_SHADOW_OPACITY),
+            custom_size: Some(sprite_size * Vec2::new(1.0, GROUND_SHADOW_SQUASH)),
             ..default()
         },
-        Transform::from_xyz(initial_light_pos_xy.x, initial_light_pos_xy.y, 10.0),
+        Transform::from_xyz(rock.position.x, rock.position.y, -0.5),
+        GroundShadow {
+            opacity: DEFAULT_SHADOW_OPACITY,
+            caster: rock_entity,
+        },
         ProceduralSceneEntity,
     ));
 
-    // Spawn UI for this scene
-    spawn_procedural_ui(&mut commands, render_mode.as_str());
+    rock_entity
+}
+
+/// Entities a scene switch despawns: both scenes' and the scene UI
+#[derive(SystemParam)]
+struct SceneEntities<'w, 's> {
+    texture_scene: Query<'w, 's, Entity, With<TextureMappedSceneEntity>>,
+    procedural_scene: Query<'w, 's, Entity, With<ProceduralSceneEntity>>,
+    ui: Query<'w, 's, (Entity, &'static SceneUi)>,
+}
+
+/// Assets the scene setups load and create their sprites from
+#[derive(SystemParam)]
+struct SceneAssets<'w> {
+    asset_server: Res<'w, AssetServer>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    custom_materials: ResMut<'w, Assets<PositionMappedMaterial>>,
+    images: ResMut<'w, Assets<Image>>,
 }
 
 fn handle_scene_switching(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut current_scene: ResMut<CurrentScene>,
     mut commands: Commands,
-    texture_scene_query: Query<Entity, With<TextureMappedSceneEntity>>,
-    procedural_scene_query: Query<Entity, With<ProceduralSceneEntity>>,
-    ui_query: Query<(Entity, &SceneUi)>,
-    asset_server: Res<AssetServer>,
-    meshes: ResMut<Assets<Mesh>>,
-    custom_materials: ResMut<Assets<PositionMappedMaterial>>,
-    images: ResMut<Assets<Image>>,
+    entities: SceneEntities,
+    assets: SceneAssets,
+    mut volume_cache: ResMut<VolumeCache>,
     render_mode: Res<VolumeRenderMode>,
 ) {
     if keyboard_input.just_pressed(KeyCode::F1) {
@@ -413,7 +1006,7 @@ fn handle_scene_switching(
             CurrentScene::TextureMapped => SceneType::TextureMapped,
             CurrentScene::Procedural => SceneType::Procedural,
         };
-        despawn_scene_ui(commands.reborrow(), ui_query, old_scene_type);
+        despawn_scene_ui(commands.reborrow(), entities.ui, old_scene_type);
         
         // Toggle scene
         *current_scene = match *current_scene {
@@ -422,88 +1015,148 @@ fn handle_scene_switching(
         };
 
         // Despawn all entities from both scenes
-        for entity in texture_scene_query.iter() {
+        for entity in entities.texture_scene.iter() {
             commands.entity(entity).despawn();
         }
-        for entity in procedural_scene_query.iter() {
+        for entity in entities.procedural_scene.iter() {
             commands.entity(entity).despawn();
         }
 
         // Setup the new scene (will spawn new UI)
+        let SceneAssets { asset_server, meshes, custom_materials, images } = assets;
         match *current_scene {
             CurrentScene::TextureMapped => {
-                setup_texture_mapped_scene(commands, asset_server, meshes, custom_materials);
+                setup_texture_mapped_scene(commands, asset_server, meshes, custom_materials, images);
             }
             CurrentScene::Procedural => {
-                setup_procedural_scene(commands, asset_server, meshes, custom_materials, images, *render_mode);
+                setup_procedural_scene(commands, asset_server, meshes, custom_materials, images, &mut volume_cache, *render_mode, PROCEDURAL_OUTPUT_SIZE);
             }
         }
     }
 }
 
+/// Ranges the held-key light controls stay inside, so no setting blanks the scene for good
+const LIGHT_INTENSITY_RANGE: RangeInclusive<f32> = 0.0..=20.0;
+const AMBIENT_INTENSITY_RANGE: RangeInclusive<f32> = 0.0..=5.0;
+const LIGHT_RADIUS_RANGE: RangeInclusive<f32> = 10.0..=5000.0;
+const LIGHT_FALLOFF_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+const VIRTUAL_HEIGHT_RANGE: RangeInclusive<f32> = -500.0..=2000.0;
+const POSITION_SCALE_RANGE: RangeInclusive<f32> = 0.01..=10.0;
+
+/// Seconds the status panel stays tinted after a light control runs into its limit
+const LIMIT_FLASH_SECONDS: f32 = 0.4;
+/// Tint the status panel flashes to
+const LIMIT_FLASH_COLOR: Color = Color::srgba(0.6, 0.1, 0.1, 0.9);
+
+/// Time left on the status panel's limit flash
+#[derive(Resource, Default)]
+struct LightLimitFlash {
+    remaining: f32,
+}
+
+/// Nudge a light setting by `delta`, keeping it inside `range`
+/// Returns whether the nudge was cut short by the limit.
+fn nudge_clamped(value: &mut f32, delta: f32, range: RangeInclusive<f32>) -> bool {
+    let target = *value + delta;
+    *value = target.clamp(*range.start(), *range.end());
+    *value != target
+}
+
 fn control_light_properties(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
     mut light_query: Query<(&mut MovableLightMarker, &Transform)>,
+    mut flash: ResMut<LightLimitFlash>,
 ) {
     if let Ok((mut light_props, _light_transform)) = light_query.single_mut() {
         let dt = time.delta_secs();
+        let light_props = &mut *light_props;
 
-        // Control light intensity
-        if keyboard_input.pressed(KeyCode::KeyI) {
-            light_props.intensity += 0.5 * dt;
-        }
-        if keyboard_input.pressed(KeyCode::KeyK) {
-            light_props.intensity = (light_props.intensity - 0.5 * dt).max(0.0);
-        }
-
-        // Control ambient intensity
-        if keyboard_input.pressed(KeyCode::KeyO) {
-            light_props.ambient_intensity += 0.3 * dt;
-        }
-        if keyboard_input.pressed(KeyCode::KeyL) {
-            light_props.ambient_intensity = (light_props.ambient_intensity - 0.3 * dt).max(0.0);
-        }
+        // Held keys nudge a setting per frame; the last one to hit a limit is reported
+        let mut limited = None;
+        let mut nudge = |keys: (KeyCode, KeyCode), value: &mut f32, rate: f32, range, name: &'static str| {
+            let mut delta = 0.0;
+            if keyboard_input.pressed(keys.0) {
+                delta += rate * dt;
+            }
+            if keyboard_input.pressed(keys.1) {
+                delta -= rate * dt;
+            }
+            if delta != 0.0 && nudge_clamped(value, delta, range) {
+                limited = Some(name);
+            }
+        };
+        nudge((KeyCode::KeyI, KeyCode::KeyK), &mut light_props.intensity, 0.5, LIGHT_INTENSITY_RANGE, "intensity");
+        nudge((KeyCode::KeyO, KeyCode::KeyL), &mut light_props.ambient_intensity, 0.3, AMBIENT_INTENSITY_RANGE, "ambient intensity");
+        nudge((KeyCode::BracketRight, KeyCode::BracketLeft), &mut light_props.radius, 100.0, LIGHT_RADIUS_RANGE, "radius");
+        nudge((KeyCode::Equal, KeyCode::Minus), &mut light_props.falloff, 0.5, LIGHT_FALLOFF_RANGE, "falloff");
+        // U/J move the light up/down in the game world
+        nudge((KeyCode::KeyU, KeyCode::KeyJ), &mut light_props.virtual_height, 50.0, VIRTUAL_HEIGHT_RANGE, "virtual height");
+        nudge((KeyCode::KeyP, KeyCode::Semicolon), &mut light_props.position_scale, 0.1, POSITION_SCALE_RANGE, "position scale");
 
-        // Control light radius
-        if keyboard_input.pressed(KeyCode::BracketRight) {
-            light_props.radius += 100.0 * dt;
+        // Log once per flash rather than every frame the key is held
+        if let Some(name) = limited.filter(|_| flash.remaining <= 0.0) {
+            info!("Light {} is at its limit", name);
         }
-        if keyboard_input.pressed(KeyCode::BracketLeft) {
-            light_props.radius = (light_props.radius - 100.0 * dt).max(10.0);
+        if limited.is_some() {
+            flash.remaining = LIMIT_FLASH_SECONDS;
         }
 
-        // Control light falloff
-        if keyboard_input.pressed(KeyCode::Equal) {
-            light_props.falloff += 0.5 * dt;
+        // Toggle toon shading and adjust its band count
+        if keyboard_input.just_pressed(KeyCode::KeyG) {
+            light_props.shading_mode = match light_props.shading_mode {
+                ShadingMode::Smooth => ShadingMode::Toon { bands: 3 },
+                ShadingMode::Toon { .. } => ShadingMode::Smooth,
+            };
         }
-        if keyboard_input.pressed(KeyCode::Minus) {
-            light_props.falloff = (light_props.falloff - 0.5 * dt).max(0.1);
+        if let ShadingMode::Toon { bands } = &mut light_props.shading_mode {
+            if keyboard_input.just_pressed(KeyCode::Period) {
+                *bands = (*bands + 1).min(8);
+            }
+            if keyboard_input.just_pressed(KeyCode::Comma) {
+                *bands = bands.saturating_sub(1).max(2);
+            }
         }
 
-        // Control virtual height (U/J keys for up/down in game world)
-        if keyboard_input.pressed(KeyCode::KeyU) {
-            light_props.virtual_height += 50.0 * dt;
-        }
-        if keyboard_input.pressed(KeyCode::KeyJ) {
-            light_props.virtual_height -= 50.0 * dt;
+        // Cycle which maps drive the lighting
+        if keyboard_input.just_pressed(KeyCode::KeyN) {
+            light_props.lighting_source = light_props.lighting_source.next();
         }
 
-        // Control position scale
-        if keyboard_input.pressed(KeyCode::KeyP) {
-            light_props.position_scale += 0.1 * dt;
+        // Toggle individual debug overlays with number keys (pressing the active one turns it off)
+        for &(key, mode) in DEBUG_OVERLAY_KEYS.iter() {
+            if keyboard_input.just_pressed(key) {
+                light_props.debug_mode = if light_props.debug_mode == mode { DebugMode::Off } else { mode };
+            }
         }
-        if keyboard_input.pressed(KeyCode::Semicolon) {
-            light_props.position_scale = (light_props.position_scale - 0.1 * dt).max(0.01);
+        if keyboard_input.just_pressed(KeyCode::Digit0) {
+            light_props.debug_mode = DebugMode::Off;
         }
 
         // Cycle debug modes
         if keyboard_input.just_pressed(KeyCode::KeyV) {
-            light_props.debug_mode = (light_props.debug_mode + 1) % 6;
+            light_props.debug_mode = light_props.debug_mode.next();
         }
     }
 }
 
+/// Tint the status panel while a limit flash runs, fading back to its usual background
+fn flash_status_panel(
+    time: Res<Time>,
+    mut flash: ResMut<LightLimitFlash>,
+    mut panel_query: Query<&mut BackgroundColor, With<StatusPanel>>,
+) {
+    if flash.remaining <= 0.0 {
+        return;
+    }
+    flash.remaining = (flash.remaining - time.delta_secs()).max(0.0);
+
+    let strength = flash.remaining / LIMIT_FLASH_SECONDS;
+    for mut background in panel_query.iter_mut() {
+        background.0 = STATUS_PANEL_BACKGROUND.mix(&LIMIT_FLASH_COLOR, strength);
+    }
+}
+
 fn handle_input(
     time: Res<Time>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -528,12 +1181,159 @@ fn handle_input(
     }
 }
 
+/// C starts or stops a light sweep around the sprite
+fn toggle_light_path(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    light_query: Query<(Entity, Has<LightPath>), With<MovableLightMarker>>,
+    sprite_query: Query<&Transform, With<PositionMappedSprite>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    let Ok((light, has_path)) = light_query.single() else {
+        return;
+    };
+
+    if has_path {
+        commands.entity(light).remove::<LightPath>();
+        return;
+    }
+
+    // Circle the sprites, bobbing between low grazing and high overhead light
+    let sprite_count = sprite_query.iter().len().max(1) as f32;
+    let center = sprite_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .sum::<Vec2>()
+        / sprite_count;
+    let waypoints = (0..6)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / 6.0;
+            let height = if i % 2 == 0 { 20.0 } else { 120.0 };
+            (center + Vec2::from_angle(angle) * 200.0).extend(height)
+        })
+        .collect();
+    // Shift+C sweeps back and forth instead of looping
+    let mode = if keyboard_input.pressed(KeyCode::ShiftLeft) {
+        LightPathMode::PingPong
+    } else {
+        LightPathMode::Loop
+    };
+    commands.entity(light).insert(LightPath::new(waypoints, 8.0, mode));
+}
+
+/// Opacity the procedural scene's ground shadow starts with
+const DEFAULT_SHADOW_OPACITY: f32 = 0.5;
+/// Vertical squash of the shadow so it reads as lying on the ground
+const GROUND_SHADOW_SQUASH: f32 = 0.4;
+
+/// Projected shadow drawn behind a procedural sprite, using its diffuse alpha as coverage
+#[derive(Component)]
+struct GroundShadow {
+    /// 0 hides the shadow
+    opacity: f32,
+    /// Sprite casting this shadow
+    caster: Entity,
+}
+
+/// Ground shadow sprites, kept apart from the casters' and the light's transforms
+type GroundShadowQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut GroundShadow, &'static mut Sprite, &'static mut Transform, &'static mut Visibility),
+    (Without<PositionMappedSprite>, Without<MovableLightMarker>),
+>;
+
+/// Project the shadow away from the light; H steps its opacity (0 turns it off)
+fn update_ground_shadow(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    images: Res<Assets<Image>>,
+    light_query: Query<(&Transform, &MovableLightMarker)>,
+    caster_query: Query<&Transform, (With<PositionMappedSprite>, With<ProceduralSceneEntity>)>,
+    mut shadow_query: GroundShadowQuery,
+) {
+    let step_opacity = keyboard_input.just_pressed(KeyCode::KeyH);
+    let mut stepped_opacity = None;
+    for (mut shadow, mut sprite, mut transform, mut visibility) in shadow_query.iter_mut() {
+        if step_opacity {
+            shadow.opacity = if shadow.opacity >= 0.75 { 0.0 } else { shadow.opacity + 0.25 };
+            stepped_opacity = Some(shadow.opacity);
+        }
+        *visibility = if shadow.opacity > 0.0 { Visibility::Inherited } else { Visibility::Hidden };
+        sprite.color = Color::BLACK.with_alpha(shadow.opacity);
+
+        let (Ok((light_transform, light)), Ok(caster)) = (light_query.single(), caster_query.get(shadow.caster)) else {
+            continue;
+        };
+        let Some(size) = images.get(&sprite.image).map(|image| image.size().as_vec2()) else {
+            continue;
+        };
+
+        place_ground_shadow(&mut transform, &mut sprite, caster, light_transform, light, size);
+    }
+
+    if let Some(opacity) = stepped_opacity {
+        info!("Ground shadow opacity: {:.2}", opacity);
+    }
+}
+
+/// Position a shadow sprite for its caster and the light
+fn place_ground_shadow(
+    transform: &mut Transform,
+    sprite: &mut Sprite,
+    caster: &Transform,
+    light_transform: &Transform,
+    light: &MovableLightMarker,
+    size: Vec2,
+) {
+    // Treat the sprite as a caster of about a quarter of its height: a point at height h lit
+    // from height H at ground distance d lands h * d / (H - h) further from the light
+    let caster_pos = caster.translation.truncate();
+    let to_caster = caster_pos - light_transform.translation.truncate();
+    let caster_height = size.y * 0.25;
+    let light_height = (light.virtual_height - caster_height).max(1.0);
+    let displacement = (caster_height * to_caster.length() / light_height).min(size.y);
+
+    // Sit the shadow at the base of the sprite, then push it away from the light
+    let base = caster_pos - Vec2::new(0.0, size.y * 0.5 * (1.0 - GROUND_SHADOW_SQUASH));
+    let offset = to_caster.normalize_or_zero() * displacement;
+    transform.translation = (base + offset).extend(caster.translation.z - 0.5);
+    sprite.custom_size = Some(size * Vec2::new(1.0, GROUND_SHADOW_SQUASH));
+}
+
+/// Move the selection to the next procedural rock, left to right, with Tab
+fn cycle_selected_volume(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    rock_query: Query<(Entity, &Transform, Has<SelectedVolume>), With<PositionMappedSprite>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let mut rocks: Vec<_> = rock_query.iter().collect();
+    if rocks.is_empty() {
+        return;
+    }
+    rocks.sort_by(|a, b| a.1.translation.x.total_cmp(&b.1.translation.x));
+
+    let current = rocks.iter().position(|(_, _, selected)| *selected);
+    let next = current.map_or(0, |index| (index + 1) % rocks.len());
+    if let Some(index) = current {
+        commands.entity(rocks[index].0).remove::<SelectedVolume>();
+    }
+    commands.entity(rocks[next].0).insert(SelectedVolume);
+
+    info!("Selected rock {}/{}", next + 1, rocks.len());
+}
+
 /// System to control procedural volume rotation with keyboard
 fn control_volume_rotation(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
-    mut cpu_volume_query: Query<&mut ProceduralVolume>,
-    mut gpu_volume_query: Query<&mut GpuVolumeRenderer>,
+    mut cpu_volume_query: Query<&mut ProceduralVolume, (With<SelectedVolume>, Without<GpuVolumeRenderer>)>,
+    mut gpu_volume_query: Query<&mut GpuVolumeRenderer, With<SelectedVolume>>,
 ) {
     let dt = time.delta_secs();
     let rotation_speed = 0.5; // radians per second
@@ -566,6 +1366,31 @@ fn control_volume_rotation(
     
     // Apply to CPU volume if present
     if let Ok(mut proc_volume) = cpu_volume_query.single_mut() {
+        if keyboard_input.just_pressed(KeyCode::KeyB) {
+            proc_volume.dither = proc_volume.dither.next();
+            proc_volume.needs_update = true;
+            info!("Map dithering: {:?}", proc_volume.dither);
+        }
+        if keyboard_input.just_pressed(KeyCode::F5) {
+            proc_volume.quality = proc_volume.quality.next();
+            proc_volume.needs_update = true;
+            info!("Render quality: {:?}", proc_volume.quality);
+        }
+        if keyboard_input.just_pressed(KeyCode::F6) {
+            proc_volume.normal_alpha = match proc_volume.normal_alpha {
+                ChannelSource::Height => PROCEDURAL_NORMAL_OPTIONS.channel_layout.a,
+                _ => ChannelSource::Height,
+            };
+            proc_volume.needs_update = true;
+            info!("Normal map alpha: {:?}", proc_volume.normal_alpha);
+        }
+        if keyboard_input.just_pressed(KeyCode::KeyZ) {
+            let enabled = proc_volume.self_shadow.strength == 0.0;
+            proc_volume.self_shadow.strength = if enabled { PROCEDURAL_SELF_SHADOW_STRENGTH } else { 0.0 };
+            proc_volume.needs_update = true;
+            info!("Volumetric self-shadows: {}", if enabled { "on" } else { "off" });
+        }
+
         let rotation_changed = rotation_delta != Vec3::ZERO || reset;
         
         if reset {
@@ -599,101 +1424,236 @@ fn control_volume_rotation(
 }
 
 /// System to regenerate textures when the volume rotation changes
+/// Self-shadows are baked toward wherever the light is at that moment.
 fn update_procedural_volume(
-    mut volume_query: Query<(&mut ProceduralVolume, &MeshMaterial2d<PositionMappedMaterial>)>,
+    light_query: Query<(&Transform, &MovableLightMarker)>,
+    mut volume_query: Query<(
+        &mut ProceduralVolume,
+        &MeshMaterial2d<PositionMappedMaterial>,
+        &Transform,
+    ), Without<GpuVolumeRenderer>>,
     mut materials: ResMut<Assets<PositionMappedMaterial>>,
     mut images: ResMut<Assets<Image>>,
 ) {
-    for (mut proc_volume, material_handle) in volume_query.iter_mut() {
+    for (mut proc_volume, material_handle, transform) in volume_query.iter_mut() {
         if !proc_volume.needs_update {
             continue;
         }
 
+        // World Y points up the screen while map rows run down it, and the light's virtual
+        // height lifts it toward the viewer (-Z in view space)
+        if let Ok((light_transform, light)) = light_query.single() {
+            let offset = (light_transform.translation - transform.translation).truncate();
+            proc_volume.self_shadow.light_dir = Vec3::new(offset.x, -offset.y, -light.virtual_height);
+        }
+
         // Get the material
         let Some(material) = materials.get_mut(material_handle) else {
             continue;
         };
 
-        // Regenerate the maps with the new rotation (256x256 for faster updates)
-        let output_size = 256;
-        
-        let render_result = render_volume_to_maps(
-            &proc_volume.volume,
+        // Regenerate the maps with the new rotation
+        let output_size = proc_volume.output_size;
+        let settings = proc_volume.quality.apply(RenderSettings {
             output_size,
-            proc_volume.rotation,
-        );
-
-        // Create new images and replace the old ones
-        let position_image = Image::new(
-            bevy::render::render_resource::Extent3d {
-                width: render_result.width,
-                height: render_result.height,
-                depth_or_array_layers: 1,
+            rotation: proc_volume.rotation,
+            normal: NormalMapOptions {
+                channel_layout: ChannelLayout { a: proc_volume.normal_alpha, ..PROCEDURAL_NORMAL_OPTIONS.channel_layout },
+                ..PROCEDURAL_NORMAL_OPTIONS
             },
-            bevy::render::render_resource::TextureDimension::D2,
-            render_result.position_map,
-            bevy::render::render_resource::TextureFormat::Rgba8Unorm,
-            bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
-        );
-        
-        let normal_image = Image::new(
-            bevy::render::render_resource::Extent3d {
-                width: render_result.width,
-                height: render_result.height,
-                depth_or_array_layers: 1,
-            },
-            bevy::render::render_resource::TextureDimension::D2,
-            render_result.normal_map,
-            bevy::render::render_resource::TextureFormat::Rgba8Unorm,
-            bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
+            dither: proc_volume.dither,
+            self_shadow: proc_volume.self_shadow,
+            ..default()
+        });
+
+        let render_start = std::time::Instant::now();
+        let render_result = render_volume_with_settings(&proc_volume.volume, &settings);
+        info!(
+            "Rendered {}x{} procedural maps in {:.1} ms",
+            output_size.x,
+            output_size.y,
+            render_start.elapsed().as_secs_f32() * 1000.0
         );
-        
-        let diffuse_image = Image::new(
+        proc_volume.normal_samples = sample_normal_grid(&render_result);
+        insert_rendered_maps(&mut images, material, &render_result);
+        proc_volume.last_render = Some((settings, render_result));
+
+        proc_volume.needs_update = false;
+    }
+}
+
+/// Replace a procedural sprite's maps with a render's
+/// `FitToDiffuse` follows any change in size.
+fn insert_rendered_maps(images: &mut Assets<Image>, material: &PositionMappedMaterial, result: &VolumeRenderResult) {
+    let map = |data: &[u8], format| {
+        Image::new(
             bevy::render::render_resource::Extent3d {
-                width: render_result.width,
-                height: render_result.height,
+                width: result.width,
+                height: result.height,
                 depth_or_array_layers: 1,
             },
             bevy::render::render_resource::TextureDimension::D2,
-            render_result.diffuse_map,
-            bevy::render::render_resource::TextureFormat::Rgba8Unorm,
+            data.to_vec(),
+            format,
             bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
-        );
+        )
+    };
+    let rgba8 = bevy::render::render_resource::TextureFormat::Rgba8Unorm;
+    images.insert(&material.position_texture, map(&result.position_map, rgba8));
+    images.insert(&material.normal_texture, map(&result.normal_map, result.normal_format.texture_format()));
+    images.insert(&material.diffuse_texture, map(&result.diffuse_map, rgba8));
+}
 
-        // Replace the images in the asset storage
-        images.insert(&material.position_texture, position_image);
-        images.insert(&material.normal_texture, normal_image);
-        images.insert(&material.diffuse_texture, diffuse_image);
+/// Radius in voxels of the hole a right click carves
+const SCULPT_RADIUS: f32 = 4.0;
+/// Width in voxels of the carved hole's soft edge
+const SCULPT_FALLOFF: f32 = 1.0;
+/// How far in voxels a Ctrl + right click drills into the rock
+const SCULPT_DRILL_DEPTH: f32 = 16.0;
 
-        proc_volume.needs_update = false;
+/// Right click carves a hole in the selected rock where the cursor hits its surface
+/// Holding Shift carves a cube instead, and Ctrl drills a narrow shaft straight in along the view.
+/// Only the pixels whose rays pass through the hole are re-marched, by patching the last
+/// render with `update_volume_render_region`. A rock with no CPU render to patch, such as
+/// one just switched over from the GPU renderer, gets a full re-render instead.
+#[allow(clippy::type_complexity)]
+fn sculpt_selected_volume(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut volume_query: Query<
+        (&mut ProceduralVolume, &MeshMaterial2d<PositionMappedMaterial>, &GlobalTransform),
+        (With<SelectedVolume>, Without<GpuVolumeRenderer>),
+    >,
+    mut materials: ResMut<Assets<PositionMappedMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.single(), camera.single()) else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+    let Ok((mut proc_volume, material_handle, transform)) = volume_query.single_mut() else {
+        return;
+    };
+    let proc_volume = &mut *proc_volume;
+    let Some((settings, result)) = proc_volume.last_render.as_mut() else {
+        proc_volume.needs_update = true;
+        return;
+    };
+
+    // The sprite is the map's size in world units, centered on the rock, rows running down
+    let local = transform.affine().inverse().transform_point3(cursor.extend(0.0)).truncate();
+    let pixel = Vec2::new(local.x + result.width as f32 * 0.5, result.height as f32 * 0.5 - local.y).floor();
+    if pixel.cmplt(Vec2::ZERO).any() || pixel.x >= result.width as f32 || pixel.y >= result.height as f32 {
+        return;
+    }
+    let index = (pixel.y as u32 * result.width + pixel.x as u32) as usize * 4;
+    let encoded = &result.position_map[index..index + 4];
+    if encoded[3] == 0 {
+        return; // The cursor is over a pixel the rock doesn't cover
+    }
+
+    // Position maps hold the hit in march space scaled by the largest axis, voxel centres at +0.5
+    let extent = proc_volume.volume.dimensions.max_element() as f32;
+    let hit = Vec3::new(encoded[0] as f32, encoded[1] as f32, encoded[2] as f32) / 255.0 * extent;
+    let center = hit - Vec3::splat(0.5);
+    let reach = Vec3::splat(SCULPT_RADIUS + SCULPT_FALLOFF);
+    let (min, max) = if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        // The camera looks down its -Z, in the same volume space the hit is in
+        let camera = settings.camera.unwrap_or_else(|| euler_camera(settings.rotation, extent));
+        let end = center + camera.forward() * SCULPT_DRILL_DEPTH;
+        proc_volume.volume.fill_cylinder(center, end, SCULPT_RADIUS * 0.5, 0.0, SCULPT_FALLOFF);
+        (center.min(end) - reach, center.max(end) + reach)
+    } else if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let half = Vec3::splat(SCULPT_RADIUS);
+        proc_volume.volume.fill_box(center - half, center + half, 0.0, SCULPT_FALLOFF);
+        (center - reach, center + reach)
+    } else {
+        proc_volume.volume.fill_sphere(center, SCULPT_RADIUS, 0.0, SCULPT_FALLOFF);
+        (center - reach, center + reach)
+    };
+
+    let patch_start = std::time::Instant::now();
+    update_volume_render_region(&proc_volume.volume, settings, min, max, result);
+    info!("Sculpted rock, patched its maps in {:.1} ms", patch_start.elapsed().as_secs_f32() * 1000.0);
+
+    // Borrowed mutably so the material picks up the replaced maps, as in `update_procedural_volume`
+    if let Some(material) = materials.get_mut(material_handle) {
+        insert_rendered_maps(&mut images, material, result);
     }
+    proc_volume.normal_samples = sample_normal_grid(result);
+    proc_volume.stats = proc_volume.volume.stats(RenderSettings::default().threshold);
 }
 
-/// System to update the material's uniform data based on the light's transform and properties
+/// Lit sprites with their material, placement and, once computed, bounds
+type LitSpriteQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static MeshMaterial2d<PositionMappedMaterial>, &'static Transform, Option<&'static Aabb>),
+    With<PositionMappedSprite>,
+>;
+
+/// System to update each sprite material's uniform data from the lights that reach it
+/// Scene-wide settings (ambient, debug mode, shading) come from the first light, so a sprite
+/// with no lights in range is still ambient lit.
 fn update_material_light_info(
     light_query: Query<(&Transform, &MovableLightMarker)>,
     mut custom_materials: ResMut<Assets<PositionMappedMaterial>>,
-    sprite_query: Query<(&MeshMaterial2d<PositionMappedMaterial>, &Transform), With<PositionMappedSprite>>,
+    sprite_query: LitSpriteQuery,
 ) {
-    if let Ok((light_transform, light_props)) = light_query.single()
-        && let Ok((material_handle, sprite_transform)) = sprite_query.single()
-        && let Some(material) = custom_materials.get_mut(material_handle)
-    {
-        // Light position: XY from transform (ground position), Z from virtual_height
-        material.uniform_data.light_pos_world_3d = Vec3::new(
-            light_transform.translation.x,
-            light_transform.translation.y,
-            light_props.virtual_height,
+    let Some((_, scene_props)) = light_query.iter().next() else {
+        return;
+    };
+
+    for (material_handle, sprite_transform, aabb) in sprite_query.iter() {
+        let Some(material) = custom_materials.get_mut(material_handle) else {
+            continue;
+        };
+
+        // Sprite bounds on the ground plane (rotation is ignored, which only over-includes)
+        let center = sprite_transform.translation.truncate();
+        let half_size = aabb
+            .map(|aabb| Vec3::from(aabb.half_extents).truncate() * sprite_transform.scale.truncate().abs())
+            .unwrap_or(Vec2::ZERO);
+        let offset = aabb.map(|aabb| Vec3::from(aabb.center).truncate()).unwrap_or(Vec2::ZERO);
+        let bounds = Rect::from_center_half_size(center + offset, half_size);
+
+        let lights = cull_lights_for_sprite(
+            bounds,
+            light_query
+                .iter()
+                .map(|(transform, props)| (transform.translation.truncate(), props)),
         );
-        material.uniform_data.sprite_world_pos = sprite_transform.translation.truncate();
-        material.uniform_data.light_color =
-            LinearRgba::from(light_props.color) * light_props.intensity;
-        material.uniform_data.ambient_light_color =
-            LinearRgba::from(light_props.ambient_color) * light_props.ambient_intensity;
-        material.uniform_data.light_radius = light_props.radius;
-        material.uniform_data.light_falloff = light_props.falloff;
-        material.uniform_data.position_scale = light_props.position_scale;
-        material.uniform_data.debug_mode = light_props.debug_mode;
+
+        let uniform = &mut material.uniform_data;
+        uniform.sprite_world_pos = center;
+        uniform.sky_ambient_color =
+            LinearRgba::from(scene_props.sky_ambient_color) * scene_props.ambient_intensity;
+        uniform.ground_ambient_color =
+            LinearRgba::from(scene_props.ground_ambient_color) * scene_props.ambient_intensity;
+        uniform.position_scale = scene_props.position_scale;
+        uniform.debug_mode = scene_props.debug_mode.as_uniform();
+        uniform.shading_bands = scene_props.shading_mode.bands();
+        uniform.lighting_source = scene_props.lighting_source.as_uniform();
+
+        if let Some(&(light_pos, light_props)) = lights.first() {
+            // Light position: XY from transform (ground position), Z from virtual_height
+            uniform.light_pos_world_3d = light_pos.extend(light_props.virtual_height);
+            uniform.light_color = LinearRgba::from(light_props.color) * light_props.intensity;
+            uniform.light_radius = light_props.radius;
+            uniform.light_falloff = light_props.falloff;
+        } else {
+            uniform.light_color = LinearRgba::BLACK;
+        }
     }
 }
 
@@ -709,56 +1669,119 @@ fn update_gpu_volume(
 }
 
 /// Toggle between CPU and GPU rendering modes
+/// Only each rock's renderer is swapped: volumes, rotations, render settings and the
+/// light carry over, so both paths can be compared on the same view. The maps keep
+/// their handles and are refilled by the new renderer.
+#[allow(clippy::too_many_arguments)]
 fn toggle_render_mode(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut render_mode: ResMut<VolumeRenderMode>,
     mut commands: Commands,
-    procedural_scene_query: Query<Entity, With<ProceduralSceneEntity>>,
+    mut rock_query: Query<(
+        Entity,
+        &mut ProceduralVolume,
+        &MeshMaterial2d<PositionMappedMaterial>,
+        Option<&GpuVolumeRenderer>,
+    )>,
     ui_query: Query<(Entity, &SceneUi)>,
-    asset_server: Res<AssetServer>,
-    meshes: ResMut<Assets<Mesh>>,
-    custom_materials: ResMut<Assets<PositionMappedMaterial>>,
-    images: ResMut<Assets<Image>>,
+    custom_materials: Res<Assets<PositionMappedMaterial>>,
+    mut images: ResMut<Assets<Image>>,
     current_scene: Res<CurrentScene>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyM) && *current_scene == CurrentScene::Procedural {
-        // Toggle mode
-        *render_mode = match *render_mode {
-            VolumeRenderMode::Cpu => VolumeRenderMode::Gpu,
-            VolumeRenderMode::Gpu => VolumeRenderMode::Cpu,
+    if !keyboard_input.just_pressed(KeyCode::KeyM) || *current_scene != CurrentScene::Procedural {
+        return;
+    }
+
+    *render_mode = match *render_mode {
+        VolumeRenderMode::Cpu => VolumeRenderMode::Gpu,
+        VolumeRenderMode::Gpu => VolumeRenderMode::Cpu,
+    };
+
+    for (entity, mut proc_volume, material_handle, gpu_renderer) in rock_query.iter_mut() {
+        let Some(material) = custom_materials.get(material_handle) else {
+            continue;
         };
 
-        // Despawn current procedural scene entities
-        for entity in procedural_scene_query.iter() {
-            commands.entity(entity).despawn();
+        match *render_mode {
+            VolumeRenderMode::Gpu => {
+                // Pick up any rotation still waiting out the CPU debounce
+                let rotation = proc_volume.target_rotation;
+                proc_volume.rotation = rotation;
+                let output_size = proc_volume.output_size;
+                for map in [&material.position_texture, &material.normal_texture, &material.diffuse_texture] {
+                    images.insert(map, output_texture(output_size));
+                }
+                commands.entity(entity).insert(GpuVolumeRenderer {
+                    volume_texture: create_volume_texture(&proc_volume.volume, &mut images),
+                    position_output: material.position_texture.clone(),
+                    normal_output: material.normal_texture.clone(),
+                    diffuse_output: material.diffuse_texture.clone(),
+                    rotation,
+                    camera: None,
+                    volume_size: proc_volume.params.size as f32,
+                    output_size,
+                });
+            }
+            VolumeRenderMode::Cpu => {
+                if let Some(gpu_renderer) = gpu_renderer {
+                    proc_volume.rotation = gpu_renderer.rotation;
+                    proc_volume.target_rotation = gpu_renderer.rotation;
+                }
+                // `update_procedural_volume` writes the CPU maps over the GPU outputs
+                proc_volume.needs_update = true;
+                commands.entity(entity).remove::<GpuVolumeRenderer>();
+            }
         }
-        
-        // Despawn current UI
-        despawn_scene_ui(commands.reborrow(), ui_query, SceneType::Procedural);
-
-        // Recreate the procedural scene with new render mode (will spawn new UI)
-        setup_procedural_scene(commands, asset_server, meshes, custom_materials, images, *render_mode);
     }
+
+    // Respawn the UI to show the new mode
+    despawn_scene_ui(commands.reborrow(), ui_query, SceneType::Procedural);
+    spawn_procedural_ui(&mut commands, render_mode.as_str());
+    info!("Volume render mode: {}", render_mode.as_str());
 }
 
 /// Update the debug mode display in the status panel
+/// The selected CPU rock's render quality is listed too, when there is one.
 fn update_debug_mode_display(
-    light_query: Query<&MovableLightMarker, Changed<MovableLightMarker>>,
+    light_query: Query<Ref<MovableLightMarker>>,
+    volume_query: Query<&ProceduralVolume, (With<SelectedVolume>, Without<GpuVolumeRenderer>)>,
     mut status_query: Query<&mut Text, With<StatusPanel>>,
+    mut shown_volume: Local<Option<(RenderQuality, VolumeStats)>>,
 ) {
-    // Only update if light properties changed
+    let volume = volume_query.single().ok().map(|volume| (volume.quality, volume.stats));
+    let volume_changed = *shown_volume != volume;
+
+    // Only update if light properties or the selected volume's quality or stats changed
     if let Ok(light_props) = light_query.single() {
+        if !light_props.is_changed() && !volume_changed {
+            return;
+        }
         if let Ok(mut text) = status_query.single_mut() {
-            let mode_text = match light_props.debug_mode {
-                0 => "Normal Lighting",
-                1 => "Show Position Map (RGB = XYZ)",
-                2 => "Show Normal Map",
-                3 => "Show Distance to Light",
-                4 => "Show Ground Level Only",
-                5 => "Show 3D World Positions",
-                _ => "Unknown",
-            };
-            **text = format!("Debug Mode: {}", mode_text);
+            *shown_volume = volume;
+            let mut status = String::from("Debug Overlays (0 = off):\n");
+            for (index, &(_, mode)) in DEBUG_OVERLAY_KEYS.iter().enumerate() {
+                let marker = if light_props.debug_mode == mode { "x" } else { " " };
+                status.push_str(&format!("  [{}] {} - {}\n", marker, index + 1, mode.label()));
+            }
+            if light_props.debug_mode == DebugMode::Off {
+                status.push_str(DebugMode::Off.label());
+            }
+            match light_props.shading_mode {
+                ShadingMode::Smooth => status.push_str("\nShading: Smooth"),
+                ShadingMode::Toon { bands } => status.push_str(&format!("\nShading: Toon ({} bands)", bands)),
+            }
+            status.push_str(&format!("\nLighting: {}", light_props.lighting_source.label()));
+            if let Some((quality, stats)) = volume {
+                status.push_str(&format!("\nQuality: {:?}", quality));
+                status.push_str(&format!(
+                    "\nSolid: {:.1}% (density {:.2}..{:.2}, mean {:.2})",
+                    stats.solid_fraction * 100.0,
+                    stats.min,
+                    stats.max,
+                    stats.mean
+                ));
+            }
+            **text = status;
         }
     }
 }
@@ -34,3 +34,7 @@ pub struct ProceduralSceneEntity;
 
 #[derive(Component)]
 pub struct PositionMappedSprite;
+
+/// The procedural volume the rotation and inspection controls act on
+#[derive(Component)]
+pub struct SelectedVolume;
@@ -8,6 +8,9 @@ pub struct InstructionsPanel;
 #[derive(Component)]
 pub struct StatusPanel;
 
+/// Usual background of the status panel
+pub const STATUS_PANEL_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+
 /// Component to track which scene the UI belongs to
 #[derive(Component)]
 pub struct SceneUi {
@@ -83,13 +86,29 @@ pub fn spawn_texture_mapped_ui(commands: &mut Commands) {
                 text_font.clone(),
             ));
             parent.spawn((
-                TextSpan::new("  V - Cycle debug modes\n"),
+                TextSpan::new("  1-5 - Toggle debug overlay (V cycles)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  G - Toggle toon shading (,/. bands)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  N - Cycle lighting source (normal/position maps)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  C - Toggle light path sweep (Shift: ping-pong)\n"),
                 text_font.clone(),
             ));
             parent.spawn((
                 TextSpan::new("  Space - Display info\n"),
                 text_font.clone(),
             ));
+            parent.spawn((
+                TextSpan::new("  F4 - Ground grid\n"),
+                text_font.clone(),
+            ));
             parent.spawn((
                 TextSpan::new("  F1 - Switch to Procedural Scene\n"),
                 TextFont {
@@ -112,7 +131,7 @@ pub fn spawn_texture_mapped_ui(commands: &mut Commands) {
                 padding: UiRect::all(Val::Px(8.0)),
                 ..default()
             },
-            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            BackgroundColor(STATUS_PANEL_BACKGROUND),
             StatusPanel,
             SceneUi {
                 scene_type: SceneType::TextureMapped,
@@ -187,7 +206,19 @@ pub fn spawn_procedural_ui(commands: &mut Commands, render_mode: &str) {
                 text_font.clone(),
             ));
             parent.spawn((
-                TextSpan::new("  V - Cycle debug modes\n"),
+                TextSpan::new("  1-5 - Toggle debug overlay (V cycles)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  G - Toggle toon shading (,/. bands)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  N - Cycle lighting source (normal/position maps)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  C - Toggle light path sweep (Shift: ping-pong)\n"),
                 text_font.clone(),
             ));
             parent.spawn((
@@ -202,6 +233,10 @@ pub fn spawn_procedural_ui(commands: &mut Commands, render_mode: &str) {
                 },
                 TextColor(Color::srgb(1.0, 1.0, 0.5)),
             ));
+            parent.spawn((
+                TextSpan::new("  Tab - Select next rock\n"),
+                text_font.clone(),
+            ));
             parent.spawn((
                 TextSpan::new("  Q/E - Rotate Y-axis\n"),
                 text_font.clone(),
@@ -218,6 +253,50 @@ pub fn spawn_procedural_ui(commands: &mut Commands, render_mode: &str) {
                 TextSpan::new("  X - Reset rotation\n"),
                 text_font.clone(),
             ));
+            parent.spawn((
+                TextSpan::new("  Right click - Carve a hole in the selected rock\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  Shift/Ctrl + Right click - Carve a cube / drill a shaft\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  O - Bake a turntable of the selected rock\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  F2 - Volume slice inspector (PgUp/PgDn scrub Z)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  F3 - Normal arrows (CPU)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  F5 - Cycle render quality (CPU)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  F6 - Height in the normal map's alpha (CPU)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  F4 - Ground grid\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  H - Ground shadow opacity (0 = off)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  B - Cycle 8-bit map dithering (CPU)\n"),
+                text_font.clone(),
+            ));
+            parent.spawn((
+                TextSpan::new("  Z - Toggle volumetric self-shadows (CPU)\n"),
+                text_font.clone(),
+            ));
             parent.spawn((
                 TextSpan::new("\n  M - Toggle CPU/GPU rendering\n"),
                 TextFont {
@@ -248,7 +327,7 @@ pub fn spawn_procedural_ui(commands: &mut Commands, render_mode: &str) {
                 padding: UiRect::all(Val::Px(8.0)),
                 ..default()
             },
-            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            BackgroundColor(STATUS_PANEL_BACKGROUND),
             StatusPanel,
             SceneUi {
                 scene_type: SceneType::Procedural,
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
-use noise::{Fbm, NoiseFn, Perlin};
+use bevy::image::ImageSampler;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use noise::{Fbm, NoiseFn, Perlin, RidgedMulti};
 
 /// Represents a 3D voxel volume with density values
 #[derive(Clone)]
@@ -8,8 +11,32 @@ pub struct Volume {
     pub dimensions: UVec3,
     /// Density values at each voxel (0.0 = empty, 1.0 = solid)
     pub data: Vec<f32>,
+    /// Material id at each voxel, indexing `VOLUME_MATERIAL_COLORS` (0 = plain rock)
+    pub materials: Vec<u8>,
 }
 
+/// Summary of a volume's densities, from `Volume::stats`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VolumeStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// Share of voxels above the solid threshold, 0..=1
+    pub solid_fraction: f32,
+}
+
+/// Volume material ids
+pub const VOLUME_MATERIAL_ROCK: u8 = 0;
+pub const VOLUME_MATERIAL_CRYSTAL: u8 = 1;
+pub const VOLUME_MATERIAL_ORE: u8 = 2;
+
+/// Diffuse color per volume material id; unknown ids render as rock
+pub const VOLUME_MATERIAL_COLORS: [[u8; 3]; 3] = [
+    [180, 170, 160], // Rock
+    [120, 220, 255], // Crystal
+    [235, 170, 60],  // Ore
+];
+
 impl Volume {
     /// Create a new empty volume with given dimensions
     pub fn new(width: u32, height: u32, depth: u32) -> Self {
@@ -17,7 +44,35 @@ impl Volume {
         Self {
             dimensions: UVec3::new(width, height, depth),
             data: vec![0.0; size],
+            materials: vec![VOLUME_MATERIAL_ROCK; size],
+        }
+    }
+
+    /// Build a volume from a signed distance function
+    ///
+    /// `sdf` receives each voxel position relative to the volume center (in voxels)
+    /// and returns the signed distance to the surface (negative inside). Density is
+    /// a smoothstep of `-sdf` over -1..1, from 0 a voxel outside the surface to 1 a voxel
+    /// inside it (0.5 on the surface), so surfaces stay slightly soft for the
+    /// gradient-based normals.
+    ///
+    /// The legacy demo is a binary, so example SDFs (sphere, box, torus) live in the
+    /// `test_from_sdf_*` unit tests rather than in doctests.
+    pub fn from_sdf(dims: UVec3, sdf: impl Fn(Vec3) -> f32) -> Self {
+        let mut volume = Self::new(dims.x, dims.y, dims.z);
+        let center = dims.as_vec3() / 2.0;
+
+        for z in 0..dims.z {
+            for y in 0..dims.y {
+                for x in 0..dims.x {
+                    let p = Vec3::new(x as f32, y as f32, z as f32) - center;
+                    let t = ((1.0 - sdf(p)) * 0.5).clamp(0.0, 1.0);
+                    volume.set(x, y, z, t * t * (3.0 - 2.0 * t));
+                }
+            }
         }
+
+        volume
     }
 
     /// Get the index for a 3D coordinate
@@ -43,6 +98,98 @@ impl Volume {
         self.data[idx] = value;
     }
 
+    /// Get the material id at a specific voxel position
+    pub fn get_material(&self, x: u32, y: u32, z: u32) -> u8 {
+        if x >= self.dimensions.x || y >= self.dimensions.y || z >= self.dimensions.z {
+            return VOLUME_MATERIAL_ROCK;
+        }
+        self.materials[self.index(x, y, z)]
+    }
+
+    /// Set the material id at a specific voxel position
+    pub fn set_material(&mut self, x: u32, y: u32, z: u32, material: u8) {
+        if x >= self.dimensions.x || y >= self.dimensions.y || z >= self.dimensions.z {
+            return;
+        }
+        let idx = self.index(x, y, z);
+        self.materials[idx] = material;
+    }
+
+    /// Fill a sphere with `density`
+    ///
+    /// Positions are in voxels (voxel `(x, y, z)` sits at that coordinate, as in `sample`).
+    /// `falloff` is the width in voxels of a smooth edge blended into the existing density;
+    /// 0 gives a hard edge. The fill helpers overwrite rather than accumulate, so they can
+    /// also carve by passing a density of 0.
+    pub fn fill_sphere(&mut self, center: Vec3, radius: f32, density: f32, falloff: f32) {
+        let extent = Vec3::splat(radius);
+        self.fill_shape(center - extent, center + extent, density, falloff, |p| {
+            p.distance(center) - radius
+        });
+    }
+
+    /// Fill an axis-aligned box between `min` and `max` (inclusive, in voxels) with `density`
+    pub fn fill_box(&mut self, min: Vec3, max: Vec3, density: f32, falloff: f32) {
+        let center = (min + max) * 0.5;
+        let half = (max - min) * 0.5;
+        self.fill_shape(min, max, density, falloff, |p| {
+            let q = (p - center).abs() - half;
+            q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+        });
+    }
+
+    /// Fill a capped cylinder running from `start` to `end` with `density`
+    pub fn fill_cylinder(&mut self, start: Vec3, end: Vec3, radius: f32, density: f32, falloff: f32) {
+        let axis = end - start;
+        let length = axis.length();
+        let Some(direction) = axis.try_normalize() else {
+            return;
+        };
+        let extent = Vec3::splat(radius);
+        self.fill_shape(start.min(end) - extent, start.max(end) + extent, density, falloff, |p| {
+            // Distance to the side wall and to the nearer cap, combined like a 2D box
+            let along = (p - start).dot(direction);
+            let radial = (p - start - direction * along).length();
+            let q = Vec2::new(radial - radius, (along - length * 0.5).abs() - length * 0.5);
+            q.max(Vec2::ZERO).length() + q.max_element().min(0.0)
+        });
+    }
+
+    /// Write `density` into every voxel inside `sdf`, visiting only the given bounds
+    fn fill_shape(&mut self, min: Vec3, max: Vec3, density: f32, falloff: f32, sdf: impl Fn(Vec3) -> f32) {
+        // Pad by the falloff so the soft edge isn't clipped, then clamp to the volume
+        let last = (self.dimensions.as_ivec3() - IVec3::ONE).as_vec3();
+        let pad = Vec3::splat(falloff.max(0.0) * 0.5);
+        let lo = (min - pad).floor();
+        let hi = (max + pad).ceil();
+        if hi.cmplt(Vec3::ZERO).any() || lo.cmpgt(last).any() {
+            return;
+        }
+        let lo = lo.clamp(Vec3::ZERO, last).as_uvec3();
+        let hi = hi.clamp(Vec3::ZERO, last).as_uvec3();
+
+        for z in lo.z..=hi.z {
+            for y in lo.y..=hi.y {
+                for x in lo.x..=hi.x {
+                    let distance = sdf(Vec3::new(x as f32, y as f32, z as f32));
+                    let coverage = if falloff > 0.0 {
+                        let t = (0.5 - distance / falloff).clamp(0.0, 1.0);
+                        t * t * (3.0 - 2.0 * t)
+                    } else if distance <= 0.0 {
+                        1.0
+                    } else {
+                        0.0
+                    };
+
+                    if coverage > 0.0 {
+                        let idx = self.index(x, y, z);
+                        self.data[idx] += (density - self.data[idx]) * coverage;
+                    }
+                }
+            }
+        }
+    }
+
     /// Sample the volume with trilinear interpolation
     pub fn sample(&self, pos: Vec3) -> f32 {
         // Clamp to volume bounds
@@ -83,8 +230,67 @@ impl Volume {
         c0 * (1.0 - fz) + c1 * fz
     }
 
-    /// Calculate the gradient (normal) at a position using central differences
-    pub fn gradient(&self, x: u32, y: u32, z: u32) -> Vec3 {
+    /// Sample the volume at the closest voxel, keeping hard edges intact
+    pub fn sample_nearest(&self, pos: Vec3) -> f32 {
+        let max = (self.dimensions - UVec3::ONE).as_vec3();
+        let voxel = pos.round().clamp(Vec3::ZERO, max).as_uvec3();
+        self.get(voxel.x, voxel.y, voxel.z)
+    }
+
+    /// Sample the volume with tricubic (Catmull-Rom) interpolation
+    /// Smoother than trilinear across voxel boundaries, at 64 reads per sample.
+    /// Catmull-Rom can overshoot slightly near sharp density steps.
+    pub fn sample_tricubic(&self, pos: Vec3) -> f32 {
+        let max = (self.dimensions - UVec3::ONE).as_ivec3();
+        let pos = pos.clamp(Vec3::ZERO, max.as_vec3());
+        let base = pos.floor().as_ivec3();
+        let t = pos - base.as_vec3();
+
+        let fetch = |x: i32, y: i32, z: i32| {
+            let voxel = IVec3::new(x, y, z).clamp(IVec3::ZERO, max).as_uvec3();
+            self.get(voxel.x, voxel.y, voxel.z)
+        };
+
+        let mut planes = [0.0; 4];
+        for (k, plane) in planes.iter_mut().enumerate() {
+            let z = base.z + k as i32 - 1;
+            let mut rows = [0.0; 4];
+            for (j, row) in rows.iter_mut().enumerate() {
+                let y = base.y + j as i32 - 1;
+                let x = base.x;
+                *row = cubic_interpolate(
+                    [fetch(x - 1, y, z), fetch(x, y, z), fetch(x + 1, y, z), fetch(x + 2, y, z)],
+                    t.x,
+                );
+            }
+            *plane = cubic_interpolate(rows, t.y);
+        }
+        cubic_interpolate(planes, t.z)
+    }
+
+    /// Sample the volume with the given filter
+    pub fn sample_filtered(&self, pos: Vec3, filter: SampleFilter) -> f32 {
+        match filter {
+            SampleFilter::Nearest => self.sample_nearest(pos),
+            SampleFilter::Trilinear => self.sample(pos),
+            SampleFilter::Tricubic => self.sample_tricubic(pos),
+        }
+    }
+
+    /// Outward-facing density gradient at a fractional position, before normalization
+    /// Unlike `gradient_raw` the differences are taken on the filtered field, so normals
+    /// vary smoothly across a voxel instead of stepping at voxel boundaries.
+    pub fn gradient_filtered(&self, pos: Vec3, filter: SampleFilter) -> Vec3 {
+        let sample = |offset: Vec3| self.sample_filtered(pos + offset, filter);
+        let dx = (sample(Vec3::X) - sample(-Vec3::X)) * 0.5;
+        let dy = (sample(Vec3::Y) - sample(-Vec3::Y)) * 0.5;
+        let dz = (sample(Vec3::Z) - sample(-Vec3::Z)) * 0.5;
+        Vec3::new(-dx, -dy, -dz)
+    }
+
+    /// Outward-facing density gradient before normalization
+    /// A tiny magnitude means the surface direction is unreliable (thin features, silhouettes).
+    pub fn gradient_raw(&self, x: u32, y: u32, z: u32) -> Vec3 {
         let step = 1.0;
         
         let dx = if x > 0 && x < self.dimensions.x - 1 {
@@ -106,18 +312,78 @@ impl Volume {
         };
 
         // Negate for outward-facing normals
-        let normal = Vec3::new(-dx, -dy, -dz);
-        
-        if normal.length_squared() > 0.0001 {
-            normal.normalize()
-        } else {
-            Vec3::Y // Default up vector if no gradient
+        Vec3::new(-dx, -dy, -dz)
+    }
+
+    /// Density statistics over every voxel, counting densities above `threshold` as solid
+    pub fn stats(&self, threshold: f32) -> VolumeStats {
+        if self.data.is_empty() {
+            return VolumeStats::default();
+        }
+        let mut stats = VolumeStats {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            ..default()
+        };
+        let mut sum = 0.0;
+        let mut solid = 0;
+        for &density in &self.data {
+            stats.min = stats.min.min(density);
+            stats.max = stats.max.max(density);
+            sum += density as f64;
+            solid += (density > threshold) as usize;
+        }
+        stats.mean = (sum / self.data.len() as f64) as f32;
+        stats.solid_fraction = solid as f32 / self.data.len() as f32;
+        stats
+    }
+
+    /// Coordinates of every voxel whose density is NaN or infinite
+    pub fn non_finite_voxels(&self) -> Vec<UVec3> {
+        let (width, height) = (self.dimensions.x as usize, self.dimensions.y as usize);
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, density)| !density.is_finite())
+            .map(|(i, _)| UVec3::new((i % width) as u32, (i / width % height) as u32, (i / (width * height)) as u32))
+            .collect()
+    }
+
+    /// Check every density is finite
+    /// A single NaN voxel spreads into the gradients around it and renders as garbage normals.
+    pub fn validate(&self) -> Result<(), String> {
+        let bad = self.non_finite_voxels();
+        if bad.is_empty() {
+            return Ok(());
         }
+        let shown: Vec<String> = bad.iter().take(8).map(|v| format!("({}, {}, {})", v.x, v.y, v.z)).collect();
+        let more = if bad.len() > shown.len() { ", ..." } else { "" };
+        Err(format!("{} non-finite densities at {}{}", bad.len(), shown.join(", "), more))
     }
 }
 
+/// Catmull-Rom interpolation between `p[1]` and `p[2]`
+fn cubic_interpolate(p: [f32; 4], t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p[1]
+        + (p[2] - p[0]) * t
+        + (2.0 * p[0] - 5.0 * p[1] + 4.0 * p[2] - p[3]) * t2
+        + (3.0 * p[1] - p[0] - 3.0 * p[2] + p[3]) * t3)
+}
+
+/// Fractal noise that shapes a procedural rock's surface, both built over Perlin noise
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoiseKind {
+    /// Fractal Brownian motion: rounded, lumpy boulders
+    #[default]
+    Fbm,
+    /// Ridged multifractal: sharp crests and cliff faces, best with a weaker sphere mask
+    RidgedMulti,
+}
+
 /// Parameters for procedural rock generation
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct RockGenerationParams {
     pub size: u32,
     pub scale: f32,
@@ -126,6 +392,12 @@ pub struct RockGenerationParams {
     pub persistence: f32,
     pub threshold: f32,
     pub seed: u32,
+    /// Frequency of the secondary detail noise (relative to `scale`)
+    pub detail_scale: f32,
+    /// Strength of the detail noise; 0 disables the detail pass
+    pub detail_amplitude: f32,
+    /// Noise for the main surface shape; the detail pass always uses `Fbm`
+    pub noise: NoiseKind,
 }
 
 impl Default for RockGenerationParams {
@@ -138,16 +410,97 @@ impl Default for RockGenerationParams {
             persistence: 0.5,
             threshold: 0.0,
             seed: 42,
+            detail_scale: 16.0,
+            detail_amplitude: 0.0,
+            noise: NoiseKind::default(),
+        }
+    }
+}
+
+/// Largest rock edge `generate_rock_volume` accepts; beyond this a single bake takes seconds
+pub const MAX_ROCK_SIZE: u32 = 256;
+
+impl RockGenerationParams {
+    /// Check every parameter is in a range the generator can handle
+    /// Params driven from UI can be anything, and out-of-range ones divide by zero or hang.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(2..=MAX_ROCK_SIZE).contains(&self.size) {
+            return Err(format!("size must be in 2..={}, got {}", MAX_ROCK_SIZE, self.size));
+        }
+        if !(1..=8).contains(&self.octaves) {
+            return Err(format!("octaves must be in 1..=8, got {}", self.octaves));
+        }
+        if !(self.scale.is_finite() && self.scale > 0.0) {
+            return Err(format!("scale must be positive, got {}", self.scale));
+        }
+        if !(self.lacunarity.is_finite() && self.lacunarity > 0.0) {
+            return Err(format!("lacunarity must be positive, got {}", self.lacunarity));
+        }
+        if !(0.0..=1.0).contains(&self.persistence) {
+            return Err(format!("persistence must be in 0..=1, got {}", self.persistence));
+        }
+        if !self.threshold.is_finite() {
+            return Err(format!("threshold must be finite, got {}", self.threshold));
+        }
+        if !(self.detail_scale.is_finite() && self.detail_scale > 0.0) {
+            return Err(format!("detail_scale must be positive, got {}", self.detail_scale));
         }
+        if !(self.detail_amplitude.is_finite() && self.detail_amplitude >= 0.0) {
+            return Err(format!("detail_amplitude must be non-negative, got {}", self.detail_amplitude));
+        }
+        Ok(())
+    }
+}
+
+/// Rock volumes already generated, each stored with the params that made it
+/// Scene rebuilds ask for the same few rocks over and over; the noise pass only
+/// runs the first time each set of params is seen. Lookups compare the whole params,
+/// so a field added to `RockGenerationParams` takes part without touching the cache.
+#[derive(Resource, Default)]
+pub struct VolumeCache {
+    volumes: Vec<(RockGenerationParams, Volume)>,
+}
+
+impl VolumeCache {
+    /// The volume for `params`, generating and storing it on a miss
+    pub fn get_or_generate(&mut self, params: &RockGenerationParams) -> Result<&Volume, String> {
+        let index = match self.volumes.iter().position(|(cached, _)| cached == params) {
+            Some(index) => index,
+            None => {
+                self.volumes.push((params.clone(), generate_rock_volume(params)?));
+                self.volumes.len() - 1
+            }
+        };
+        Ok(&self.volumes[index].1)
+    }
+
+    /// Number of cached volumes
+    pub fn len(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Whether nothing has been generated yet
+    pub fn is_empty(&self) -> bool {
+        self.volumes.is_empty()
     }
 }
 
 /// Generate a procedural rock volume using noise
-pub fn generate_rock_volume(params: &RockGenerationParams) -> Volume {
+/// Fails with `RockGenerationParams::validate`'s message instead of generating from bad params.
+pub fn generate_rock_volume(params: &RockGenerationParams) -> Result<Volume, String> {
+    params.validate()?;
+
     let mut volume = Volume::new(params.size, params.size, params.size);
     
-    // Create Fractal Brownian Motion noise
-    let fbm = Fbm::<Perlin>::new(params.seed);
+    let shape: Box<dyn NoiseFn<f64, 3>> = match params.noise {
+        NoiseKind::Fbm => Box::new(Fbm::<Perlin>::new(params.seed)),
+        NoiseKind::RidgedMulti => Box::new(RidgedMulti::<Perlin>::new(params.seed)),
+    };
+    let detail_fbm = Fbm::<Perlin>::new(params.seed.wrapping_add(1));
+
+    // Detail only modulates densities close to the rendered surface
+    let surface_density = RenderSettings::default().threshold;
+    let detail_band = 0.2;
     
     let center = params.size as f32 / 2.0;
     let radius = center * 0.8; // Make it slightly smaller than the volume
@@ -174,10 +527,25 @@ pub fn generate_rock_volume(params: &RockGenerationParams) -> Volume {
                     (pz / params.size as f32 * params.scale) as f64,
                 ];
                 
-                let noise_value = fbm.get(noise_pos) as f32;
+                let noise_value = shape.get(noise_pos) as f32;
                 
                 // Combine sphere with noise
-                let density = sphere_value + noise_value * 0.3;
+                let mut density = sphere_value + noise_value * 0.3;
+
+                // Fine grit near the surface, fading out toward the interior and exterior
+                if params.detail_amplitude > 0.0 {
+                    let surface_weight =
+                        1.0 - ((density - surface_density).abs() / detail_band).clamp(0.0, 1.0);
+                    if surface_weight > 0.0 {
+                        let detail_pos = [
+                            (px / params.size as f32 * params.detail_scale) as f64,
+                            (py / params.size as f32 * params.detail_scale) as f64,
+                            (pz / params.size as f32 * params.detail_scale) as f64,
+                        ];
+                        let detail = detail_fbm.get(detail_pos) as f32;
+                        density += detail * params.detail_amplitude * surface_weight;
+                    }
+                }
                 
                 // Apply threshold
                 let final_density = if density > params.threshold {
@@ -191,140 +559,1063 @@ pub fn generate_rock_volume(params: &RockGenerationParams) -> Volume {
         }
     }
     
+    Ok(volume)
+}
+
+/// Known, non-random shapes for checking renderer output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestPattern {
+    /// Fully solid box over the middle half of each axis
+    SolidCube,
+    /// Alternating solid and empty cells of `TEST_CHECKER_CELL` voxels
+    Checkerboard,
+    /// Solid ball whose radius is a third of the smallest axis
+    Sphere,
+    /// Density ramping from 0 at x = 0 to 1 at the last column
+    Gradient,
+}
+
+/// Cell edge in voxels for `TestPattern::Checkerboard`
+pub const TEST_CHECKER_CELL: u32 = 8;
+
+/// Build a volume with a deterministic test pattern
+/// Densities are exactly 0 or 1 except for `Gradient`, so expected render output can be
+/// worked out by hand: e.g. the front face of a `SolidCube` renders a flat normal facing the viewer.
+pub fn generate_test_volume(dims: UVec3, pattern: TestPattern) -> Volume {
+    let mut volume = Volume::new(dims.x, dims.y, dims.z);
+    let center = dims.as_vec3() / 2.0;
+    let radius = dims.min_element() as f32 / 3.0;
+
+    for z in 0..dims.z {
+        for y in 0..dims.y {
+            for x in 0..dims.x {
+                let voxel = UVec3::new(x, y, z);
+                let density = match pattern {
+                    TestPattern::SolidCube => {
+                        let inside = voxel.cmpge(dims / 4).all() && voxel.cmplt(dims - dims / 4).all();
+                        inside as u32 as f32
+                    }
+                    TestPattern::Checkerboard => {
+                        let cell = voxel / TEST_CHECKER_CELL;
+                        (cell.x + cell.y + cell.z).is_multiple_of(2) as u32 as f32
+                    }
+                    TestPattern::Sphere => {
+                        let inside = (voxel.as_vec3() + 0.5).distance(center) <= radius;
+                        inside as u32 as f32
+                    }
+                    TestPattern::Gradient => x as f32 / (dims.x - 1).max(1) as f32,
+                };
+                volume.set(x, y, z, density);
+            }
+        }
+    }
+
     volume
 }
 
+/// Parameters for threading mineral veins through a generated volume
+#[derive(Clone)]
+pub struct VeinParams {
+    /// Number of independent vein networks
+    pub count: u32,
+    /// Half-width of each vein in noise units; larger values give thicker streaks
+    pub thickness: f32,
+    /// Noise frequency across the volume; higher values give shorter, twistier veins
+    pub scale: f32,
+    /// Material id written into vein voxels
+    pub material: u8,
+    pub seed: u32,
+}
+
+impl Default for VeinParams {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            thickness: 0.06,
+            scale: 2.5,
+            material: VOLUME_MATERIAL_ORE,
+            seed: 7,
+        }
+    }
+}
+
+/// Thread worm-like veins of `params.material` through the solid part of a volume
+/// Each vein is where two independent noise fields are both near zero, which traces
+/// a winding tube. Only voxels that are already solid are touched, so veins never
+/// float in air; they are made fully dense so they read as hard inclusions.
+pub fn add_mineral_veins(volume: &mut Volume, params: &VeinParams) {
+    let solid_density = RenderSettings::default().threshold;
+    let fields: Vec<(Perlin, Perlin)> = (0..params.count)
+        .map(|i| {
+            let seed = params.seed.wrapping_add(i * 2);
+            (Perlin::new(seed), Perlin::new(seed.wrapping_add(1)))
+        })
+        .collect();
+
+    let dims = volume.dimensions;
+    let center = dims.as_vec3() / 2.0;
+    let size = dims.max_element() as f32;
+
+    for z in 0..dims.z {
+        for y in 0..dims.y {
+            for x in 0..dims.x {
+                if volume.get(x, y, z) <= solid_density {
+                    continue;
+                }
+
+                let p = (Vec3::new(x as f32, y as f32, z as f32) - center) / size * params.scale;
+                let point = [p.x as f64, p.y as f64, p.z as f64];
+                let in_vein = fields.iter().any(|(a, b)| {
+                    (a.get(point) as f32).abs() < params.thickness
+                        && (b.get(point) as f32).abs() < params.thickness
+                });
+
+                if in_vein {
+                    volume.set(x, y, z, 1.0);
+                    volume.set_material(x, y, z, params.material);
+                }
+            }
+        }
+    }
+}
+
+/// Interpolation used when the renderer reads densities and normals from a volume
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SampleFilter {
+    /// Closest voxel: crisp, voxel-accurate edges (e.g. hard-edged `.vox` imports)
+    #[default]
+    Nearest,
+    /// Blend of the 8 surrounding voxels
+    Trilinear,
+    /// Catmull-Rom over the 64 surrounding voxels, for the smoothest normals
+    Tricubic,
+}
+
+/// Storage precision for the generated normal map
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalMapFormat {
+    /// 8 bits per channel (256 steps per axis)
+    #[default]
+    Rgba8,
+    /// 16 bits per channel, avoids faceting under specular lighting
+    Rgba16,
+}
+
+impl NormalMapFormat {
+    /// Bytes used by a single RGBA pixel
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            NormalMapFormat::Rgba8 => 4,
+            NormalMapFormat::Rgba16 => 8,
+        }
+    }
+
+    /// Texture format matching the encoded bytes
+    pub fn texture_format(&self) -> TextureFormat {
+        match self {
+            NormalMapFormat::Rgba8 => TextureFormat::Rgba8Unorm,
+            NormalMapFormat::Rgba16 => TextureFormat::Rgba16Unorm,
+        }
+    }
+
+    /// Read back the 0..1 RGBA value `write_pixel` stored at the given pixel
+    pub fn read_pixel(&self, data: &[u8], pixel: usize) -> Vec4 {
+        match self {
+            NormalMapFormat::Rgba8 => {
+                let idx = pixel * 4;
+                Vec4::from_array(std::array::from_fn(|c| data[idx + c] as f32 / 255.0))
+            }
+            NormalMapFormat::Rgba16 => {
+                let idx = pixel * 8;
+                Vec4::from_array(std::array::from_fn(|c| {
+                    let bytes = [data[idx + c * 2], data[idx + c * 2 + 1]];
+                    u16::from_le_bytes(bytes) as f32 / 65535.0
+                }))
+            }
+        }
+    }
+
+    /// Write a 0..1 RGBA value into `out` at the given pixel
+    /// `dither` is the pixel's `DitherMode::threshold`; 16-bit output is fine enough to ignore it.
+    fn write_pixel(&self, out: &mut [u8], pixel: usize, rgba: Vec4, dither: f32) {
+        match self {
+            NormalMapFormat::Rgba8 => {
+                let idx = pixel * 4;
+                for c in 0..4 {
+                    out[idx + c] = quantize_u8(rgba[c].clamp(0.0, 1.0) * 255.0, dither);
+                }
+            }
+            NormalMapFormat::Rgba16 => {
+                let idx = pixel * 8;
+                for c in 0..4 {
+                    let value = (rgba[c].clamp(0.0, 1.0) * 65535.0) as u16;
+                    out[idx + c * 2..idx + c * 2 + 2].copy_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// Dithering applied when quantizing map values to 8 bits
+/// Truncating smooth gradients to 256 steps leaves visible bands, which on the position map
+/// turn into stepped lighting. Dithering adds a per-pixel offset below one step before
+/// truncating, so neighbouring pixels average out to the unquantized value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DitherMode {
+    /// Plain truncation
+    #[default]
+    Off,
+    /// 4x4 Bayer matrix: regular pattern, cheapest
+    Ordered,
+    /// Interleaved gradient noise: a procedural stand-in for a blue-noise texture, with
+    /// mostly high-frequency error that reads as fine grain rather than a pattern
+    BlueNoise,
+}
+
+/// 4x4 Bayer matrix, row-major, with values 0..16
+const BAYER_4X4: [u8; 16] = [0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5];
+
+impl DitherMode {
+    /// Offset in 0..1 (of one 8-bit step) added before truncating the pixel's values
+    pub fn threshold(&self, px: u32, py: u32) -> f32 {
+        match self {
+            DitherMode::Off => 0.0,
+            DitherMode::Ordered => {
+                let index = (py % 4) * 4 + px % 4;
+                (BAYER_4X4[index as usize] as f32 + 0.5) / 16.0
+            }
+            DitherMode::BlueNoise => {
+                let gradient = 0.06711056 * px as f32 + 0.00583715 * py as f32;
+                (52.982918 * gradient.fract()).fract()
+            }
+        }
+    }
+
+    /// Next mode, for toggling through them from a key
+    pub fn next(&self) -> Self {
+        match self {
+            DitherMode::Off => DitherMode::Ordered,
+            DitherMode::Ordered => DitherMode::BlueNoise,
+            DitherMode::BlueNoise => DitherMode::Off,
+        }
+    }
+}
+
+/// Quantize a value already scaled to 0..255, offset by a dither threshold
+fn quantize_u8(scaled: f32, dither: f32) -> u8 {
+    // Float-to-int casts saturate, so overshooting 255 is safe
+    (scaled + dither) as u8
+}
+
+/// Value written into one channel of the normal map
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChannelSource {
+    /// Normal X component
+    X,
+    /// Normal Y component
+    Y,
+    /// Normal Z component
+    Z,
+    /// Normalized height of the hit point within the volume
+    Height,
+    /// Fixed value in 0..1
+    Constant(f32),
+}
+
+/// Which computed value goes into each RGBA channel of the normal map
+/// Different tools disagree on channel order; the default is RGB = XYZ, A = 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelLayout {
+    pub r: ChannelSource,
+    pub g: ChannelSource,
+    pub b: ChannelSource,
+    pub a: ChannelSource,
+}
+
+impl Default for ChannelLayout {
+    fn default() -> Self {
+        Self {
+            r: ChannelSource::X,
+            g: ChannelSource::Y,
+            b: ChannelSource::Z,
+            a: ChannelSource::Constant(1.0),
+        }
+    }
+}
+
+impl ChannelLayout {
+    /// Build the 0..1 RGBA value for a world-space normal and normalized height
+    fn apply(&self, normal: Vec3, height: f32) -> Vec4 {
+        let encoded = normal.mul_add(Vec3::splat(0.5), Vec3::splat(0.5));
+        let pick = |source: ChannelSource| match source {
+            ChannelSource::X => encoded.x,
+            ChannelSource::Y => encoded.y,
+            ChannelSource::Z => encoded.z,
+            ChannelSource::Height => height,
+            ChannelSource::Constant(value) => value,
+        };
+        Vec4::new(pick(self.r), pick(self.g), pick(self.b), pick(self.a))
+    }
+
+    /// Recover the normal from an RGBA value built by `apply`
+    /// `None` if the layout doesn't store all three components.
+    pub fn decode_normal(&self, rgba: Vec4) -> Option<Vec3> {
+        let mut encoded = [None; 3];
+        for (source, value) in [self.r, self.g, self.b, self.a].into_iter().zip(rgba.to_array()) {
+            match source {
+                ChannelSource::X => encoded[0] = Some(value),
+                ChannelSource::Y => encoded[1] = Some(value),
+                ChannelSource::Z => encoded[2] = Some(value),
+                ChannelSource::Height | ChannelSource::Constant(_) => {}
+            }
+        }
+        let [Some(x), Some(y), Some(z)] = encoded else {
+            return None;
+        };
+        Some(Vec3::new(x, y, z).mul_add(Vec3::splat(2.0), Vec3::NEG_ONE))
+    }
+}
+
+/// Options controlling how the normal map is written
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NormalMapOptions {
+    pub format: NormalMapFormat,
+    pub channel_layout: ChannelLayout,
+}
+
 /// Result of rendering a volume to 2D textures
 pub struct VolumeRenderResult {
     pub position_map: Vec<u8>,
     pub normal_map: Vec<u8>,
     pub diffuse_map: Vec<u8>,
+    /// Encoding of `normal_map`
+    pub normal_format: NormalMapFormat,
     pub width: u32,
     pub height: u32,
 }
 
+impl VolumeRenderResult {
+    /// View-space normal at a pixel, decoded from the normal map
+    /// `None` where the ray missed, or if `layout` (the one the map was rendered with)
+    /// doesn't store the full normal.
+    pub fn normal_at(&self, px: u32, py: u32, layout: &ChannelLayout) -> Option<Vec3> {
+        let pixel = (py * self.width + px) as usize;
+        if self.position_map[pixel * 4 + 3] == 0 {
+            return None;
+        }
+        layout.decode_normal(self.normal_format.read_pixel(&self.normal_map, pixel))
+    }
+}
+
+/// Volumetric self-shadowing baked into the diffuse map
+/// A cone is marched from each surface hit toward the light, and the density it passes through
+/// darkens the hit. Unlike the screen-space approximation in the shader this sees occluders
+/// hidden behind the surface, at the cost of a second march per pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfShadow {
+    /// Direction toward the light in view space: x right, y down the map, -z toward the viewer
+    pub light_dir: Vec3,
+    /// How much a fully occluded hit is darkened; 0 skips the shadow march entirely
+    pub strength: f32,
+    /// Cone radius gained per voxel travelled; 0 gives hard-edged shadows
+    pub softness: f32,
+}
+
+impl Default for SelfShadow {
+    fn default() -> Self {
+        Self {
+            light_dir: Vec3::new(-0.5, -1.0, -1.0),
+            strength: 0.0,
+            softness: 0.1,
+        }
+    }
+}
+
+/// Distance in voxels the shadow cone starts from the surface, so a hit doesn't shadow itself
+const SELF_SHADOW_BIAS: f32 = 1.5;
+
+/// Settings for rendering a volume to 2D maps
+/// New renderer features add a field here (with a default) instead of a positional argument.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderSettings {
+    /// Width and height of the output maps in pixels
+    /// Pixels are square in volume space: the longer side spans the volume's largest axis.
+    pub output_size: UVec2,
+    /// Euler rotation (radians, XYZ order) applied to the volume
+    pub rotation: Vec3,
+    /// Camera placed in volume space, overriding `rotation`; see `camera_rays`
+    pub camera: Option<Transform>,
+    /// Density above which a voxel counts as solid
+    pub threshold: f32,
+    /// Raymarch step in voxels
+    pub step_size: f32,
+    /// Normal map precision and channel layout
+    pub normal: NormalMapOptions,
+    /// Interpolation for density lookups and normals
+    pub filter: SampleFilter,
+    /// Dithering for the 8-bit maps
+    pub dither: DitherMode,
+    /// Shadows the volume casts onto itself, baked into the diffuse map
+    pub self_shadow: SelfShadow,
+    /// Rays per pixel along each axis; above 1 the maps are rendered larger and box-filtered down
+    pub supersample: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            output_size: UVec2::splat(256),
+            rotation: Vec3::ZERO,
+            camera: None,
+            threshold: 0.3,
+            step_size: 0.75,
+            normal: NormalMapOptions::default(),
+            filter: SampleFilter::default(),
+            dither: DitherMode::default(),
+            self_shadow: SelfShadow::default(),
+            supersample: 1,
+        }
+    }
+}
+
+/// One-knob speed/quality trade-off for the CPU renderer
+/// Sets the march step, sample filter and supersampling together. The step count always
+/// follows from `step_size`, so every preset marches through the whole volume.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderQuality {
+    /// Coarse steps and hard voxels, for fast previews while editing
+    Low,
+    /// The `RenderSettings` defaults
+    #[default]
+    Medium,
+    /// Finer steps with trilinear densities and normals
+    High,
+    /// Tricubic normals and 2x2 supersampling, for final renders
+    Ultra,
+}
+
+impl RenderQuality {
+    /// `settings` with this preset's tuning params applied
+    pub fn apply(&self, settings: RenderSettings) -> RenderSettings {
+        let (step_size, filter, supersample) = match self {
+            RenderQuality::Low => (1.5, SampleFilter::Nearest, 1),
+            RenderQuality::Medium => (0.75, SampleFilter::Nearest, 1),
+            RenderQuality::High => (0.5, SampleFilter::Trilinear, 1),
+            RenderQuality::Ultra => (0.25, SampleFilter::Tricubic, 2),
+        };
+        RenderSettings {
+            step_size,
+            filter,
+            supersample,
+            ..settings
+        }
+    }
+
+    /// Next preset, wrapping from `Ultra` back to `Low`
+    pub fn next(&self) -> Self {
+        match self {
+            RenderQuality::Low => RenderQuality::Medium,
+            RenderQuality::Medium => RenderQuality::High,
+            RenderQuality::High => RenderQuality::Ultra,
+            RenderQuality::Ultra => RenderQuality::Low,
+        }
+    }
+}
+
+/// Gradient magnitude below which a hit's normal is treated as unreliable
+const SILHOUETTE_GRADIENT_EPSILON: f32 = 0.05;
+
+/// Normal for a surface hit that never faces away from the viewer
+/// Near the silhouette the central-difference gradient is tiny or flips backwards, which
+/// shows up as a dark fringe at grazing light angles. Back-facing normals are projected onto
+/// the view plane (so the edge reads as a rim), and degenerate ones fall back to the view direction.
+fn silhouette_safe_normal(gradient: Vec3, to_viewer: Vec3) -> Vec3 {
+    if gradient.length() < SILHOUETTE_GRADIENT_EPSILON {
+        return to_viewer;
+    }
+
+    let normal = gradient.normalize();
+    let facing = normal.dot(to_viewer);
+    if facing >= 0.0 {
+        return normal;
+    }
+
+    (normal - to_viewer * facing).try_normalize().unwrap_or(to_viewer)
+}
+
+/// Wall-clock times of repeated renders, in milliseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderTimings {
+    pub runs: usize,
+    pub mean_ms: f32,
+    pub median_ms: f32,
+    pub min_ms: f32,
+    pub max_ms: f32,
+}
+
+impl RenderTimings {
+    /// Summarize per-run times; the median of an even count is the mean of the middle two
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let mid = sorted.len() / 2;
+        let median_ms = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) * 0.5
+        } else {
+            sorted[mid]
+        };
+
+        Self {
+            runs: sorted.len(),
+            mean_ms: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            median_ms,
+            min_ms: sorted[0],
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Time `runs` calls to `render_volume_to_maps`, after one untimed warm-up render
+pub fn time_render_volume_to_maps(volume: &Volume, output_size: UVec2, rotation: Vec3, runs: usize) -> RenderTimings {
+    render_volume_to_maps(volume, output_size, rotation);
+    let samples: Vec<f32> = (0..runs)
+        .map(|_| {
+            let start = std::time::Instant::now();
+            render_volume_to_maps(volume, output_size, rotation);
+            start.elapsed().as_secs_f32() * 1000.0
+        })
+        .collect();
+    RenderTimings::from_samples(&samples)
+}
+
 /// Render a volume to 2D position, normal, and diffuse maps using orthographic projection
-pub fn render_volume_to_maps(volume: &Volume, output_size: u32, rotation: Vec3) -> VolumeRenderResult {
-    let width = output_size;
-    let height = output_size;
+pub fn render_volume_to_maps(volume: &Volume, output_size: UVec2, rotation: Vec3) -> VolumeRenderResult {
+    render_volume_with_settings(
+        volume,
+        &RenderSettings {
+            output_size,
+            rotation,
+            ..default()
+        },
+    )
+}
+
+/// Render a volume to maps using the given settings
+pub fn render_volume_with_settings(volume: &Volume, settings: &RenderSettings) -> VolumeRenderResult {
+    if settings.supersample > 1 {
+        let full = render_volume_with_settings(
+            volume,
+            &RenderSettings {
+                output_size: settings.output_size * settings.supersample,
+                supersample: 1,
+                ..*settings
+            },
+        );
+        return downsample_render(&full, settings.supersample);
+    }
+
+    let normal_format = settings.normal.format;
+    let width = settings.output_size.x;
+    let height = settings.output_size.y;
     let pixel_count = (width * height) as usize;
     
-    let mut position_map = vec![0u8; pixel_count * 4]; // RGBA
-    let mut normal_map = vec![0u8; pixel_count * 4];   // RGBA
-    let mut diffuse_map = vec![0u8; pixel_count * 4];  // RGBA
-    
-    let vol_size = volume.dimensions.x as f32;
-    let threshold = 0.3; // Density threshold for "solid"
-    let center = vol_size / 2.0;
+    let mut result = VolumeRenderResult {
+        position_map: vec![0u8; pixel_count * 4], // RGBA
+        normal_map: vec![0u8; pixel_count * normal_format.bytes_per_pixel()],
+        diffuse_map: vec![0u8; pixel_count * 4], // RGBA
+        normal_format,
+        width,
+        height,
+    };
     
-    // Create rotation matrix from Euler angles (in radians)
-    let rotation_matrix = create_rotation_matrix(rotation);
-    let inverse_rotation = create_rotation_matrix(-rotation);
+    // Debug builds report bad densities up front; the marcher reports the pixels they break
+    if cfg!(debug_assertions) {
+        warn_on_error(volume.validate());
+    }
+
+    let marcher = PixelMarcher::new(volume, settings);
+    for py in 0..height {
+        for px in 0..width {
+            marcher.shade_pixel(volume, px, py, &mut result);
+        }
+    }
     
-    // Orthographic projection: shoot rays from front (Z+) toward back (Z-)
+    result
+}
+
+/// Log a failed validation without interrupting the render
+fn warn_on_error(result: Result<(), String>) {
+    if let Err(err) = result {
+        warn!("Volume render: {}", err);
+    }
+}
+
+/// Box-filter a render down by `factor` along each axis
+/// Position, normal and diffuse colors average only the samples that hit the volume, so edges
+/// don't blend toward the empty background. Diffuse alpha becomes the hit coverage, which
+/// antialiases the silhouette; position alpha still marks any hit.
+fn downsample_render(full: &VolumeRenderResult, factor: u32) -> VolumeRenderResult {
+    let width = full.width / factor;
+    let height = full.height / factor;
+    let normal_format = full.normal_format;
+    let pixel_count = (width * height) as usize;
+    let mut result = VolumeRenderResult {
+        position_map: vec![0u8; pixel_count * 4],
+        normal_map: vec![0u8; pixel_count * normal_format.bytes_per_pixel()],
+        diffuse_map: vec![0u8; pixel_count * 4],
+        normal_format,
+        width,
+        height,
+    };
+
     for py in 0..height {
         for px in 0..width {
-            let pixel_idx = (py * width + px) as usize * 4;
-            
-            // Map pixel to volume coordinates (XY plane, centered)
-            let screen_x = (px as f32 / width as f32 - 0.5) * vol_size;
-            let screen_y = (py as f32 / height as f32 - 0.5) * vol_size;
+            let mut hits = 0;
+            let mut position = Vec3::ZERO;
+            let mut diffuse = Vec3::ZERO;
+            let mut normal = Vec4::ZERO;
+            for sy in py * factor..(py + 1) * factor {
+                for sx in px * factor..(px + 1) * factor {
+                    let sample = (sy * full.width + sx) as usize;
+                    if full.position_map[sample * 4 + 3] == 0 {
+                        continue;
+                    }
+                    let rgb = |map: &[u8]| {
+                        Vec3::new(map[sample * 4] as f32, map[sample * 4 + 1] as f32, map[sample * 4 + 2] as f32)
+                    };
+                    hits += 1;
+                    position += rgb(&full.position_map);
+                    diffuse += rgb(&full.diffuse_map);
+                    normal += normal_format.read_pixel(&full.normal_map, sample);
+                }
+            }
+            if hits == 0 {
+                continue;
+            }
+
+            let pixel = (py * width + px) as usize;
+            let inv_hits = 1.0 / hits as f32;
+            let coverage = hits as f32 / (factor * factor) as f32;
+            let position = (position * inv_hits).round();
+            let diffuse = (diffuse * inv_hits).round();
+            result.position_map[pixel * 4..pixel * 4 + 4]
+                .copy_from_slice(&[position.x as u8, position.y as u8, position.z as u8, 255]);
+            result.diffuse_map[pixel * 4..pixel * 4 + 4].copy_from_slice(&[
+                diffuse.x as u8,
+                diffuse.y as u8,
+                diffuse.z as u8,
+                (coverage * 255.0).round() as u8,
+            ]);
+            // Rounding to nearest rather than dithering: the average is already smooth
+            normal_format.write_pixel(&mut result.normal_map, pixel, normal * inv_hits, 0.5);
+        }
+    }
+
+    result
+}
+
+/// Re-render only the pixels whose rays pass through a changed region of the volume
+/// `dirty_min`/`dirty_max` are the edited region's corners in voxel coordinates, and `result`
+/// must come from a render of the same volume with the same `settings`. Much cheaper than a
+/// full render for brush-sized edits; the patched maps match a full re-render exactly.
+/// With self-shadowing on, an edit can shadow pixels anywhere, so every pixel is re-rendered.
+/// Supersampled renders are redone in full, since the larger render isn't kept around.
+pub fn update_volume_render_region(
+    volume: &Volume,
+    settings: &RenderSettings,
+    dirty_min: Vec3,
+    dirty_max: Vec3,
+    result: &mut VolumeRenderResult,
+) {
+    if settings.supersample > 1 {
+        *result = render_volume_with_settings(volume, settings);
+        return;
+    }
+
+    let marcher = PixelMarcher::new(volume, settings);
+    if settings.self_shadow.strength > 0.0 {
+        for py in 0..result.height {
+            for px in 0..result.width {
+                marcher.shade_pixel(volume, px, py, result);
+            }
+        }
+        return;
+    }
+
+    let Some((min, max)) = marcher.project_region(dirty_min, dirty_max, result.width, result.height) else {
+        return; // Region is entirely off screen
+    };
+    
+    for py in min.y..=max.y {
+        for px in min.x..=max.x {
+            marcher.shade_pixel(volume, px, py, result);
+        }
+    }
+}
+
+/// Per-render constants for marching one orthographic ray per output pixel
+struct PixelMarcher {
+    normal_options: NormalMapOptions,
+    filter: SampleFilter,
+    dither: DitherMode,
+    self_shadow: SelfShadow,
+    /// `self_shadow.light_dir` in volume space, zero when shadows are off
+    shadow_dir: Vec3,
+    threshold: f32,
+    step_size: f32,
+    max_steps: usize,
+    dims: UVec3,
+    dims_f: Vec3,
+    extent: f32,
+    /// Volume-space start of the ray through the screen's center
+    origin: Vec3,
+    rotation_matrix: Mat3,
+    inverse_rotation: Mat3,
+}
+
+impl PixelMarcher {
+    fn new(volume: &Volume, settings: &RenderSettings) -> Self {
+        // Volumes may be non-cubic: the screen covers the largest axis so nothing is stretched,
+        // while bounds checks and centering use each axis' own dimension
+        let dims = volume.dimensions;
+        let dims_f = dims.as_vec3();
+        let extent = dims_f.max_element();
+        let camera = settings.camera.unwrap_or_else(|| euler_camera(settings.rotation, extent));
+        let (inverse_rotation, origin) = camera_rays(&camera, dims_f);
+        let rotation_matrix = inverse_rotation.transpose();
+        let shadow_dir = if settings.self_shadow.strength > 0.0 {
+            rotate_point(settings.self_shadow.light_dir.normalize_or_zero(), inverse_rotation)
+        } else {
+            Vec3::ZERO
+        };
+        
+        Self {
+            normal_options: settings.normal,
+            filter: settings.filter,
+            dither: settings.dither,
+            self_shadow: settings.self_shadow,
+            shadow_dir,
+            threshold: settings.threshold,
+            step_size: settings.step_size,
+            max_steps: (extent * 1.125 / settings.step_size) as usize, // Same march distance for any step size
+            dims,
+            dims_f,
+            extent,
+            origin,
+            rotation_matrix,
+            inverse_rotation,
+        }
+    }
+    
+    /// Fraction of the light reaching a surface hit through the cone traced toward it
+    fn light_transmittance(&self, volume: &Volume, hit_pos: Vec3) -> f32 {
+        if self.shadow_dir == Vec3::ZERO {
+            return 1.0;
+        }
+        
+        let (side, up) = self.shadow_dir.any_orthonormal_pair();
+        let max_distance = self.dims_f.length();
+        let mut occlusion = 0.0;
+        let mut t = SELF_SHADOW_BIAS;
+        while t < max_distance && occlusion < 0.99 {
+            let pos = hit_pos + self.shadow_dir * t;
+            if pos.cmplt(Vec3::ZERO).any() || pos.cmpge(self.dims_f).any() {
+                break;
+            }
             
-            // Raycast from front to back along Z axis
-            let mut hit = false;
-            let mut hit_pos = Vec3::ZERO;
-            let mut hit_voxel = UVec3::ZERO;
+            // Averaging density across the cone's footprint blurs occluders more the further
+            // they are from the hit, which is what softens the shadow's edge
+            let radius = t * self.self_shadow.softness;
+            let density = [Vec3::ZERO, side, -side, up, -up]
+                .iter()
+                .map(|offset| volume.sample(pos + *offset * radius - 0.5))
+                .sum::<f32>()
+                / 5.0;
+            let coverage = (density / self.threshold - 0.5).clamp(0.0, 1.0);
+            occlusion += (1.0 - occlusion) * coverage;
             
-            // Ray in screen space (before rotation)
-            let ray_start = Vec3::new(screen_x, screen_y, -vol_size);
-            let ray_dir = Vec3::new(0.0, 0.0, 1.0);
+            // Wide cones take proportionally longer steps, keeping soft shadows cheap
+            t += self.step_size.max(radius);
+        }
+        
+        1.0 - self.self_shadow.strength * occlusion
+    }
+    
+    /// Inclusive pixel rectangle covered by a voxel-space box under this render's rotation
+    fn project_region(&self, min: Vec3, max: Vec3, width: u32, height: u32) -> Option<(UVec2, UVec2)> {
+        // Gradients read one voxel past the edit, and filtered lookups reach further still
+        let reach = match self.filter {
+            SampleFilter::Nearest => 1.0,
+            SampleFilter::Trilinear => 2.0,
+            SampleFilter::Tricubic => 3.0,
+        };
+        let min = min - Vec3::splat(reach);
+        let max = max + Vec3::splat(reach);
+        // Rays map screen to volume through `inverse_rotation`; the forward rotation goes back
+        let to_screen = self.rotation_matrix;
+        
+        let mut screen_min = Vec2::splat(f32::MAX);
+        let mut screen_max = Vec2::splat(f32::MIN);
+        for corner in 0..8 {
+            let point = Vec3::new(
+                if corner & 1 == 0 { min.x } else { max.x },
+                if corner & 2 == 0 { min.y } else { max.y },
+                if corner & 4 == 0 { min.z } else { max.z },
+            );
+            let screen = rotate_point(point - self.origin, to_screen).truncate();
+            screen_min = screen_min.min(screen);
+            screen_max = screen_max.max(screen);
+        }
+        
+        // Inverse of the pixel -> screen mapping in `shade_pixel`, padded by a pixel for rounding
+        let size = Vec2::new(width as f32, height as f32);
+        let pixel_size = self.extent / size.max_element();
+        let pixel_min = (screen_min / pixel_size + size * 0.5).floor() - Vec2::ONE;
+        let pixel_max = (screen_max / pixel_size + size * 0.5).ceil() + Vec2::ONE;
+        if pixel_max.cmplt(Vec2::ZERO).any() || pixel_min.cmpge(size).any() {
+            return None;
+        }
+        
+        let last = size - Vec2::ONE;
+        Some((
+            pixel_min.clamp(Vec2::ZERO, last).as_uvec2(),
+            pixel_max.clamp(Vec2::ZERO, last).as_uvec2(),
+        ))
+    }
+    
+    /// March the ray for one pixel and write (or clear) it in every map
+    fn shade_pixel(&self, volume: &Volume, px: u32, py: u32, result: &mut VolumeRenderResult) {
+        let (width, height) = (result.width, result.height);
+        let dims = self.dims;
+        let dims_f = self.dims_f;
+        let extent = self.extent;
+        let pixel_idx = (py * width + px) as usize * 4;
+        
+        // Map pixel to volume coordinates (XY plane, centered)
+        // Square pixels: the longer output side spans the largest volume axis
+        let pixel_size = extent / width.max(height) as f32;
+        let screen_x = (px as f32 - width as f32 * 0.5) * pixel_size;
+        let screen_y = (py as f32 - height as f32 * 0.5) * pixel_size;
+        
+        // Raycast from front to back along Z axis
+        let mut hit = false;
+        let mut hit_pos = Vec3::ZERO;
+        let mut hit_voxel = UVec3::ZERO;
+        
+        // Ray in screen space (before rotation)
+        let ray_start = Vec3::new(screen_x, screen_y, 0.0);
+        let ray_dir = Vec3::new(0.0, 0.0, 1.0);
+        
+        for step in 0..self.max_steps {
+            let t = step as f32 * self.step_size;
+            let ray_pos = ray_start + ray_dir * t;
             
-            // March along the ray with adaptive step size
-            let max_steps = (vol_size * 1.5) as usize; // Reduced from 2.0
-            let step_size = 0.75; // Increased from 0.5 for faster marching
+            // Rotate ray position to volume space
+            let rotated_pos = rotate_point(ray_pos, self.inverse_rotation) + self.origin;
             
-            let center_vec = Vec3::splat(center);
-            let vol_size_u = vol_size as u32;
+            // Early exit with combined bounds check
+            let in_bounds = rotated_pos.x >= 0.0 && rotated_pos.x < dims_f.x &&
+                            rotated_pos.y >= 0.0 && rotated_pos.y < dims_f.y &&
+                            rotated_pos.z >= 0.0 && rotated_pos.z < dims_f.z;
             
-            for step in 0..max_steps {
-                let t = step as f32 * step_size;
-                let ray_pos = ray_start + ray_dir * t;
-                
-                // Rotate ray position to volume space
-                let rotated_pos = rotate_point(ray_pos, inverse_rotation) + center_vec;
-                
-                // Early exit with combined bounds check
-                let in_bounds = rotated_pos.x >= 0.0 && rotated_pos.x < vol_size &&
-                                rotated_pos.y >= 0.0 && rotated_pos.y < vol_size &&
-                                rotated_pos.z >= 0.0 && rotated_pos.z < vol_size;
-                
-                if !in_bounds {
-                    continue;
-                }
-                
-                let vx = rotated_pos.x as u32;
-                let vy = rotated_pos.y as u32;
-                let vz = rotated_pos.z as u32;
-                
-                // Inline bounds check to avoid redundant checks in volume.get()
-                let density = if vx < vol_size_u && vy < vol_size_u && vz < vol_size_u {
-                    let idx = (vz * volume.dimensions.x * volume.dimensions.y + vy * volume.dimensions.x + vx) as usize;
-                    volume.data[idx]
-                } else {
-                    0.0
-                };
-                
-                if density > threshold {
-                    // Hit! Record the position
-                    hit = true;
-                    hit_pos = rotated_pos;
-                    hit_voxel = UVec3::new(vx, vy, vz);
-                    break;
-                }
+            if !in_bounds {
+                continue;
             }
             
+            let vx = rotated_pos.x as u32;
+            let vy = rotated_pos.y as u32;
+            let vz = rotated_pos.z as u32;
             
-            // Branchless output: multiply values by hit flag
-            let hit_u8 = hit as u8 * 255;
+            // Inline bounds check to avoid redundant checks in volume.get()
+            let density = if self.filter != SampleFilter::Nearest {
+                // Voxel centres sit at +0.5 in march space
+                volume.sample_filtered(rotated_pos - 0.5, self.filter)
+            } else if vx < dims.x && vy < dims.y && vz < dims.z {
+                let idx = (vz * dims.x * dims.y + vy * dims.x + vx) as usize;
+                volume.data[idx]
+            } else {
+                0.0
+            };
             
-            if hit {
-                // Position map: encode world position as RGB
-                // Normalize to 0-255 range based on volume size
-                let inv_vol_size = 1.0 / vol_size;
-                position_map[pixel_idx] = (hit_pos.x * inv_vol_size * 255.0) as u8;
-                position_map[pixel_idx + 1] = (hit_pos.y * inv_vol_size * 255.0) as u8;
-                position_map[pixel_idx + 2] = (hit_pos.z * inv_vol_size * 255.0) as u8;
-                
-                // Normal map: calculate gradient in volume space, then rotate to world space
-                let normal_volume = volume.gradient(hit_voxel.x, hit_voxel.y, hit_voxel.z);
-                let normal_world = rotate_point(normal_volume, rotation_matrix);
-                
-                // Map from -1..1 to 0..255
-                normal_map[pixel_idx] = (normal_world.x.mul_add(0.5, 0.5) * 255.0) as u8;
-                normal_map[pixel_idx + 1] = (normal_world.y.mul_add(0.5, 0.5) * 255.0) as u8;
-                normal_map[pixel_idx + 2] = (normal_world.z.mul_add(0.5, 0.5) * 255.0) as u8;
-                
-                // Diffuse map: simple gray rock color with slight variation based on position
-                let variation = hit_pos.y * inv_vol_size * 0.2; // Height-based variation
-                let base_color = 0.5 + variation;
-                diffuse_map[pixel_idx] = (base_color * 180.0) as u8;
-                diffuse_map[pixel_idx + 1] = (base_color * 170.0) as u8;
-                diffuse_map[pixel_idx + 2] = (base_color * 160.0) as u8;
+            if density > self.threshold {
+                // Hit! Record the position
+                hit = true;
+                hit_pos = rotated_pos;
+                hit_voxel = UVec3::new(vx, vy, vz);
+                break;
             }
-            
-            // Set alpha channels (0 for no hit, 255 for hit)
-            position_map[pixel_idx + 3] = hit_u8;
-            normal_map[pixel_idx + 3] = hit_u8;
-            diffuse_map[pixel_idx + 3] = hit_u8;
         }
+        
+        if !hit {
+            // Clear the pixel so patched maps match a fresh render
+            let normal_bytes = result.normal_format.bytes_per_pixel();
+            let normal_idx = pixel_idx / 4 * normal_bytes;
+            result.position_map[pixel_idx..pixel_idx + 4].fill(0);
+            result.diffuse_map[pixel_idx..pixel_idx + 4].fill(0);
+            result.normal_map[normal_idx..normal_idx + normal_bytes].fill(0);
+            return;
+        }
+        
+        // Position map: encode world position as RGB
+        // Normalize to 0-255 range based on the largest volume axis
+        let inv_vol_size = 1.0 / extent;
+        let dither = self.dither.threshold(px, py);
+        let position_map = &mut result.position_map;
+        position_map[pixel_idx] = quantize_u8(hit_pos.x * inv_vol_size * 255.0, dither);
+        position_map[pixel_idx + 1] = quantize_u8(hit_pos.y * inv_vol_size * 255.0, dither);
+        position_map[pixel_idx + 2] = quantize_u8(hit_pos.z * inv_vol_size * 255.0, dither);
+        position_map[pixel_idx + 3] = 255;
+        
+        // Normal map: calculate gradient in volume space, then rotate to world space
+        let gradient_volume = match self.filter {
+            SampleFilter::Nearest => volume.gradient_raw(hit_voxel.x, hit_voxel.y, hit_voxel.z),
+            filter => volume.gradient_filtered(hit_pos - 0.5, filter),
+        };
+        let gradient_world = rotate_point(gradient_volume, self.rotation_matrix);
+        let normal_world = silhouette_safe_normal(gradient_world, -ray_dir);
+        if cfg!(debug_assertions) && !normal_world.is_finite() {
+            warn!(
+                "Volume render: non-finite normal at pixel ({}, {}) from voxel ({}, {}, {})",
+                px, py, hit_voxel.x, hit_voxel.y, hit_voxel.z
+            );
+        }
+        
+        // Map from -1..1 to 0..1 and arrange channels per the requested layout
+        let height = hit_pos.y * inv_vol_size;
+        self.normal_options.format.write_pixel(
+            &mut result.normal_map,
+            pixel_idx / 4,
+            self.normal_options.channel_layout.apply(normal_world, height),
+            dither,
+        );
+        
+        // Diffuse map: material color with slight variation based on position
+        let variation = hit_pos.y * inv_vol_size * 0.2; // Height-based variation
+        let base_color = (0.5 + variation) * self.light_transmittance(volume, hit_pos);
+        let material = volume.get_material(hit_voxel.x, hit_voxel.y, hit_voxel.z) as usize;
+        let color = VOLUME_MATERIAL_COLORS.get(material).unwrap_or(&VOLUME_MATERIAL_COLORS[0]);
+        let diffuse_map = &mut result.diffuse_map;
+        diffuse_map[pixel_idx] = quantize_u8(base_color * color[0] as f32, dither);
+        diffuse_map[pixel_idx + 1] = quantize_u8(base_color * color[1] as f32, dither);
+        diffuse_map[pixel_idx + 2] = quantize_u8(base_color * color[2] as f32, dither);
+        diffuse_map[pixel_idx + 3] = 255;
     }
-    
-    VolumeRenderResult {
-        position_map,
-        normal_map,
-        diffuse_map,
-        width,
-        height,
+}
+
+/// Bake a full turn of the volume around its Y axis into a horizontal strip of diffuse frames
+/// Frame `i` turns the volume `i / frames` of a turn: added to `settings.rotation`, or, when
+/// `settings.camera` is set, by orbiting that camera the other way around the volume's center.
+/// `frame_size` overrides `settings.output_size`. Play it back by stepping a sprite's rect
+/// along the strip.
+pub fn bake_turntable_atlas(
+    volume: &Volume,
+    frames: u32,
+    frame_size: u32,
+    settings: &RenderSettings,
+) -> Image {
+    let frames = frames.max(1);
+    let atlas_width = frames * frame_size;
+    let mut atlas = vec![0u8; (atlas_width * frame_size * 4) as usize];
+
+    for frame in 0..frames {
+        let angle = std::f32::consts::TAU * frame as f32 / frames as f32;
+        let mut camera = settings.camera;
+        if let Some(camera) = camera.as_mut() {
+            camera.rotate_around(Vec3::ZERO, Quat::from_rotation_y(-angle));
+        }
+        let result = render_volume_with_settings(
+            volume,
+            &RenderSettings {
+                output_size: UVec2::splat(frame_size),
+                rotation: settings.rotation + Vec3::new(0.0, angle, 0.0),
+                camera,
+                ..*settings
+            },
+        );
+
+        // Copy each row of the frame into its slot in the strip
+        let row_bytes = (frame_size * 4) as usize;
+        for row in 0..frame_size {
+            let src = (row * frame_size * 4) as usize;
+            let dst = ((row * atlas_width + frame * frame_size) * 4) as usize;
+            atlas[dst..dst + row_bytes].copy_from_slice(&result.diffuse_map[src..src + row_bytes]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: atlas_width,
+            height: frame_size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        atlas,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Render one Z slice of the volume as a grayscale density image (white = solid)
+/// `z` is clamped to the volume depth. Uses nearest sampling so voxels stay crisp when scaled up.
+pub fn volume_slice_to_image(volume: &Volume, z: u32) -> Image {
+    let dims = volume.dimensions;
+    let z = z.min(dims.z.saturating_sub(1));
+    let mut pixels = Vec::with_capacity((dims.x * dims.y * 4) as usize);
+
+    // Flip rows so +Y points up on screen
+    for y in (0..dims.y).rev() {
+        for x in 0..dims.x {
+            let value = (volume.get(x, y, z).clamp(0.0, 1.0) * 255.0) as u8;
+            pixels.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: dims.x,
+            height: dims.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Forward and inverse rotation for Euler angles, shared by the CPU and GPU renderers
+/// The forward matrix turns volume-space normals to view space, the inverse takes rays from
+/// view space into the volume. Negating the angles is not an inverse once more than one axis
+/// is rotated (the order flips), so the inverse is the forward matrix's transpose.
+pub fn rotation_matrices(rotation: Vec3) -> (Mat3, Mat3) {
+    let forward = create_rotation_matrix(rotation);
+    (forward, forward.transpose())
+}
+
+/// Screen space has +Y down the image and +Z along the ray: a half turn about X from a camera's axes
+const CAMERA_TO_SCREEN: Mat3 = Mat3::from_diagonal(Vec3::new(1.0, -1.0, -1.0));
+
+/// Screen-to-volume rotation and the volume-space point the central ray starts from
+/// `camera` is relative to the volume's center in voxels and, like a Bevy camera, looks down its
+/// local -Z with +Y up the image. Rays start on the camera's plane and march away from it. Scale
+/// is ignored, since pixel size follows the volume's extent.
+pub fn camera_rays(camera: &Transform, dims: Vec3) -> (Mat3, Vec3) {
+    let screen_to_volume = Mat3::from_quat(camera.rotation) * CAMERA_TO_SCREEN;
+    (screen_to_volume, dims / 2.0 + camera.translation)
+}
+
+/// Camera showing the volume under an Euler `rotation`, backed off `extent` voxels from its center
+pub fn euler_camera(rotation: Vec3, extent: f32) -> Transform {
+    let (_, inverse) = rotation_matrices(rotation);
+    Transform {
+        translation: inverse * Vec3::new(0.0, 0.0, -extent),
+        rotation: Quat::from_mat3(&(inverse * CAMERA_TO_SCREEN)),
+        ..default()
     }
 }
 
@@ -363,3 +1654,601 @@ fn create_rotation_matrix(rotation: Vec3) -> Mat3 {
 fn rotate_point(point: Vec3, rotation_matrix: Mat3) -> Vec3 {
     rotation_matrix * point
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pixel bounding box (min_x, min_y, max_x, max_y) of all hit pixels
+    fn coverage_bounds(result: &VolumeRenderResult) -> (u32, u32, u32, u32) {
+        let mut bounds = (u32::MAX, u32::MAX, 0, 0);
+        for py in 0..result.height {
+            for px in 0..result.width {
+                let idx = ((py * result.width + px) * 4 + 3) as usize;
+                if result.diffuse_map[idx] > 0 {
+                    bounds.0 = bounds.0.min(px);
+                    bounds.1 = bounds.1.min(py);
+                    bounds.2 = bounds.2.max(px);
+                    bounds.3 = bounds.3.max(py);
+                }
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn test_from_sdf_falloff_spans_one_voxel_each_side() {
+        // A plane with distance p.x / 2 samples the falloff every half voxel of distance
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| p.x * 0.5);
+        let densities: Vec<f32> = (13..=19).map(|x| volume.get(x, 16, 16)).collect();
+        let expected = [1.0, 1.0, 0.84375, 0.5, 0.15625, 0.0, 0.0];
+        for (density, expected) in densities.iter().zip(expected) {
+            assert!((density - expected).abs() < 1e-5, "{densities:?}");
+        }
+    }
+
+    /// Density `from_sdf` gives a voxel at `offset` from the volume's center
+    fn sdf_density(volume: &Volume, offset: IVec3) -> f32 {
+        let voxel = (volume.dimensions / 2).as_ivec3() + offset;
+        volume.get(voxel.x as u32, voxel.y as u32, voxel.z as u32)
+    }
+
+    #[test]
+    fn test_from_sdf_sphere() {
+        // Sphere of radius 24
+        let sphere = Volume::from_sdf(UVec3::splat(64), |p| p.length() - 24.0);
+
+        // Solid inside, half density on the surface, empty a voxel past it
+        assert_eq!(sdf_density(&sphere, IVec3::ZERO), 1.0);
+        assert_eq!(sdf_density(&sphere, IVec3::new(-23, 0, 0)), 1.0);
+        assert_eq!(sdf_density(&sphere, IVec3::new(0, 24, 0)), 0.5);
+        assert_eq!(sdf_density(&sphere, IVec3::new(0, 0, 25)), 0.0);
+
+        // A voxel just off the surface (distance 24.04) is partially filled
+        let edge = sdf_density(&sphere, IVec3::new(17, 17, 0));
+        assert!(edge > 0.4 && edge < 0.5, "edge density {edge}");
+    }
+
+    #[test]
+    fn test_from_sdf_box() {
+        // Box with half extents 20 x 10 x 16
+        let half = Vec3::new(20.0, 10.0, 16.0);
+        let cube = Volume::from_sdf(UVec3::splat(64), |p| {
+            let q = p.abs() - half;
+            q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+        });
+
+        assert_eq!(sdf_density(&cube, IVec3::new(19, 9, 15)), 1.0);
+        assert_eq!(sdf_density(&cube, IVec3::new(20, 0, 0)), 0.5);
+        assert_eq!(sdf_density(&cube, IVec3::new(21, 0, 0)), 0.0);
+        assert_eq!(sdf_density(&cube, IVec3::new(0, 11, 0)), 0.0);
+
+        // The corner is on the surface too
+        assert_eq!(sdf_density(&cube, IVec3::new(20, 10, 16)), 0.5);
+    }
+
+    #[test]
+    fn test_from_sdf_torus() {
+        // Torus lying in the XZ plane (major radius 20, minor radius 6)
+        let torus = Volume::from_sdf(UVec3::splat(64), |p| {
+            Vec2::new(p.xz().length() - 20.0, p.y).length() - 6.0
+        });
+
+        // Solid around the ring, empty in the hole and above it
+        assert_eq!(sdf_density(&torus, IVec3::new(20, 0, 0)), 1.0);
+        assert_eq!(sdf_density(&torus, IVec3::new(0, 0, -20)), 1.0);
+        assert_eq!(sdf_density(&torus, IVec3::ZERO), 0.0);
+        assert_eq!(sdf_density(&torus, IVec3::new(20, 7, 0)), 0.0);
+
+        // Half density on the tube's top, outer and inner surfaces
+        assert_eq!(sdf_density(&torus, IVec3::new(20, 6, 0)), 0.5);
+        assert_eq!(sdf_density(&torus, IVec3::new(26, 0, 0)), 0.5);
+        assert_eq!(sdf_density(&torus, IVec3::new(14, 0, 0)), 0.5);
+    }
+
+    #[test]
+    fn test_ridged_noise_differs_from_fbm() {
+        let fbm_params = RockGenerationParams { size: 32, ..default() };
+        let ridged_params = RockGenerationParams { noise: NoiseKind::RidgedMulti, ..fbm_params.clone() };
+        let fbm = generate_rock_volume(&fbm_params).unwrap();
+        let ridged = generate_rock_volume(&ridged_params).unwrap();
+
+        // Same seed gives the same rock every time, but the two kinds don't match
+        assert_eq!(ridged.data, generate_rock_volume(&ridged_params).unwrap().data);
+        let differing = fbm.data.iter().zip(&ridged.data).filter(|(a, b)| (*a - *b).abs() > 1e-3).count();
+        assert!(differing > fbm.data.len() / 10, "only {} voxels differ", differing);
+    }
+
+    #[test]
+    fn test_render_timings_summary() {
+        let timings = RenderTimings::from_samples(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(timings.runs, 4);
+        assert_eq!(timings.mean_ms, 2.5);
+        assert_eq!(timings.median_ms, 2.5);
+        assert_eq!((timings.min_ms, timings.max_ms), (1.0, 4.0));
+        assert_eq!(RenderTimings::from_samples(&[5.0, 1.0, 9.0]).median_ms, 5.0);
+        assert_eq!(RenderTimings::from_samples(&[]), RenderTimings::default());
+    }
+
+    #[test]
+    fn test_volume_cache_reuses_identical_params() {
+        let params = RockGenerationParams { size: 16, ..default() };
+        let mut cache = VolumeCache::default();
+        assert!(cache.is_empty());
+
+        let first = cache.get_or_generate(&params).unwrap().data.clone();
+        assert_eq!(cache.get_or_generate(&params.clone()).unwrap().data, first);
+        assert_eq!(cache.len(), 1);
+
+        // Any change to the params is a different rock
+        let reseeded = RockGenerationParams { seed: 7, ..params.clone() };
+        cache.get_or_generate(&reseeded).unwrap();
+        assert_eq!(cache.len(), 2);
+        let rougher = RockGenerationParams { detail_amplitude: 0.1, ..params.clone() };
+        assert_ne!(cache.get_or_generate(&rougher).unwrap().data, first);
+        assert_eq!(cache.len(), 3);
+
+        // Bad params are reported and nothing is cached for them
+        assert!(cache.get_or_generate(&RockGenerationParams { size: 0, ..params }).is_err());
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_mineral_veins_stay_inside_rock() {
+        let mut volume = generate_rock_volume(&RockGenerationParams {
+            size: 48,
+            scale: 3.0,
+            octaves: 4,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            threshold: 0.0,
+            seed: 42,
+            detail_scale: 16.0,
+            detail_amplitude: 0.0,
+            noise: NoiseKind::Fbm,
+        })
+        .unwrap();
+        let solid_density = RenderSettings::default().threshold;
+        let solid_before: Vec<bool> = volume.data.iter().map(|&d| d > solid_density).collect();
+
+        add_mineral_veins(&mut volume, &VeinParams { thickness: 0.08, ..default() });
+
+        let mut vein_voxels = 0;
+        for (i, &material) in volume.materials.iter().enumerate() {
+            if material == VOLUME_MATERIAL_ORE {
+                vein_voxels += 1;
+                assert!(solid_before[i], "vein voxel {i} was placed in air");
+            }
+        }
+        assert!(vein_voxels > 0, "no veins were generated");
+        assert!(vein_voxels < solid_before.iter().filter(|&&s| s).count() / 2, "veins swallowed the rock");
+    }
+
+    #[test]
+    fn test_fill_shapes() {
+        let mut volume = Volume::new(32, 32, 32);
+
+        // Hard-edged box covers exactly its inclusive voxel range
+        volume.fill_box(Vec3::new(2.0, 2.0, 2.0), Vec3::new(5.0, 6.0, 7.0), 1.0, 0.0);
+        assert_eq!(volume.data.iter().filter(|&&d| d == 1.0).count(), 4 * 5 * 6);
+        assert_eq!(volume.get(5, 6, 7), 1.0);
+        assert_eq!(volume.get(6, 6, 7), 0.0);
+
+        // Carving with density 0 removes part of it again
+        volume.fill_box(Vec3::new(2.0, 2.0, 2.0), Vec3::new(5.0, 6.0, 2.0), 0.0, 0.0);
+        assert_eq!(volume.data.iter().filter(|&&d| d == 1.0).count(), 4 * 5 * 5);
+
+        // A soft sphere is solid inside, empty outside, and partially filled at the edge
+        volume.fill_sphere(Vec3::splat(20.0), 6.0, 1.0, 2.0);
+        assert_eq!(volume.get(20, 20, 20), 1.0);
+        assert_eq!(volume.get(20, 20, 28), 0.0);
+        let edge = volume.get(20, 20, 26);
+        assert!(edge > 0.0 && edge < 1.0, "edge density {edge}");
+
+        // Cylinder along X: inside the radius along its length, nothing past the caps
+        volume.fill_cylinder(Vec3::new(4.0, 24.0, 24.0), Vec3::new(12.0, 24.0, 24.0), 3.0, 0.5, 0.0);
+        assert_eq!(volume.get(8, 26, 24), 0.5);
+        assert_eq!(volume.get(8, 28, 24), 0.0);
+        assert_eq!(volume.get(13, 24, 24), 0.0);
+    }
+
+    #[test]
+    fn test_render_normals_face_viewer() {
+        // Silhouette pixels of a sphere must not encode normals pointing away from the camera
+        let mut volume = Volume::new(48, 48, 48);
+        let center = Vec3::splat(24.0);
+        for z in 0..48 {
+            for y in 0..48 {
+                for x in 0..48 {
+                    let pos = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+                    if pos.distance(center) <= 14.0 {
+                        volume.set(x, y, z, 1.0);
+                    }
+                }
+            }
+        }
+
+        let result = render_volume_to_maps(&volume, UVec2::splat(96), Vec3::new(0.3, 0.7, 0.0));
+        let mut hits = 0;
+        for pixel in 0..(result.width * result.height) as usize {
+            if result.diffuse_map[pixel * 4 + 3] == 0 {
+                continue;
+            }
+            hits += 1;
+            // Rays travel +Z, so a normal facing the viewer has z <= 0 (encoded <= 0.5)
+            assert!(result.normal_map[pixel * 4 + 2] <= 128, "back-facing normal at pixel {pixel}");
+        }
+        assert!(hits > 0, "sphere was not rendered");
+    }
+
+    #[test]
+    fn test_normal_at_decodes_any_layout() {
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| p.length() - 10.0);
+        let layout = ChannelLayout {
+            r: ChannelSource::Z,
+            g: ChannelSource::Height,
+            b: ChannelSource::X,
+            a: ChannelSource::Y,
+        };
+        let result = render_volume_with_settings(
+            &volume,
+            &RenderSettings {
+                output_size: UVec2::splat(32),
+                normal: NormalMapOptions { format: NormalMapFormat::Rgba16, channel_layout: layout },
+                ..default()
+            },
+        );
+
+        // The sphere's centre faces the viewer, its right edge faces +X, and corners are misses
+        let centre = result.normal_at(16, 16, &layout).unwrap();
+        assert!(centre.z < -0.9, "centre normal {centre}");
+        let right = result.normal_at(25, 16, &layout).unwrap();
+        assert!(right.x > 0.5, "right edge normal {right}");
+        assert_eq!(result.normal_at(0, 0, &layout), None);
+        assert_eq!(result.normal_at(16, 16, &ChannelLayout { a: ChannelSource::Height, ..layout }), None);
+    }
+
+    #[test]
+    fn test_incremental_render_matches_full_render() {
+        let mut volume = Volume::from_sdf(UVec3::splat(48), |p| p.length() - 14.0);
+        let settings = RenderSettings {
+            output_size: UVec2::splat(96),
+            rotation: Vec3::new(0.4, 0.9, 0.2),
+            ..default()
+        };
+        let mut patched = render_volume_with_settings(&volume, &settings);
+
+        // Carve a notch out of the surface and grow a bump elsewhere
+        for z in 8..14 {
+            for y in 20..28 {
+                for x in 20..28 {
+                    volume.set(x, y, z, 0.0);
+                }
+            }
+        }
+        for z in 22..26 {
+            for y in 36..42 {
+                for x in 22..26 {
+                    volume.set(x, y, z, 1.0);
+                }
+            }
+        }
+        update_volume_render_region(&volume, &settings, Vec3::new(20.0, 20.0, 8.0), Vec3::new(28.0, 28.0, 14.0), &mut patched);
+        update_volume_render_region(&volume, &settings, Vec3::new(22.0, 36.0, 22.0), Vec3::new(26.0, 42.0, 26.0), &mut patched);
+
+        let full = render_volume_with_settings(&volume, &settings);
+        assert!(patched.position_map == full.position_map, "position maps differ");
+        assert!(patched.normal_map == full.normal_map, "normal maps differ");
+        assert!(patched.diffuse_map == full.diffuse_map, "diffuse maps differ");
+    }
+
+    #[test]
+    fn test_render_non_cubic_volume() {
+        // A sphere in the middle of a 64x32x64 volume should render round
+        let mut volume = Volume::new(64, 32, 64);
+        let center = Vec3::new(32.0, 16.0, 32.0);
+        for z in 0..64 {
+            for y in 0..32 {
+                for x in 0..64 {
+                    let pos = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+                    if pos.distance(center) <= 12.0 {
+                        volume.set(x, y, z, 1.0);
+                    }
+                }
+            }
+        }
+
+        let result = render_volume_to_maps(&volume, UVec2::splat(64), Vec3::ZERO);
+        let (min_x, min_y, max_x, max_y) = coverage_bounds(&result);
+
+        assert!(max_x >= min_x && max_y >= min_y, "sphere was not rendered");
+        let width = max_x - min_x + 1;
+        let height = max_y - min_y + 1;
+        assert!(width.abs_diff(height) <= 1, "sphere rendered {}x{}", width, height);
+        assert!(width.abs_diff(24) <= 2, "unexpected sphere width {}", width);
+
+        // Centered in the output
+        let center_x = (min_x + max_x) as f32 / 2.0;
+        let center_y = (min_y + max_y) as f32 / 2.0;
+        assert!((center_x - 32.0).abs() <= 1.5);
+        assert!((center_y - 32.0).abs() <= 1.5);
+    }
+
+    #[test]
+    fn test_render_non_square_output() {
+        // A wide output keeps square pixels: the sphere stays round and centered
+        let volume = Volume::from_sdf(UVec3::splat(64), |p| p.length() - 12.0);
+        let result = render_volume_to_maps(&volume, UVec2::new(128, 64), Vec3::ZERO);
+        assert_eq!((result.width, result.height), (128, 64));
+        assert_eq!(result.diffuse_map.len(), 128 * 64 * 4);
+
+        let (min_x, min_y, max_x, max_y) = coverage_bounds(&result);
+        assert!(max_x >= min_x && max_y >= min_y, "sphere was not rendered");
+        let width = max_x - min_x + 1;
+        let height = max_y - min_y + 1;
+        assert!(width.abs_diff(height) <= 1, "sphere rendered {}x{}", width, height);
+        assert!(width.abs_diff(48) <= 3, "unexpected sphere width {}", width);
+        assert!(((min_x + max_x) as f32 / 2.0 - 64.0).abs() <= 1.5);
+        assert!(((min_y + max_y) as f32 / 2.0 - 32.0).abs() <= 1.5);
+    }
+
+    #[test]
+    fn test_sample_filters() {
+        // Along a linear ramp every filter agrees at voxel centres,
+        // and trilinear and tricubic both reproduce the ramp between them
+        let mut volume = Volume::new(8, 8, 8);
+        for z in 0..8 {
+            for y in 0..8 {
+                for x in 0..8 {
+                    volume.set(x, y, z, x as f32 / 7.0);
+                }
+            }
+        }
+        let voxel = Vec3::new(3.0, 4.0, 2.0);
+        for filter in [SampleFilter::Nearest, SampleFilter::Trilinear, SampleFilter::Tricubic] {
+            assert!((volume.sample_filtered(voxel, filter) - 3.0 / 7.0).abs() < 1e-5);
+        }
+        let between = Vec3::new(3.25, 4.5, 2.75);
+        assert!((volume.sample(between) - 3.25 / 7.0).abs() < 1e-5);
+        assert!((volume.sample_tricubic(between) - 3.25 / 7.0).abs() < 1e-5);
+        assert_eq!(volume.sample_nearest(between), 3.0 / 7.0);
+        assert_eq!(volume.sample_nearest(Vec3::splat(-5.0)), 0.0);
+
+        // Every filter renders a sphere at roughly the same size
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| p.length() - 10.0);
+        for filter in [SampleFilter::Nearest, SampleFilter::Trilinear, SampleFilter::Tricubic] {
+            let settings = RenderSettings {
+                output_size: UVec2::splat(64),
+                filter,
+                ..default()
+            };
+            let (min_x, _, max_x, _) = coverage_bounds(&render_volume_with_settings(&volume, &settings));
+            let width = max_x.saturating_sub(min_x) + 1;
+            assert!(width.abs_diff(40) <= 4, "{:?} rendered width {}", filter, width);
+        }
+    }
+
+    #[test]
+    fn test_supersampling_antialiases_silhouette() {
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| p.length() - 10.0);
+        // Trilinear so the surface isn't snapped to the same voxel grid as the pixels
+        let settings = RenderSettings {
+            output_size: UVec2::splat(64),
+            filter: SampleFilter::Trilinear,
+            ..default()
+        };
+        let plain = render_volume_with_settings(&volume, &settings);
+        let smooth = render_volume_with_settings(&volume, &RenderSettings { supersample: 2, ..settings });
+
+        // Same output size and silhouette, but with partially covered edge pixels
+        assert_eq!((smooth.width, smooth.height), (64, 64));
+        let (plain_min, _, plain_max, _) = coverage_bounds(&plain);
+        let (smooth_min, _, smooth_max, _) = coverage_bounds(&smooth);
+        assert!(plain_min.abs_diff(smooth_min) <= 1 && plain_max.abs_diff(smooth_max) <= 1);
+        let partial = |result: &VolumeRenderResult| {
+            result.diffuse_map.chunks(4).filter(|pixel| pixel[3] != 0 && pixel[3] != 255).count()
+        };
+        assert_eq!(partial(&plain), 0);
+        assert!(partial(&smooth) > 0);
+
+        // Medium is the plain defaults
+        assert_eq!(RenderQuality::default().apply(RenderSettings::default()), RenderSettings::default());
+        assert_eq!(RenderQuality::Ultra.next(), RenderQuality::Low);
+    }
+
+    #[test]
+    fn test_dithering_preserves_average() {
+        // A flat value between two 8-bit steps averages out to itself over a tile
+        for mode in [DitherMode::Ordered, DitherMode::BlueNoise] {
+            let mut sum = 0.0;
+            for py in 0..16 {
+                for px in 0..16 {
+                    sum += quantize_u8(100.4, mode.threshold(px, py)) as f32;
+                }
+            }
+            assert!((sum / 256.0 - 100.4).abs() < 0.05, "{:?} averaged {}", mode, sum / 256.0);
+        }
+        assert_eq!(quantize_u8(100.9, DitherMode::Off.threshold(3, 7)), 100);
+
+        // Dithered maps stay within one step of the plain render, and only on hit pixels
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| p.length() - 10.0);
+        let plain = render_volume_to_maps(&volume, UVec2::splat(64), Vec3::ZERO);
+        let dithered = render_volume_with_settings(&volume, &RenderSettings {
+            output_size: UVec2::splat(64),
+            dither: DitherMode::Ordered,
+            ..default()
+        });
+        for (plain_map, dithered_map) in [
+            (&plain.position_map, &dithered.position_map),
+            (&plain.normal_map, &dithered.normal_map),
+            (&plain.diffuse_map, &dithered.diffuse_map),
+        ] {
+            assert_ne!(plain_map, dithered_map);
+            assert!(plain_map.iter().zip(dithered_map).all(|(a, b)| b.abs_diff(*a) <= 1));
+        }
+        assert_eq!(coverage_bounds(&plain), coverage_bounds(&dithered));
+    }
+
+    #[test]
+    fn test_self_shadow_darkens_occluded_surface() {
+        // A wall at the back with a block floating in front of it, lit from the viewer's right
+        let mut volume = Volume::new(32, 32, 32);
+        volume.fill_box(Vec3::new(0.0, 0.0, 12.0), Vec3::new(31.0, 31.0, 31.0), 1.0, 0.0);
+        volume.fill_box(Vec3::new(12.0, 12.0, 2.0), Vec3::new(19.0, 19.0, 5.0), 1.0, 0.0);
+
+        let settings = RenderSettings { output_size: UVec2::splat(32), ..default() };
+        let unshadowed = render_volume_with_settings(&volume, &settings);
+        let shadowed = render_volume_with_settings(
+            &volume,
+            &RenderSettings {
+                self_shadow: SelfShadow {
+                    light_dir: Vec3::new(1.0, 0.0, -1.0),
+                    strength: 1.0,
+                    softness: 0.0,
+                },
+                ..settings
+            },
+        );
+
+        // Light from the wall at x=6 passes through the block on its way out; at x=26 it doesn't
+        let red = |result: &VolumeRenderResult, px: u32| result.diffuse_map[((16 * 32 + px) * 4) as usize];
+        assert!(red(&shadowed, 6) < red(&unshadowed, 6) / 2, "occluded wall stayed lit");
+        assert_eq!(red(&shadowed, 26), red(&unshadowed, 26));
+        assert_eq!(red(&shadowed, 16), red(&unshadowed, 16), "the block's own face is unshadowed");
+    }
+
+    #[test]
+    fn test_rotation_matrices_invert_each_other() {
+        // Rotating about several axes at once is where negated angles stop being an inverse
+        let rotation = Vec3::new(0.7, -1.2, 0.4);
+        let (forward, inverse) = rotation_matrices(rotation);
+        let point = Vec3::new(3.0, -5.0, 8.0);
+
+        assert!((inverse * (forward * point)).abs_diff_eq(point, 1e-5));
+        assert!((forward * (inverse * point)).abs_diff_eq(point, 1e-5));
+        assert!(!(create_rotation_matrix(-rotation) * (forward * point)).abs_diff_eq(point, 1e-3));
+    }
+
+    #[test]
+    fn test_turntable_frames_turn_with_and_without_camera() {
+        // Off-center so every quarter turn shows a different silhouette
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| (p - Vec3::new(7.0, 0.0, 0.0)).length() - 6.0);
+        let frame_size = 32;
+        let frame_pixels = |atlas: &Image, frame: u32| -> Vec<u8> {
+            let data = atlas.data.as_ref().unwrap();
+            let row_bytes = (frame_size * 4) as usize;
+            (0..frame_size)
+                .flat_map(|row| {
+                    let start = ((row * 4 * frame_size + frame * frame_size) * 4) as usize;
+                    data[start..start + row_bytes].to_vec()
+                })
+                .collect()
+        };
+
+        let front = Transform::from_xyz(0.0, 0.0, -32.0).looking_at(Vec3::ZERO, Vec3::NEG_Y);
+        for camera in [None, Some(front)] {
+            let settings = RenderSettings { camera, ..default() };
+            let atlas = bake_turntable_atlas(&volume, 4, frame_size, &settings);
+            assert_eq!(atlas.width(), 4 * frame_size);
+            let first = frame_pixels(&atlas, 0);
+            assert!(first.chunks(4).any(|pixel| pixel[3] > 0), "frame 0 is empty");
+            for frame in 1..4 {
+                assert_ne!(frame_pixels(&atlas, frame), first, "frame {frame} matches frame 0 (camera {camera:?})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_from_camera_transform() {
+        let volume = Volume::from_sdf(UVec3::splat(32), |p| (p - Vec3::new(4.0, -2.0, 0.0)).length() - 9.0);
+        let settings = RenderSettings { output_size: UVec2::splat(32), ..default() };
+        let euler = render_volume_with_settings(&volume, &settings);
+
+        // The unrotated view is a camera in front of the volume with +Y down the image
+        let front = Transform::from_xyz(0.0, 0.0, -32.0).looking_at(Vec3::ZERO, Vec3::NEG_Y);
+        let camera = render_volume_with_settings(&volume, &RenderSettings { camera: Some(front), ..settings });
+        assert_eq!(camera.diffuse_map, euler.diffuse_map);
+        assert_eq!(camera.normal_map, euler.normal_map);
+
+        // Sliding the camera 5 voxels right moves the image 5 pixels left
+        let slid = Transform { translation: front.translation + front.right() * 5.0, ..front };
+        let slid = render_volume_with_settings(&volume, &RenderSettings { camera: Some(slid), ..settings });
+        for y in 0..32 {
+            for x in 0..27 {
+                let (moved, original) = ((y * 32 + x) * 4, (y * 32 + x + 5) * 4);
+                assert_eq!(slid.diffuse_map[moved..moved + 4], euler.diffuse_map[original..original + 4]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solid_cube_front_face_is_flat() {
+        let volume = generate_test_volume(UVec3::splat(32), TestPattern::SolidCube);
+        let result = render_volume_to_maps(&volume, UVec2::splat(32), Vec3::ZERO);
+        let layout = ChannelLayout::default();
+
+        // The cube spans voxels 8..24 on every axis; pixels map one to one onto voxels
+        assert!(result.normal_at(4, 16, &layout).is_none());
+        for py in 9..23 {
+            for px in 9..23 {
+                let normal = result.normal_at(px, py, &layout).expect("cube face pixel");
+                assert!(normal.abs_diff_eq(Vec3::NEG_Z, 0.02), "normal {normal} at ({px}, {py})");
+            }
+        }
+
+        // The other patterns are fixed by their definition
+        let checker = generate_test_volume(UVec3::splat(16), TestPattern::Checkerboard);
+        assert_eq!((checker.get(0, 0, 0), checker.get(8, 0, 0), checker.get(8, 8, 0)), (1.0, 0.0, 1.0));
+        let gradient = generate_test_volume(UVec3::new(5, 2, 2), TestPattern::Gradient);
+        assert_eq!((0..5).map(|x| gradient.get(x, 1, 1)).collect::<Vec<_>>(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn test_volume_stats() {
+        assert_eq!(Volume::new(0, 0, 0).stats(0.5), VolumeStats::default());
+
+        // One layer empty, one at half density, two solid
+        let mut volume = Volume::new(2, 2, 4);
+        volume.fill_box(Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 1.0, 1.0), 0.5, 0.0);
+        volume.fill_box(Vec3::new(0.0, 0.0, 2.0), Vec3::new(1.0, 1.0, 3.0), 1.0, 0.0);
+        let stats = volume.stats(0.3);
+        assert_eq!((stats.min, stats.max), (0.0, 1.0));
+        assert!((stats.mean - 0.625).abs() < 1e-6);
+        assert_eq!(stats.solid_fraction, 0.75);
+        assert_eq!(volume.stats(0.5).solid_fraction, 0.5);
+    }
+
+    #[test]
+    fn test_invalid_rock_params_are_rejected() {
+        assert!(RockGenerationParams::default().validate().is_ok());
+
+        let invalid = [
+            RockGenerationParams { size: 0, ..default() },
+            RockGenerationParams { size: MAX_ROCK_SIZE + 1, ..default() },
+            RockGenerationParams { octaves: 0, ..default() },
+            RockGenerationParams { octaves: 64, ..default() },
+            RockGenerationParams { scale: -1.0, ..default() },
+            RockGenerationParams { persistence: 1.5, ..default() },
+            RockGenerationParams { detail_amplitude: f32::NAN, ..default() },
+        ];
+        for params in &invalid {
+            assert!(generate_rock_volume(params).is_err());
+        }
+
+        let tiny = generate_rock_volume(&RockGenerationParams { size: 2, ..default() }).unwrap();
+        assert_eq!(tiny.dimensions, UVec3::splat(2));
+    }
+
+    #[test]
+    fn test_validate_finds_non_finite_voxels() {
+        let mut volume = Volume::new(4, 5, 6);
+        assert!(volume.validate().is_ok());
+
+        volume.set(1, 2, 3, f32::NAN);
+        volume.set(3, 4, 5, f32::INFINITY);
+        assert_eq!(volume.non_finite_voxels(), vec![UVec3::new(1, 2, 3), UVec3::new(3, 4, 5)]);
+        let err = volume.validate().unwrap_err();
+        assert!(err.starts_with("2 non-finite"), "{}", err);
+        assert!(err.contains("(1, 2, 3)") && err.contains("(3, 4, 5)"), "{}", err);
+    }
+}
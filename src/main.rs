@@ -24,21 +24,39 @@ fn main() {
         .init_resource::<ChunkManager>()
         .init_resource::<SpatialIndex>()
         .init_resource::<SimulationSettings>()
+        .init_resource::<VisualizerBudget>()
+        .insert_resource(MaterialRegistry::load_or_default(MATERIALS_FILE))
+        .add_event::<ChunkDirty>()
         // Rendering systems
         .add_plugins(IsometricVoxelRendererPlugin)
         .add_plugins(GpuRendererPlugin)
         .add_plugins(VoxelWorldMaterialPlugin)
+        .add_plugins(FireSparkPlugin)
+        .add_plugins(MinimapPlugin)
+        .add_plugins(ChunkFreezePlugin)
+        .add_plugins(BloomControlPlugin)
+        .add_plugins(WorldMapPlugin)
+        .add_plugins(VoxelOutlinePlugin)
+        .add_plugins(ScreenRecorderPlugin)
+        .add_plugins(OitPlugin)
+        .add_plugins(FireFlickerPlugin)
         // Simulation systems
         .add_plugins(ComputeSimulationPlugin)
-        .add_plugins(CpuSimulationPlugin) // CPU sim (GPU requires complex render world setup)
+        .add_plugins(CpuSimulationPlugin) // Runs unless the compute plugin picks the GPU backend
+        .add_plugins(SimulationControlsPlugin)
         // Setup and update systems
         .add_systems(Startup, (setup_test_world, setup_camera))
         .add_systems(Update, (
             manage_chunk_loading,
-            update_chunk_textures,
             update_auto_spawners,
             spawn_test_elements,
+            emit_chunk_dirty_events.after(update_auto_spawners).after(spawn_test_elements),
+            update_chunk_textures.after(emit_chunk_dirty_events),
             debug_info,
+            update_selected_voxel,
+            edit_voxels_with_mouse.before(emit_chunk_dirty_events),
+            toggle_dynamic_voxel_visualizer,
+            visualize_dynamic_voxels.after(toggle_dynamic_voxel_visualizer),
         ))
         .run();
 }
@@ -57,10 +75,10 @@ fn setup_test_world(
         2,  // Simulate chunks within 2 chunk radius
     );
 
-    // Create a few test chunks around origin
+    // Create a ground layer of test chunks around origin
     for x in -1..=1 {
-        for y in -1..=1 {
-            for z in 0..=0 {
+        for y in 0..=0 {
+            for z in -1..=1 {
                 let chunk_pos = IVec3::new(x, y, z);
                 spawn_test_chunk(&mut commands, &mut chunk_manager, &mut images, chunk_pos);
             }
@@ -70,6 +88,9 @@ fn setup_test_world(
     info!("Test world setup complete - {} chunks created", chunk_manager.chunks.len());
 }
 
+/// Layers of rock at the bottom of the ground chunks
+const TEST_FLOOR_HEIGHT: u32 = 4;
+
 /// Spawn a single chunk with test geometry
 fn spawn_test_chunk(
     commands: &mut Commands,
@@ -80,17 +101,14 @@ fn spawn_test_chunk(
     let mut chunk = WorldChunk::new(chunk_pos);
     
     // Fill bottom layer with rock
-    if chunk_pos.z == 0 {
-        chunk.fill_region(
-            UVec3::new(0, 0, 0),
-            UVec3::new(CHUNK_SIZE, CHUNK_SIZE, 4),
-            VoxelData::rock(255),
-        );
+    if chunk_pos.y == 0 {
+        chunk.fill_floor(TEST_FLOOR_HEIGHT, VoxelData::rock(255));
     }
     
-    // Create the GPU texture for this chunk
+    // Create the GPU textures for this chunk
     let texture_handle = create_chunk_texture(&chunk, images);
     chunk.gpu_texture = Some(texture_handle);
+    chunk.gpu_density_texture = Some(create_chunk_density_texture(&chunk, images));
     chunk.dirty = false;
 
     // Spawn the chunk entity
@@ -112,25 +130,121 @@ fn manage_chunk_loading(
 }
 
 /// System to update chunk textures when they're marked dirty
+/// `ChunkDirty` events patch just the changed voxels into the image. Anything still
+/// dirty afterwards is uploaded whole. `dirty` is only cleared once the new data is in
+/// the image; if the image asset isn't available yet the chunk is retried next frame,
+/// and the skipped region is kept in `texture_pending` so a later patch includes it.
+/// The density texture is patched over the same region as the voxel texture.
 fn update_chunk_textures(
+    mut dirty_events: EventReader<ChunkDirty>,
+    chunk_manager: Res<ChunkManager>,
     mut chunks: Query<&mut WorldChunk>,
     mut images: ResMut<Assets<Image>>,
+    mut perf: ResMut<SimPerf>,
 ) {
-    for mut chunk in chunks.iter_mut() {
-        if chunk.dirty {
-            // Re-upload texture data to GPU
-            if let Some(texture_handle) = &chunk.gpu_texture {
-                if let Some(image) = images.get_mut(texture_handle) {
-                    // Update the texture data
-                    let voxel_data: Vec<u8> = chunk.voxels
-                        .iter()
-                        .flat_map(|v| v.as_u32().to_le_bytes())
-                        .collect();
-                    image.data = Some(voxel_data);
+    let start = std::time::Instant::now();
+    let mut uploaded = 0;
+
+    for event in dirty_events.read() {
+        let Some(entity) = chunk_manager.get_chunk_entity(event.chunk_pos) else {
+            continue;
+        };
+        let Ok(mut chunk) = chunks.get_mut(entity) else {
+            continue;
+        };
+        if !chunk.dirty {
+            continue;
+        }
+
+        // Include earlier edits whose patch was skipped, or they'd never reach the image
+        let (min, max) = event.local_range();
+        let (min, max) = match chunk.texture_pending {
+            Some((pending_min, pending_max)) => (min.min(pending_min), max.max(pending_max)),
+            None => (min, max),
+        };
+        chunk.texture_pending = Some((min, max));
+
+        let Some(image) = chunk.gpu_texture.as_ref().and_then(|handle| images.get_mut(handle)) else {
+            continue;
+        };
+        let Some(data) = image.data.as_mut().filter(|data| data.len() == CHUNK_TEXTURE_BYTES) else {
+            continue;
+        };
+
+        // Copy the changed rows; the X span of each row is contiguous in both layouts
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                let first = ((z * CHUNK_SIZE + y) * CHUNK_SIZE + min.x) as usize;
+                let last = ((z * CHUNK_SIZE + y) * CHUNK_SIZE + max.x) as usize;
+                for (i, voxel) in chunk.voxels[first..=last].iter().enumerate() {
+                    let offset = (first + i) * 4;
+                    data[offset..offset + 4].copy_from_slice(&voxel.as_u32().to_le_bytes());
                 }
             }
+        }
+        update_chunk_density_texture(&chunk, &mut images, min, max);
+        chunk.texture_pending = None;
+        chunk.dirty = false;
+        uploaded += 1;
+    }
+
+    for mut chunk in chunks.iter_mut() {
+        if !chunk.dirty {
+            continue;
+        }
+
+        let Some(texture_handle) = &chunk.gpu_texture else {
+            continue;
+        };
+        let Some(image) = images.get_mut(texture_handle) else {
+            debug!("Chunk {:?} texture not available yet, retrying upload", chunk.chunk_position);
+            continue;
+        };
+
+        // Re-upload texture data to GPU
+        let size = image.texture_descriptor.size;
+        let texel_bytes = image.texture_descriptor.format.block_copy_size(None).unwrap_or(0);
+        let image_bytes = (size.width * size.height * size.depth_or_array_layers * texel_bytes) as usize;
+        if chunk.voxels.len() != VOXELS_PER_CHUNK || image_bytes != CHUNK_TEXTURE_BYTES {
+            // Writing mismatched data would silently corrupt the GPU image; retrying won't help
+            warn!(
+                "Chunk {:?} texture size mismatch: {} voxels, {} image bytes, expected {}",
+                chunk.chunk_position,
+                chunk.voxels.len(),
+                image_bytes,
+                CHUNK_TEXTURE_BYTES,
+            );
+            chunk.texture_pending = None;
             chunk.dirty = false;
+            continue;
         }
+
+        // Rewrite the image's own buffer when it has one instead of allocating a new one
+        match image.data.as_mut().filter(|data| data.len() == CHUNK_TEXTURE_BYTES) {
+            Some(data) => write_chunk_texture_bytes(&chunk, data),
+            None => image.data = Some(chunk_texture_bytes(&chunk)),
+        }
+        update_chunk_density_texture(&chunk, &mut images, UVec3::ZERO, UVec3::splat(CHUNK_SIZE - 1));
+        chunk.texture_pending = None;
+        chunk.dirty = false;
+        uploaded += 1;
+    }
+
+    // Only touch the resource when something happened so the status panel isn't redrawn every frame
+    if uploaded > 0 || perf.chunks_uploaded > 0 {
+        perf.chunks_uploaded = uploaded;
+        perf.render_time_ms = start.elapsed().as_secs_f32() * 1000.0;
+    }
+}
+
+/// Patch an inclusive local range of a chunk's density texture, or rebuild it if it has no data
+fn update_chunk_density_texture(chunk: &WorldChunk, images: &mut Assets<Image>, min: UVec3, max: UVec3) {
+    let Some(image) = chunk.gpu_density_texture.as_ref().and_then(|handle| images.get_mut(handle)) else {
+        return;
+    };
+    match image.data.as_mut().filter(|data| data.len() == VOXELS_PER_CHUNK) {
+        Some(data) => write_chunk_density_region(chunk, data, min, max),
+        None => image.data = Some(chunk_density_bytes(chunk)),
     }
 }
 
@@ -138,6 +252,7 @@ fn update_chunk_textures(
 fn debug_info(
     chunks: Query<&WorldChunk>,
     _chunk_manager: Res<ChunkManager>,
+    materials: Res<MaterialRegistry>,
     time: Res<Time>,
 ) {
     // Print debug info every 2 seconds
@@ -163,22 +278,28 @@ fn debug_info(
             }
         }
         
+        // Named as the materials file names them
+        let name = |material| &materials.get(material).name;
         info!(
-            "Chunks: {} total, {} dynamic | Elements: Fire={}, Smoke={}, Water={}, Debris={} | FPS: {:.1}",
+            "Chunks: {} total, {} dynamic | Elements: {}={}, {}={}, {}={}, {}={} | FPS: {:.1}",
             total_chunks,
             dynamic_chunks,
+            name(MaterialType::Fire),
             fire_count,
+            name(MaterialType::Smoke),
             smoke_count,
+            name(MaterialType::Water),
             water_count,
+            name(MaterialType::Debris),
             debris_count,
             1.0 / time.delta_secs()
         );
     }
 }
 
-/// Setup the camera
-fn setup_camera(mut commands: Commands) {
-    commands.spawn(Camera2d);
+/// Setup the camera with HDR so emissive materials can bloom
+fn setup_camera(mut commands: Commands, bloom: Res<BloomControl>) {
+    commands.spawn((Camera2d, bloom_camera_bundle(&bloom)));
 }
 
 /// System to create a simple preview of the world
@@ -213,21 +334,147 @@ fn render_world_preview(
     }
 }
 
+/// How far in front of the height-0 plane cursor rays start
+const AIM_RAY_OFFSET: f32 = 256.0;
+
+/// Build a world-space ray under the cursor for the isometric camera
+/// Every point along (1, 1, 1) projects to the same screen position, so the ray
+/// runs along that axis, starting in front of the height-0 plane.
+fn cursor_ray(
+    windows: &Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: &Query<(&Camera, &GlobalTransform), Without<OitCamera>>,
+) -> Option<Ray3d> {
+    let cursor = windows.single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera.single().ok()?;
+    let iso = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+
+    let on_plane = isometric_to_world_plane(iso, 0.0);
+    let toward_viewer = Vec3::ONE.normalize();
+
+    Some(Ray3d::new(
+        on_plane + toward_viewer * AIM_RAY_OFFSET,
+        Dir3::new_unchecked(-toward_viewer),
+    ))
+}
+
+/// First solid voxel along a cursor ray
+fn cursor_hit(ray: Ray3d, voxels: &VoxelWorld) -> Option<RaycastHit> {
+    voxels.raycast(ray.origin, *ray.direction, AIM_RAY_OFFSET * 2.0)
+}
+
+/// World position the spawn keys should target: the first solid voxel under the
+/// cursor, or the height-0 plane if nothing is hit
+fn aimed_spawn_position(
+    windows: &Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: &Query<(&Camera, &GlobalTransform), Without<OitCamera>>,
+    voxels: &VoxelWorld,
+) -> Option<Vec3> {
+    let ray = cursor_ray(windows, camera)?;
+    let hit = cursor_hit(ray, voxels);
+
+    Some(match hit {
+        // Spawn in the empty cell in front of the hit face so elements sit on the surface
+        Some(hit) => hit.placement_coord().as_vec3() + Vec3::splat(0.5),
+        None => ray.get_point(AIM_RAY_OFFSET),
+    })
+}
+
+/// Move a sphere spawn aimed at `target` up until it rests on the surface below it,
+/// instead of sinking most of the way into the ground
+/// Drops a box the size of the sphere onto `target` from above. A target inside solid
+/// ground has no surface to rest on and is kept as is.
+fn rest_on_surface(target: Vec3, radius: f32, voxels: &VoxelWorld) -> Vec3 {
+    if voxels.is_solid(target) {
+        return target;
+    }
+    let start = target + Vec3::Y * radius * 2.0;
+    voxels
+        .sweep(start, target, Vec3::splat(radius))
+        .map_or(target, |hit| hit.position)
+}
+
+/// Pick the solid voxel under the cursor for the selection outline
+/// Cleared when the cursor is off the window or over empty space.
+fn update_selected_voxel(
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), Without<OitCamera>>,
+    voxels: VoxelWorld,
+    mut selected: ResMut<SelectedVoxel>,
+) {
+    let hovered = cursor_ray(&windows, &camera)
+        .and_then(|ray| cursor_hit(ray, &voxels))
+        .map(|hit| hit.voxel_coord);
+    selected.set_if_neq(SelectedVoxel(hovered));
+}
+
+/// Radius of the hole a right click digs
+const DIG_RADIUS: f32 = 2.5;
+
+/// Hand-edit the world with the mouse: left click places a rock voxel against the
+/// face under the cursor, right click digs a small hole around the voxel under it,
+/// middle click places a water drain against the face
+fn edit_voxels_with_mouse(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), Without<OitCamera>>,
+    mut voxels: VoxelWorld,
+) {
+    if !mouse.any_just_pressed([MouseButton::Left, MouseButton::Right, MouseButton::Middle]) {
+        return;
+    }
+    let Some(hit) = cursor_ray(&windows, &camera).and_then(|ray| cursor_hit(ray, &voxels)) else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        voxels.set(hit.placement_coord(), VoxelData::rock(255));
+    }
+    if mouse.just_pressed(MouseButton::Right) {
+        voxels.fill_sphere(hit.voxel_coord.as_vec3() + Vec3::splat(0.5), DIG_RADIUS, VoxelData::air());
+    }
+    if mouse.just_pressed(MouseButton::Middle) {
+        voxels.set(hit.placement_coord(), VoxelData::water_drain());
+    }
+}
+
+/// Half the length of the fire line the L key lays along X
+const FIRE_LINE_HALF_LENGTH: f32 = 12.0;
+
 /// Test system to spawn fire elements for demonstration
+/// Spawn keys target the voxel under the cursor, with spheres lifted to rest on the
+/// surface there; Tab toggles the old fixed positions.
 fn spawn_test_elements(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut chunks: Query<&mut WorldChunk>,
-    chunk_manager: Res<ChunkManager>,
+    mut voxels: VoxelWorld,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), Without<OitCamera>>,
+    mut use_fixed_positions: Local<bool>,
+    mut rng: ResMut<SimulationRng>,
     time: Res<Time>,
 ) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        *use_fixed_positions = !*use_fixed_positions;
+        info!("Spawn aiming: {}", if *use_fixed_positions { "fixed positions" } else { "cursor raycast" });
+    }
+
+    let spawn_keys = [KeyCode::Space, KeyCode::KeyE, KeyCode::KeyR, KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyL];
+    let aimed = if !*use_fixed_positions && keyboard.any_just_pressed(spawn_keys) {
+        aimed_spawn_position(&windows, &camera, &voxels)
+    } else {
+        None
+    };
+
     // Spawn fire ball on spacebar press
     if keyboard.just_pressed(KeyCode::Space) {
         info!("Spawning fire sphere!");
-        ElementSpawner::spawn_fire_sphere(
-            Vec3::new(0.0, 0.0, 20.0),
+        let center = aimed.map(|target| rest_on_surface(target, 5.0, &voxels));
+        ElementSpawner::spawn_fire_sphere_jittered(
+            center.unwrap_or(Vec3::new(0.0, 20.0, 0.0)),
             5.0,
-            &mut chunks,
-            &chunk_manager,
+            0.35,
+            &mut rng,
+            &mut voxels.chunks,
+            &voxels.manager,
         );
     }
     
@@ -235,35 +482,72 @@ fn spawn_test_elements(
     if keyboard.just_pressed(KeyCode::KeyE) {
         info!("Spawning explosion!");
         ElementSpawner::spawn_explosion(
-            Vec3::new(32.0, 32.0, 10.0),
+            aimed.unwrap_or(Vec3::new(32.0, 10.0, 32.0)),
             8.0,
-            &mut chunks,
-            &chunk_manager,
+            &mut voxels.chunks,
+            &voxels.manager,
         );
     }
     
+    // Spawn a ring of fire on R key
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        info!("Spawning fire ring!");
+        ElementSpawner::spawn_ring(
+            aimed.unwrap_or(Vec3::new(0.0, 12.0, 32.0)),
+            10.0,
+            1.5,
+            Vec3::Y,
+            VoxelData::new(
+                MaterialType::Fire,
+                255,
+                255,
+                voxel_flags::EMITS_LIGHT | voxel_flags::TEMPORARY,
+            ),
+            &mut voxels.chunks,
+            &voxels.manager,
+        );
+    }
+
     // Spawn water on W key
     if keyboard.just_pressed(KeyCode::KeyW) {
         info!("Spawning water!");
-        ElementSpawner::spawn_water_sphere(
-            Vec3::new(64.0, 0.0, 20.0),
+        let center = aimed.map(|target| rest_on_surface(target, 6.0, &voxels));
+        ElementSpawner::spawn_water_sphere_jittered(
+            center.unwrap_or(Vec3::new(64.0, 20.0, 0.0)),
             6.0,
-            &mut chunks,
-            &chunk_manager,
+            0.35,
+            &mut rng,
+            &mut voxels.chunks,
+            &voxels.manager,
         );
     }
     
     // Spawn smoke on S key
     if keyboard.just_pressed(KeyCode::KeyS) {
         info!("Spawning smoke!");
+        let center = aimed.map(|target| rest_on_surface(target, 4.0, &voxels));
         ElementSpawner::spawn_smoke_sphere(
-            Vec3::new(-32.0, 32.0, 15.0),
+            center.unwrap_or(Vec3::new(-32.0, 15.0, 32.0)),
             4.0,
-            &mut chunks,
-            &chunk_manager,
+            &mut voxels.chunks,
+            &voxels.manager,
         );
     }
     
+    // Spawn a line of fire on L key, stamped as one batch
+    if keyboard.just_pressed(KeyCode::KeyL) {
+        info!("Spawning fire line!");
+        let center = aimed.unwrap_or(Vec3::new(0.0, 8.0, 16.0));
+        let half_length = Vec3::X * FIRE_LINE_HALF_LENGTH;
+        ElementSpawner::spawn_fire_line(
+            center - half_length,
+            center + half_length,
+            1.5,
+            &mut voxels.chunks,
+            &voxels.manager,
+        );
+    }
+
     // Auto-spawn a small fire every second for testing
     static mut LAST_SPAWN: f32 = 0.0;
     unsafe {
@@ -272,10 +556,10 @@ fn spawn_test_elements(
             
             // Spawn a small fire in the center chunk
             ElementSpawner::spawn_fire_sphere(
-                Vec3::new(32.0, 32.0, 5.0),
+                Vec3::new(32.0, 5.0, 32.0),
                 2.0,
-                &mut chunks,
-                &chunk_manager,
+                &mut voxels.chunks,
+                &voxels.manager,
             );
         }
     }
@@ -285,17 +569,66 @@ fn spawn_test_elements(
 #[derive(Component)]
 struct DynamicVoxelMarker;
 
+/// Switch and limits for the dynamic voxel visualizer, toggled with V
+/// A large fire can cover tens of thousands of voxels, so only the sampled voxels
+/// nearest the camera get a sprite.
+#[derive(Resource, Debug, Clone, Copy)]
+struct VisualizerBudget {
+    /// Whether the visualizer draws at all; it overlays the isometric view for debugging
+    enabled: bool,
+    /// Most sprites spawned per rebuild
+    max_sprites: usize,
+    /// Sample every `step`th voxel along each axis
+    step: usize,
+    /// Depth offset per voxel of Z, to flatten the world into the 2D view
+    z_flatten: f32,
+}
+
+impl Default for VisualizerBudget {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_sprites: 4096,
+            step: 2,
+            z_flatten: 0.1,
+        }
+    }
+}
+
+/// Show or hide the dynamic voxel visualizer
+fn toggle_dynamic_voxel_visualizer(keyboard: Res<ButtonInput<KeyCode>>, mut budget: ResMut<VisualizerBudget>) {
+    if !keyboard.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+    budget.enabled = !budget.enabled;
+    info!("Dynamic voxel visualizer {}", if budget.enabled { "shown" } else { "hidden" });
+}
+
 /// Visualize dynamic voxels (fire, smoke, water, debris) as colored pixels
+#[allow(clippy::too_many_arguments)]
 fn visualize_dynamic_voxels(
     mut commands: Commands,
     all_chunks: Query<&WorldChunk>,
     changed_chunks: Query<&WorldChunk, Changed<WorldChunk>>,
     existing_markers: Query<Entity, With<DynamicVoxelMarker>>,
+    camera: Query<&GlobalTransform, (With<Camera2d>, Without<OitCamera>)>,
+    budget: Res<VisualizerBudget>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut oit_materials: ResMut<Assets<OitVoxelMaterial>>,
 ) {
-    // Only rebuild if something changed
-    if changed_chunks.is_empty() {
+    if !budget.enabled {
+        // Clear the sprites once, right after the visualizer is switched off
+        if budget.is_changed() {
+            for entity in existing_markers.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
+        return;
+    }
+
+    // Only rebuild if something changed, or the visualizer was just switched on or retuned
+    if changed_chunks.is_empty() && !budget.is_changed() {
         return;
     }
     
@@ -304,14 +637,15 @@ fn visualize_dynamic_voxels(
         commands.entity(entity).despawn();
     }
     
-    // Visualize dynamic voxels in ALL chunks (not just changed ones)
+    // Collect dynamic voxels in ALL chunks (not just changed ones)
+    let mut sprites = Vec::new();
     for chunk in all_chunks.iter() {
         let chunk_world_pos = chunk.chunk_position.as_vec3() * CHUNK_SIZE as f32;
         
-        // Sample voxels (every 2nd voxel to reduce visual clutter)
-        for z in (0..CHUNK_SIZE).step_by(2) {
-            for y in (0..CHUNK_SIZE).step_by(2) {
-                for x in (0..CHUNK_SIZE).step_by(2) {
+        // Sample voxels sparsely to reduce visual clutter
+        for z in (0..CHUNK_SIZE).step_by(budget.step.max(1)) {
+            for y in (0..CHUNK_SIZE).step_by(budget.step.max(1)) {
+                for x in (0..CHUNK_SIZE).step_by(budget.step.max(1)) {
                     if let Some(voxel) = chunk.get_voxel(x, y, z) {
                         let material = voxel.material();
                         
@@ -329,23 +663,46 @@ fn visualize_dynamic_voxels(
                             let world_pos = chunk_world_pos + Vec3::new(
                                 x as f32 + 0.5,
                                 y as f32 + 0.5,
-                                z as f32 * 0.1, // Flatten Z for 2D view
+                                z as f32 * budget.z_flatten, // Flatten Z for 2D view
                             );
-                            
-                            // Spawn a small square to represent this voxel
-                            commands.spawn((
-                                Mesh2d(meshes.add(Rectangle::new(2.0, 2.0))),
-                                MeshMaterial2d(materials.add(ColorMaterial {
-                                    color,
-                                    ..default()
-                                })),
-                                Transform::from_translation(world_pos),
-                                DynamicVoxelMarker,
-                            ));
+                            sprites.push((world_pos, color, blends_order_independent(material)));
                         }
                     }
                 }
             }
         }
     }
+
+    // Over budget: keep the sprites nearest the camera
+    if sprites.len() > budget.max_sprites {
+        let camera_pos = camera.single().map(|t| t.translation().truncate()).unwrap_or(Vec2::ZERO);
+        let distance = |pos: &Vec3| pos.truncate().distance_squared(camera_pos);
+        if budget.max_sprites > 0 {
+            sprites.select_nth_unstable_by(budget.max_sprites - 1, |(a, _, _), (b, _, _)| {
+                distance(a).total_cmp(&distance(b))
+            });
+        }
+        sprites.truncate(budget.max_sprites);
+    }
+
+    for (world_pos, color, order_independent) in sprites {
+        let mesh = meshes.add(Rectangle::new(2.0, 2.0));
+        if order_independent {
+            for bundle in oit_voxel_bundles(mesh, color, &mut oit_materials) {
+                commands.spawn((bundle, Transform::from_translation(world_pos), DynamicVoxelMarker));
+            }
+            continue;
+        }
+
+        // Spawn a small square to represent this voxel
+        commands.spawn((
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(ColorMaterial {
+                color,
+                ..default()
+            })),
+            Transform::from_translation(world_pos),
+            DynamicVoxelMarker,
+        ));
+    }
 }
@@ -0,0 +1,75 @@
+use bevy::core_pipeline::bloom::Bloom;
+use bevy::prelude::*;
+
+/// Largest allowed bloom intensity
+const MAX_BLOOM_INTENSITY: f32 = 1.0;
+/// Change in intensity per key press
+const BLOOM_INTENSITY_STEP: f32 = 0.05;
+
+/// Global glow control for emissive materials
+/// Only colors pushed above 1.0 by `MaterialType::emissive_strength` are bright enough to bloom;
+/// this scales how far that glow spreads. 0 turns bloom off.
+#[derive(Resource, Clone)]
+pub struct BloomControl {
+    pub intensity: f32,
+}
+
+impl Default for BloomControl {
+    fn default() -> Self {
+        Self {
+            intensity: Bloom::NATURAL.intensity,
+        }
+    }
+}
+
+/// Plugin for the HDR bloom pass on the main camera
+/// `[`/`]` lower/raise the bloom intensity.
+pub struct BloomControlPlugin;
+
+impl Plugin for BloomControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BloomControl>()
+           .add_systems(Update, (control_bloom_intensity, apply_bloom_control).chain());
+    }
+}
+
+/// Components for an HDR camera with bloom, to spawn alongside `Camera2d`
+pub fn bloom_camera_bundle(control: &BloomControl) -> impl Bundle {
+    (
+        Camera {
+            hdr: true,
+            ..default()
+        },
+        Bloom {
+            intensity: control.intensity,
+            ..Bloom::NATURAL
+        },
+    )
+}
+
+/// Adjust the bloom intensity from the keyboard
+fn control_bloom_intensity(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut control: ResMut<BloomControl>,
+) {
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        control.intensity = (control.intensity + BLOOM_INTENSITY_STEP).min(MAX_BLOOM_INTENSITY);
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        control.intensity = (control.intensity - BLOOM_INTENSITY_STEP).max(0.0);
+    }
+}
+
+/// Push the current intensity to every bloom camera
+fn apply_bloom_control(
+    control: Res<BloomControl>,
+    mut cameras: Query<&mut Bloom>,
+) {
+    if !control.is_changed() {
+        return;
+    }
+
+    for mut bloom in cameras.iter_mut() {
+        bloom.intensity = control.intensity;
+    }
+}
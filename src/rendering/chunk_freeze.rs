@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use std::collections::HashMap;
+use crate::world::{emit_chunk_dirty_events, ChunkManager, MaterialRegistry, WorldChunk, CHUNK_SIZE};
+use super::chunk_mesh::build_chunk_mesh;
+use super::isometric_projection::IsometricProjectionSettings;
+use super::isometric_voxel_renderer::{get_material_color_with_shading, IsometricFogSettings};
+use super::voxel_ao::{chunk_solid, top_corner_ao, AO_BRIGHTNESS};
+
+/// Request to bake a chunk into a single static mesh
+/// The per-voxel isometric renderer skips the chunk until it is edited again.
+#[derive(Event)]
+pub struct FreezeChunk {
+    pub chunk_position: IVec3,
+}
+
+/// On a frozen chunk's entity: the entity drawing its baked mesh
+#[derive(Component)]
+pub struct FrozenChunkMesh {
+    pub mesh_entity: Entity,
+}
+
+/// Plugin for baking static chunks into meshes
+/// F freezes every loaded chunk without dynamic elements.
+pub struct ChunkFreezePlugin;
+
+impl Plugin for ChunkFreezePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FreezeChunk>()
+           .init_resource::<IsometricProjectionSettings>()
+           .add_systems(Update, (freeze_static_chunks_on_key, freeze_chunks, unfreeze_edited_chunks)
+               .chain()
+               .before(emit_chunk_dirty_events));
+    }
+}
+
+/// Shading per visible face, so baked faces keep the cube look of the voxel sprites
+const TOP_FACE_SHADE: f32 = 1.0;
+const RIGHT_FACE_SHADE: f32 = 0.8;
+const LEFT_FACE_SHADE: f32 = 0.65;
+
+fn freeze_static_chunks_on_key(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    chunks: Query<&WorldChunk>,
+    mut freeze_events: EventWriter<FreezeChunk>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+
+    for chunk in chunks.iter() {
+        if !chunk.frozen && !chunk.has_dynamic_elements {
+            freeze_events.write(FreezeChunk { chunk_position: chunk.chunk_position });
+        }
+    }
+}
+
+/// Bake requested chunks into meshes and mark them frozen
+#[allow(clippy::too_many_arguments)]
+fn freeze_chunks(
+    mut commands: Commands,
+    mut freeze_events: EventReader<FreezeChunk>,
+    chunk_manager: Res<ChunkManager>,
+    mut chunks: Query<&mut WorldChunk>,
+    fog: Res<IsometricFogSettings>,
+    projection: Res<IsometricProjectionSettings>,
+    registry: Res<MaterialRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in freeze_events.read() {
+        let Some(entity) = chunk_manager.get_chunk_entity(event.chunk_position) else {
+            continue;
+        };
+        let Ok(mut chunk) = chunks.get_mut(entity) else {
+            continue;
+        };
+        if chunk.frozen {
+            continue;
+        }
+        if chunk.has_dynamic_elements {
+            info!("Chunk {:?} has dynamic elements, not freezing", event.chunk_position);
+            continue;
+        }
+
+        let mesh_entity = commands
+            .spawn((
+                Mesh2d(meshes.add(build_frozen_chunk_mesh(&chunk, &fog, &projection, &registry))),
+                // White so the baked vertex colors come through unchanged
+                MeshMaterial2d(materials.add(ColorMaterial::default())),
+                Transform::IDENTITY,
+            ))
+            .id();
+        commands.entity(entity).insert(FrozenChunkMesh { mesh_entity });
+        chunk.frozen = true;
+        // Let the per-voxel renderer drop the sprites the mesh replaces
+        chunk.mark_region_dirty(UVec3::ZERO, UVec3::splat(CHUNK_SIZE - 1));
+    }
+}
+
+/// Drop the baked mesh of any frozen chunk that has been edited since
+fn unfreeze_edited_chunks(
+    mut commands: Commands,
+    mut chunks: Query<(Entity, &mut WorldChunk, &FrozenChunkMesh), Changed<WorldChunk>>,
+) {
+    for (entity, mut chunk, frozen_mesh) in chunks.iter_mut() {
+        if chunk.frozen {
+            continue;
+        }
+        commands.entity(frozen_mesh.mesh_entity).despawn();
+        commands.entity(entity).remove::<FrozenChunkMesh>();
+        // The whole chunk goes back to per-voxel sprites, not just the edited part
+        chunk.mark_region_dirty(UVec3::ZERO, UVec3::splat(CHUNK_SIZE - 1));
+    }
+}
+
+/// A visible voxel face, before merging: chunk-local corners and the color at each
+struct BakedFace {
+    normal: IVec3,
+    corners: [IVec3; 4],
+    colors: [[f32; 4]; 4],
+}
+
+impl BakedFace {
+    /// The face's color if all four corners share it, so it can merge with matching neighbours
+    fn uniform_color(&self) -> Option<[f32; 4]> {
+        self.colors.iter().all(|color| *color == self.colors[0]).then_some(self.colors[0])
+    }
+}
+
+/// Build one mesh for a chunk by projecting its `build_chunk_mesh` faces into the isometric view
+/// Only the faces the isometric view can see (+X, +Y, +Z) are kept, colored per voxel with
+/// the same shading, fog and top-face AO as the sprites. Coplanar faces of one flat color are
+/// greedily merged into rectangles; faces with an AO gradient stay single quads. Vertices are
+/// already projected with `IsometricProjectionSettings::world_to_isometric` (z = depth), so the
+/// mesh is drawn as is.
+pub fn build_frozen_chunk_mesh(
+    chunk: &WorldChunk,
+    fog: &IsometricFogSettings,
+    projection: &IsometricProjectionSettings,
+    registry: &MaterialRegistry,
+) -> Mesh {
+    let faces = build_chunk_mesh(chunk, true);
+    let (
+        Some(VertexAttributeValues::Float32x3(local_positions)),
+        Some(VertexAttributeValues::Float32x3(normals)),
+    ) = (faces.attribute(Mesh::ATTRIBUTE_POSITION), faces.attribute(Mesh::ATTRIBUTE_NORMAL))
+    else {
+        unreachable!("build_chunk_mesh always inserts positions and normals");
+    };
+
+    let mut baked: Vec<BakedFace> = Vec::new();
+    let is_solid = |pos: IVec3| chunk_solid(chunk, pos);
+
+    // `build_chunk_mesh` emits four corners per face, the first at the face's minimum corner
+    for (corners, face_normals) in local_positions.chunks_exact(4).zip(normals.chunks_exact(4)) {
+        let normal = Vec3::from(face_normals[0]).as_ivec3();
+        let shade = match normal {
+            IVec3::Y => TOP_FACE_SHADE,
+            IVec3::X => RIGHT_FACE_SHADE,
+            IVec3::Z => LEFT_FACE_SHADE,
+            _ => continue,
+        };
+        let voxel_pos = Vec3::from(corners[0]).as_ivec3() - normal;
+        let Some(voxel) = chunk.get_voxel(voxel_pos.x as u32, voxel_pos.y as u32, voxel_pos.z as u32) else {
+            continue;
+        };
+        let height = chunk.world_bounds.min.y + voxel_pos.y as f32 + 0.5;
+        let color = fog.apply(get_material_color_with_shading(voxel, height, registry), height, voxel.material());
+
+        let corners = [0, 1, 2, 3].map(|i| Vec3::from(corners[i]).as_ivec3());
+        let colors = corners.map(|corner| {
+            // Corner AO on the top face, toward whichever side of the voxel the corner is on
+            let occlusion = if normal == IVec3::Y {
+                let side = (corner - voxel_pos) * 2 - IVec3::ONE;
+                AO_BRIGHTNESS[top_corner_ao(is_solid, voxel_pos, side.x, side.z) as usize]
+            } else {
+                1.0
+            };
+            let shaded = color.to_linear() * (shade * occlusion);
+            [shaded.red, shaded.green, shaded.blue, color.alpha()]
+        });
+        baked.push(BakedFace { normal, corners, colors });
+    }
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 4]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    let mut push_quad = |corners: [IVec3; 4], quad_colors: [[f32; 4]; 4]| {
+        let base = positions.len() as u32;
+        for (corner, color) in corners.into_iter().zip(quad_colors) {
+            positions.push(projection.world_to_isometric(chunk.world_bounds.min + corner.as_vec3()).to_array());
+            colors.push(color);
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    };
+
+    // Faces are visited in `build_chunk_mesh` order (x fastest, then y, then z), so every
+    // face a rectangle could grow back toward along -u or -v has already been taken
+    let face_at: HashMap<(IVec3, IVec3), usize> =
+        baked.iter().enumerate().map(|(i, face)| ((face.normal, face.corners[0]), i)).collect();
+    let mut merged = vec![false; baked.len()];
+    for (i, face) in baked.iter().enumerate() {
+        if merged[i] {
+            continue;
+        }
+        merged[i] = true;
+        let Some(color) = face.uniform_color() else {
+            push_quad(face.corners, face.colors);
+            continue;
+        };
+
+        // Grow along u while the next face matches, then along v while a whole row does
+        let (u, v) = (face.corners[1] - face.corners[0], face.corners[3] - face.corners[0]);
+        let matching = |origin: IVec3, merged: &[bool]| {
+            face_at
+                .get(&(face.normal, origin))
+                .filter(|&&j| !merged[j] && baked[j].uniform_color() == Some(color))
+                .copied()
+        };
+        let mut width = 1;
+        while let Some(j) = matching(face.corners[0] + u * width, &merged) {
+            merged[j] = true;
+            width += 1;
+        }
+        let mut height = 1;
+        loop {
+            let row: Option<Vec<usize>> =
+                (0..width).map(|k| matching(face.corners[0] + u * k + v * height, &merged)).collect();
+            let Some(row) = row else {
+                break;
+            };
+            for j in row {
+                merged[j] = true;
+            }
+            height += 1;
+        }
+
+        let (origin, u, v) = (face.corners[0], u * width, v * height);
+        push_quad([origin, origin + u, origin + u + v, origin + v], [color; 4]);
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::VoxelData;
+
+    #[test]
+    fn test_frozen_mesh_keeps_only_visible_faces() {
+        // A two-voxel rock column shows one top face and two faces on each visible side
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.set_voxel(4, 4, 4, VoxelData::rock(255));
+        chunk.set_voxel(4, 5, 4, VoxelData::rock(255));
+        let projection = IsometricProjectionSettings::default();
+        let mesh = build_frozen_chunk_mesh(&chunk, &IsometricFogSettings::default(), &projection, &MaterialRegistry::default());
+        assert_eq!(mesh.count_vertices(), 5 * 4);
+
+        // The top face sits at the column's top, projected like everything else
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("missing positions");
+        };
+        let top = projection.world_to_isometric(Vec3::new(4.0, 6.0, 4.0)).to_array();
+        assert!(positions.contains(&top));
+    }
+
+    #[test]
+    fn test_frozen_mesh_merges_flat_slab() {
+        // A 16x1x16 rock slab shows 256 top faces and 16 on each visible side
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(8, 4, 8), UVec3::new(24, 5, 24), VoxelData::rock(255));
+        let visible_faces = {
+            let faces = build_chunk_mesh(&chunk, true);
+            let Some(VertexAttributeValues::Float32x3(normals)) = faces.attribute(Mesh::ATTRIBUTE_NORMAL) else {
+                panic!("missing normals");
+            };
+            normals
+                .chunks_exact(4)
+                .filter(|face| [IVec3::X, IVec3::Y, IVec3::Z].contains(&Vec3::from(face[0]).as_ivec3()))
+                .count()
+        };
+        assert_eq!(visible_faces, 16 * 16 + 16 + 16);
+
+        // Nothing sits on the slab, so every side is one flat color and merges into one quad
+        let projection = IsometricProjectionSettings::default();
+        let mesh = build_frozen_chunk_mesh(&chunk, &IsometricFogSettings::default(), &projection, &MaterialRegistry::default());
+        assert_eq!(mesh.count_vertices(), 3 * 4);
+
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("missing positions");
+        };
+        for corner in [Vec3::new(8.0, 5.0, 8.0), Vec3::new(24.0, 5.0, 24.0), Vec3::new(24.0, 4.0, 8.0), Vec3::new(8.0, 4.0, 24.0)] {
+            assert!(positions.contains(&projection.world_to_isometric(corner).to_array()), "no vertex at {corner}");
+        }
+    }
+}
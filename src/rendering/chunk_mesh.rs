@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::VertexFormat;
+use crate::world::{MaterialType, VoxelData, WorldChunk, CHUNK_SIZE};
+
+/// `MaterialType` id of the voxel a vertex belongs to, for shaders that look up their own palette
+pub const ATTRIBUTE_MATERIAL_INDEX: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_MaterialIndex", 1_987_245_311, VertexFormat::Uint32);
+
+/// The six faces of a voxel as (outward normal, u edge, v edge), with u × v = normal
+/// so the corners 0, u, u + v, v wind counter-clockwise seen from outside.
+const VOXEL_FACES: [(IVec3, IVec3, IVec3); 6] = [
+    (IVec3::X, IVec3::Y, IVec3::Z),
+    (IVec3::NEG_X, IVec3::Z, IVec3::Y),
+    (IVec3::Y, IVec3::Z, IVec3::X),
+    (IVec3::NEG_Y, IVec3::X, IVec3::Z),
+    (IVec3::Z, IVec3::X, IVec3::Y),
+    (IVec3::NEG_Z, IVec3::Y, IVec3::X),
+];
+
+/// Whether `neighbour` hides the face of `voxel` it touches
+/// Opaque solids hide everything; otherwise only the same material does, so a body of
+/// water has no inner faces but glass and smoke don't hide what's behind them.
+fn hides_face(voxel: VoxelData, neighbour: VoxelData) -> bool {
+    neighbour.blocks_light() || neighbour.material() == voxel.material()
+}
+
+/// Build a standard 3D mesh of a chunk's voxel faces
+/// Positions are chunk-local (0..`CHUNK_SIZE`), so place the mesh with a transform at
+/// `chunk.world_bounds.min`. Every non-air voxel contributes its faces with normals, UVs
+/// spanning each face and `ATTRIBUTE_MATERIAL_INDEX`. With `face_culling`, faces hidden
+/// by a neighbour inside the chunk are left out; faces on the chunk border are always kept.
+pub fn build_chunk_mesh(chunk: &WorldChunk, face_culling: bool) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut material_indices: Vec<u32> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    let neighbour_at = |pos: IVec3| {
+        if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(IVec3::splat(CHUNK_SIZE as i32)).any() {
+            return None;
+        }
+        chunk.get_voxel(pos.x as u32, pos.y as u32, pos.z as u32)
+    };
+
+    for z in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let Some(voxel) = chunk.get_voxel(x, y, z).filter(|v| v.material() != MaterialType::Air) else {
+                    continue;
+                };
+                let pos = IVec3::new(x as i32, y as i32, z as i32);
+
+                for (normal, u, v) in VOXEL_FACES {
+                    if face_culling && neighbour_at(pos + normal).is_some_and(|n| hides_face(voxel, n)) {
+                        continue;
+                    }
+
+                    // Faces on the positive side sit one voxel further along their normal
+                    let origin = pos + normal.max(IVec3::ZERO);
+                    let base = positions.len() as u32;
+                    for (corner, uv) in [(IVec3::ZERO, [0.0, 0.0]), (u, [1.0, 0.0]), (u + v, [1.0, 1.0]), (v, [0.0, 1.0])] {
+                        positions.push((origin + corner).as_vec3().to_array());
+                        normals.push(normal.as_vec3().to_array());
+                        uvs.push(uv);
+                        material_indices.push(voxel.material() as u32);
+                    }
+                    indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+                }
+            }
+        }
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_attribute(ATTRIBUTE_MATERIAL_INDEX, VertexAttributeValues::Uint32(material_indices))
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_mesh_faces_wind_outward_and_cull() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.set_voxel(4, 4, 4, VoxelData::rock(255));
+        chunk.set_voxel(5, 4, 4, VoxelData::rock(255));
+        chunk.set_voxel(4, 5, 4, VoxelData::glass());
+
+        let face_count = |mesh: &Mesh| mesh.count_vertices() / 4;
+        let mesh = build_chunk_mesh(&chunk, false);
+        assert_eq!(face_count(&mesh), 18);
+
+        // The rocks hide their shared faces and the rock hides the glass's bottom face,
+        // but the glass doesn't hide the top of the rock below it
+        let culled = build_chunk_mesh(&chunk, true);
+        assert_eq!(face_count(&culled), 18 - 3);
+
+        // Each triangle's winding agrees with its normal
+        let Some(VertexAttributeValues::Float32x3(positions)) = culled.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("missing positions");
+        };
+        let Some(VertexAttributeValues::Float32x3(normals)) = culled.attribute(Mesh::ATTRIBUTE_NORMAL) else {
+            panic!("missing normals");
+        };
+        let Some(Indices::U32(indices)) = culled.indices() else {
+            panic!("missing indices");
+        };
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+            let normal = Vec3::from(normals[triangle[0] as usize]);
+            assert!((b - a).cross(c - a).normalize().dot(normal) > 0.99);
+        }
+
+        let Some(VertexAttributeValues::Uint32(materials)) = culled.attribute(ATTRIBUTE_MATERIAL_INDEX) else {
+            panic!("missing material indices");
+        };
+        assert_eq!(materials.iter().filter(|&&m| m == MaterialType::Glass as u32).count(), 5 * 4);
+    }
+}
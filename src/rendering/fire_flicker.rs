@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use crate::simulation::SimulationRng;
+
+/// Largest allowed flicker amount
+const MAX_FLICKER_AMOUNT: f32 = 1.0;
+/// Change in flicker amount per key press
+const FLICKER_AMOUNT_STEP: f32 = 0.05;
+
+/// Settings for the flicker of glowing fire voxels
+#[derive(Resource)]
+pub struct FireFlickerSettings {
+    /// Largest brightness swing as a fraction of the steady glow; 0 keeps fire steady
+    pub flicker_amount: f32,
+    /// Largest change of a voxel's flicker level in one frame, in the same units
+    pub step: f32,
+    /// Pull back toward the steady glow each frame, so the walk doesn't stick at an extreme
+    pub settle: f32,
+}
+
+impl Default for FireFlickerSettings {
+    fn default() -> Self {
+        Self {
+            flicker_amount: 0.25,
+            step: 0.35,
+            settle: 0.1,
+        }
+    }
+}
+
+/// Brightness random walk of one glowing voxel sprite
+/// `color` is the sprite's steady emissive color; the material is rescaled from it.
+#[derive(Component, Clone, Debug)]
+pub struct FireFlicker {
+    pub color: LinearRgba,
+    /// Current position of the walk in -1..=1
+    level: f32,
+}
+
+impl FireFlicker {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: color.to_linear(),
+            level: 0.0,
+        }
+    }
+
+    /// Advance the walk by `random` (-1..1) and return the brightness multiplier
+    pub fn advance(&mut self, random: f32, settings: &FireFlickerSettings) -> f32 {
+        self.level = (self.level * (1.0 - settings.settle) + random * settings.step).clamp(-1.0, 1.0);
+        1.0 + self.level * settings.flicker_amount.max(0.0)
+    }
+}
+
+/// Plugin that makes fire glow shimmer
+/// Each glowing voxel sprite walks its brightness up and down on its own, so a burning
+/// area flickers unevenly instead of pulsing as one. `,`/`.` lower/raise the flicker amount.
+pub struct FireFlickerPlugin;
+
+impl Plugin for FireFlickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FireFlickerSettings>()
+           .add_systems(Update, (control_flicker_amount, flicker_fire).chain());
+    }
+}
+
+/// Adjust the flicker amount from the keyboard
+fn control_flicker_amount(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<FireFlickerSettings>,
+) {
+    if keyboard.just_pressed(KeyCode::Period) {
+        settings.flicker_amount = (settings.flicker_amount + FLICKER_AMOUNT_STEP).min(MAX_FLICKER_AMOUNT);
+        info!("Fire flicker: {:.2}", settings.flicker_amount);
+    }
+    if keyboard.just_pressed(KeyCode::Comma) {
+        settings.flicker_amount = (settings.flicker_amount - FLICKER_AMOUNT_STEP).max(0.0);
+        info!("Fire flicker: {:.2}", settings.flicker_amount);
+    }
+}
+
+/// Rescale each glowing sprite's color by its next flicker step
+/// The walks draw from their own `SimulationRng`, so flicker neither marks the settings
+/// changed nor disturbs the simulation's random sequence.
+fn flicker_fire(
+    settings: Res<FireFlickerSettings>,
+    mut rng: Local<SimulationRng>,
+    mut sprites: Query<(&mut FireFlicker, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Steady fire only needs its colors restored once, right after flicker is turned off
+    if settings.flicker_amount <= 0.0 && !settings.is_changed() {
+        return;
+    }
+
+    for (mut flicker, material) in sprites.iter_mut() {
+        let random = rng.next_f32() * 2.0 - 1.0;
+        let brightness = flicker.advance(random, &settings);
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+        let base = flicker.color;
+        material.color = Color::LinearRgba(LinearRgba::new(
+            base.red * brightness,
+            base.green * brightness,
+            base.blue * brightness,
+            base.alpha,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flicker_stays_within_amount() {
+        let mut settings = FireFlickerSettings::default();
+        let mut rng = SimulationRng::default();
+        let mut flicker = FireFlicker::new(Color::WHITE);
+        let amount = settings.flicker_amount;
+
+        let levels: Vec<f32> = (0..200)
+            .map(|_| {
+                let random = rng.next_f32() * 2.0 - 1.0;
+                flicker.advance(random, &settings)
+            })
+            .collect();
+        assert!(levels.iter().all(|&b| (1.0 - amount..=1.0 + amount).contains(&b)));
+        assert!(levels.windows(2).any(|pair| pair[0] != pair[1]), "flicker never moved");
+
+        // Amount 0 is perfectly steady
+        settings.flicker_amount = 0.0;
+        assert_eq!(flicker.advance(1.0, &settings), 1.0);
+    }
+}
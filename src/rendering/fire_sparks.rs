@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use crate::world::{WorldChunk, MaterialType, CHUNK_SIZE};
+use super::isometric_projection::IsometricProjectionSettings;
+
+/// Settings and state for fire spark particles
+#[derive(Resource)]
+pub struct FireSparkSettings {
+    /// Sparks spawned per second for each burning fire voxel
+    pub sparks_per_fire_voxel: f32,
+    /// Upper bound on sparks spawned in a single frame
+    pub max_spawn_per_frame: usize,
+    /// Lifetime of a spark in seconds
+    pub lifetime: f32,
+    /// Upward drift speed in screen units per second
+    pub rise_speed: f32,
+    /// Fractional spawn carried over between frames
+    spawn_accumulator: f32,
+    /// RNG state for picking fire voxels and jittering sparks
+    seed: u32,
+}
+
+impl Default for FireSparkSettings {
+    fn default() -> Self {
+        Self {
+            sparks_per_fire_voxel: 0.05,
+            max_spawn_per_frame: 32,
+            lifetime: 0.8,
+            rise_speed: 40.0,
+            spawn_accumulator: 0.0,
+            seed: 0x5EED_F1AE,
+        }
+    }
+}
+
+impl FireSparkSettings {
+    /// Next pseudo-random value in 0..1
+    fn random(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (self.seed >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+/// A short-lived spark particle drifting up from a fire voxel
+#[derive(Component)]
+pub struct FireSpark {
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+/// Plugin for rendering-only spark particles emitted from fire voxels
+pub struct FireSparkPlugin;
+
+impl Plugin for FireSparkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FireSparkSettings>()
+           .init_resource::<IsometricProjectionSettings>()
+           .add_systems(Update, (spawn_fire_sparks, update_fire_sparks));
+    }
+}
+
+/// Spawn sparks from randomly selected fire voxels, scaled by the fire voxel count
+fn spawn_fire_sparks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut settings: ResMut<FireSparkSettings>,
+    projection: Res<IsometricProjectionSettings>,
+    chunks: Query<&WorldChunk>,
+) {
+    // Gather world positions of all burning voxels
+    let mut fire_voxels: Vec<Vec3> = Vec::new();
+    for chunk in chunks.iter() {
+        if !chunk.has_dynamic_elements {
+            continue;
+        }
+
+        let chunk_world_pos = chunk.chunk_position.as_vec3() * CHUNK_SIZE as f32;
+        for (idx, voxel) in chunk.voxels.iter().enumerate() {
+            if voxel.material() != MaterialType::Fire {
+                continue;
+            }
+            let idx = idx as u32;
+            let x = idx % CHUNK_SIZE;
+            let y = (idx / CHUNK_SIZE) % CHUNK_SIZE;
+            let z = idx / (CHUNK_SIZE * CHUNK_SIZE);
+            fire_voxels.push(chunk_world_pos + Vec3::new(x as f32, y as f32, z as f32));
+        }
+    }
+
+    if fire_voxels.is_empty() {
+        settings.spawn_accumulator = 0.0;
+        return;
+    }
+
+    // Spawn rate is tied to how much is burning
+    settings.spawn_accumulator +=
+        fire_voxels.len() as f32 * settings.sparks_per_fire_voxel * time.delta_secs();
+    let spawn_count = (settings.spawn_accumulator as usize).min(settings.max_spawn_per_frame);
+    settings.spawn_accumulator -= settings.spawn_accumulator.floor();
+
+    for _ in 0..spawn_count {
+        let pick = (settings.random() * fire_voxels.len() as f32) as usize;
+        let world_pos = fire_voxels[pick.min(fire_voxels.len() - 1)];
+        let iso_pos = projection.world_to_isometric(world_pos);
+
+        let velocity = Vec2::new(
+            (settings.random() - 0.5) * settings.rise_speed * 0.5,
+            settings.rise_speed * (0.5 + settings.random()),
+        );
+        let lifetime = settings.lifetime * (0.5 + settings.random() * 0.5);
+
+        commands.spawn((
+            Sprite {
+                color: Color::srgb(1.0, 0.6, 0.1),
+                custom_size: Some(Vec2::splat(2.0)),
+                ..default()
+            },
+            // Draw sparks in front of the voxel they came from
+            Transform::from_translation(Vec3::new(iso_pos.x, iso_pos.y, iso_pos.z + 1.0)),
+            FireSpark {
+                velocity,
+                age: 0.0,
+                lifetime,
+            },
+        ));
+    }
+}
+
+/// Move sparks upward, fade them orange -> red -> transparent, and despawn when expired
+fn update_fire_sparks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sparks: Query<(Entity, &mut FireSpark, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut spark, mut transform, mut sprite) in sparks.iter_mut() {
+        spark.age += dt;
+        if spark.age >= spark.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += spark.velocity.extend(0.0) * dt;
+
+        let t = spark.age / spark.lifetime;
+        sprite.color = spark_color(t);
+    }
+}
+
+/// Spark color over its normalized lifetime (0 = born, 1 = expired)
+fn spark_color(t: f32) -> Color {
+    let orange = Vec3::new(1.0, 0.6, 0.1);
+    let red = Vec3::new(0.9, 0.1, 0.0);
+    let rgb = orange.lerp(red, (t * 2.0).min(1.0));
+    let alpha = if t < 0.5 { 1.0 } else { 1.0 - (t - 0.5) * 2.0 };
+    Color::srgba(rgb.x, rgb.y, rgb.z, alpha)
+}
@@ -1,7 +1,26 @@
 use bevy::prelude::*;
+use bevy::image::TextureFormatPixelInfo;
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::*;
-use crate::world::chunk::{WorldChunk, CHUNK_SIZE};
+use crate::world::chunk::{WorldChunk, CHUNK_SIZE, VOXELS_PER_CHUNK};
+use crate::world::VoxelData;
+
+/// Expected byte length of a chunk's R32Uint 3D texture
+pub const CHUNK_TEXTURE_BYTES: usize = VOXELS_PER_CHUNK * std::mem::size_of::<u32>();
+
+/// Pack chunk voxels as little-endian u32 bytes for the R32Uint texture
+pub fn chunk_texture_bytes(chunk: &WorldChunk) -> Vec<u8> {
+    let mut data = vec![0; CHUNK_TEXTURE_BYTES];
+    write_chunk_texture_bytes(chunk, &mut data);
+    data
+}
+
+/// Pack chunk voxels into an existing texture buffer, so re-uploads don't allocate
+pub fn write_chunk_texture_bytes(chunk: &WorldChunk, data: &mut [u8]) {
+    for (texel, voxel) in data.chunks_exact_mut(4).zip(&chunk.voxels) {
+        texel.copy_from_slice(&voxel.as_u32().to_le_bytes());
+    }
+}
 
 /// Upload chunk voxel data to GPU as a 3D texture
 pub fn create_chunk_texture(
@@ -9,10 +28,7 @@ pub fn create_chunk_texture(
     images: &mut Assets<Image>,
 ) -> Handle<Image> {
     // Convert voxel data to bytes for GPU upload
-    let voxel_data: Vec<u8> = chunk.voxels
-        .iter()
-        .flat_map(|v| v.as_u32().to_le_bytes())
-        .collect();
+    let voxel_data = chunk_texture_bytes(chunk);
     
     // Create 3D texture
     let mut image = Image::new(
@@ -24,7 +40,8 @@ pub fn create_chunk_texture(
         TextureDimension::D3,
         voxel_data,
         TextureFormat::R32Uint, // Store packed u32 voxel data
-        RenderAssetUsages::RENDER_WORLD,
+        // Keep the data in the main world so `update_chunk_textures` can rewrite it
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
     );
     
     // Set texture sampling parameters
@@ -41,6 +58,63 @@ pub fn create_chunk_texture(
     images.add(image)
 }
 
+/// Density byte of one voxel: 255 for solid materials, 0 otherwise
+fn voxel_density_byte(voxel: &VoxelData) -> u8 {
+    if voxel.material().is_solid() { u8::MAX } else { 0 }
+}
+
+/// One byte per voxel for the R8Unorm density texture, in the same order as `voxels`
+pub fn chunk_density_bytes(chunk: &WorldChunk) -> Vec<u8> {
+    chunk.voxels.iter().map(voxel_density_byte).collect()
+}
+
+/// Rewrite the density bytes of the voxels in an inclusive local range
+pub fn write_chunk_density_region(chunk: &WorldChunk, data: &mut [u8], min: UVec3, max: UVec3) {
+    for z in min.z..=max.z {
+        for y in min.y..=max.y {
+            let first = ((z * CHUNK_SIZE + y) * CHUNK_SIZE + min.x) as usize;
+            let last = ((z * CHUNK_SIZE + y) * CHUNK_SIZE + max.x) as usize;
+            for (texel, voxel) in data[first..=last].iter_mut().zip(&chunk.voxels[first..=last]) {
+                *texel = voxel_density_byte(voxel);
+            }
+        }
+    }
+}
+
+/// Upload a chunk's solid/empty mask as a linearly filtered density texture
+/// Unlike the packed R32Uint texture this can be sampled trilinearly, so it plugs into the
+/// volume raymarching shader the same way `create_volume_texture` does for `Volume`s.
+pub fn create_chunk_density_texture(
+    chunk: &WorldChunk,
+    images: &mut Assets<Image>,
+) -> Handle<Image> {
+    let mut image = Image::new(
+        Extent3d {
+            width: CHUNK_SIZE,
+            height: CHUNK_SIZE,
+            depth_or_array_layers: CHUNK_SIZE,
+        },
+        TextureDimension::D3,
+        chunk_density_bytes(chunk),
+        TextureFormat::R8Unorm,
+        // Kept in the main world so `update_chunk_textures` can patch it with the voxel texture
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+
+    // Linear filtering smooths the voxel steps into a continuous density field
+    image.sampler = bevy::image::ImageSampler::Descriptor(bevy::image::ImageSamplerDescriptor {
+        address_mode_u: bevy::image::ImageAddressMode::ClampToEdge,
+        address_mode_v: bevy::image::ImageAddressMode::ClampToEdge,
+        address_mode_w: bevy::image::ImageAddressMode::ClampToEdge,
+        mag_filter: bevy::image::ImageFilterMode::Linear,
+        min_filter: bevy::image::ImageFilterMode::Linear,
+        mipmap_filter: bevy::image::ImageFilterMode::Linear,
+        ..default()
+    });
+
+    images.add(image)
+}
+
 /// Create output render targets (position, normal, diffuse)
 pub fn create_render_targets(
     width: u32,
@@ -55,9 +129,10 @@ pub fn create_render_targets(
                 depth_or_array_layers: 1,
             },
             TextureDimension::D2,
-            vec![0u8; (width * height * 4) as usize],
+            vec![0u8; (width * height) as usize * format.pixel_size()],
             format,
-            RenderAssetUsages::RENDER_WORLD,
+            // Kept in the main world so CPU passes like `export_world_maps` can write them
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
         );
         img.texture_descriptor.usage = 
             TextureUsages::STORAGE_BINDING | 
@@ -91,3 +166,31 @@ impl Plugin for GpuRendererPlugin {
         // TODO: Add render world systems for compute-based rendering
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_density_bytes_follow_voxel_layout() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.set_voxel(1, 2, 3, VoxelData::rock(255));
+        chunk.set_voxel(4, 2, 3, VoxelData::water_source());
+        let index = |x: u32, y: u32, z: u32| ((z * CHUNK_SIZE + y) * CHUNK_SIZE + x) as usize;
+
+        // One byte per voxel, x fastest; only solids are dense
+        let bytes = chunk_density_bytes(&chunk);
+        assert_eq!(bytes.len(), VOXELS_PER_CHUNK);
+        assert_eq!(bytes[index(1, 2, 3)], u8::MAX);
+        assert_eq!(bytes[index(4, 2, 3)], 0);
+        assert_eq!(bytes.iter().filter(|&&b| b != 0).count(), 1);
+
+        // Patching a region only rewrites the voxels inside it
+        chunk.set_voxel(1, 2, 3, VoxelData::air());
+        chunk.set_voxel(9, 9, 9, VoxelData::rock(255));
+        let mut patched = bytes.clone();
+        write_chunk_density_region(&chunk, &mut patched, UVec3::new(0, 0, 0), UVec3::new(4, 4, 4));
+        assert_eq!(patched[index(1, 2, 3)], 0);
+        assert_eq!(patched[index(9, 9, 9)], 0);
+    }
+}
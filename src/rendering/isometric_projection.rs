@@ -57,3 +57,97 @@ fn update_camera_transform(
         transform.translation = Vec3::new(x, y, z);
     }
 }
+
+/// Tunables of the isometric projection
+/// Read whenever voxel sprites, baked chunk meshes and sparks are placed, so change it
+/// before the world is drawn; already placed geometry keeps its old depth.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct IsometricProjectionSettings {
+    /// Draw depth gained per voxel step toward the viewer
+    /// The view looks along -(1, 1, 1), so a voxel one step closer in any axis sits
+    /// this much further in front. Keep it small enough that the whole world's depth
+    /// range stays inside the camera's near/far planes.
+    pub depth_scale: f32,
+}
+
+impl Default for IsometricProjectionSettings {
+    fn default() -> Self {
+        Self { depth_scale: 0.1 }
+    }
+}
+
+impl IsometricProjectionSettings {
+    /// Back-to-front draw key for a world position
+    /// Of two voxels covering the same pixel, the one with the larger x + y + z is
+    /// always the nearer one, so this orders them strictly.
+    pub fn depth(&self, world_pos: Vec3) -> f32 {
+        (world_pos.x + world_pos.y + world_pos.z) * self.depth_scale
+    }
+
+    /// Convert 3D world position to isometric screen position and draw depth
+    /// Returns (screen x, screen y, depth) where larger depth draws in front.
+    pub fn world_to_isometric(&self, world_pos: Vec3) -> Vec3 {
+        isometric_screen_position(world_pos).extend(self.depth(world_pos))
+    }
+}
+
+/// Convert 3D world position to 2D isometric screen position
+/// Uses classic isometric projection (Diablo/SimCity style)
+pub fn isometric_screen_position(world_pos: Vec3) -> Vec2 {
+    // Isometric projection: 
+    // Looking from above-right, so positive X goes down-right, positive Z goes down-left
+    // and world up (+Y) goes up the screen
+    // This matches a 2:1 pixel ratio isometric view
+    
+    let iso_x = world_pos.x - world_pos.z;
+    let iso_y = world_pos.y - (world_pos.x + world_pos.z) * 0.5;
+    
+    Vec2::new(iso_x, iso_y)
+}
+
+/// Invert `isometric_screen_position` onto the plane where the projection's height axis
+/// (world Y, which raises screen y) equals `height`
+///
+/// The projection is not invertible on its own: every point along (1, 1, 1)
+/// lands on the same screen position, so a screen point is a line through the
+/// world. Fixing the height picks one point on that line; use a raycast along
+/// (1, 1, 1) when the actual surface under the cursor is needed.
+pub fn isometric_to_world_plane(screen: Vec2, height: f32) -> Vec3 {
+    // screen.x = x - z, screen.y = height - (x + z) / 2
+    let sum_xz = (height - screen.y) * 2.0;
+    Vec3::new((sum_xz + screen.x) * 0.5, height, (sum_xz - screen.x) * 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_orders_voxels_toward_viewer() {
+        let projection = IsometricProjectionSettings::default();
+        let base = Vec3::new(4.0, 2.0, 7.0);
+        let depth = projection.depth(base);
+
+        // One step toward the viewer along any axis draws in front
+        for step in [Vec3::X, Vec3::Y, Vec3::Z] {
+            assert!(projection.depth(base + step) > depth);
+        }
+
+        // A voxel stacked on top is in front of the one under it, by a full step
+        let above = projection.depth(base + Vec3::Y);
+        assert!((above - depth - projection.depth_scale).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_higher_voxels_render_higher_on_screen() {
+        let base = Vec3::new(4.0, 2.0, 7.0);
+        let screen = isometric_screen_position(base);
+        let above = isometric_screen_position(base + Vec3::Y);
+        assert_eq!(above.x, screen.x);
+        assert!(above.y > screen.y);
+
+        // The plane inversion lands back on the same point
+        let on_plane = isometric_to_world_plane(screen, base.y);
+        assert!(on_plane.distance(base) < 1e-5);
+    }
+}
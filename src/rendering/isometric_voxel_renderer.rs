@@ -1,10 +1,84 @@
 use bevy::prelude::*;
-use crate::world::{WorldChunk, MaterialType, CHUNK_SIZE};
+use crate::world::{
+    emit_chunk_dirty_events, spatial_order_key, ChunkDirty, ChunkManager, MaterialRegistry, WorldChunk,
+    MaterialType, VoxelData, CHUNK_SIZE,
+};
+use super::isometric_projection::IsometricProjectionSettings;
+use super::fire_flicker::FireFlicker;
+use super::oit::{blends_order_independent, oit_voxel_bundles, OitVoxelMaterial};
+use super::voxel_ao::{top_face_ao, AO_BRIGHTNESS};
 
-/// Resource to cache the isometric cube mesh
+/// Resource to cache the isometric cube meshes
+/// There is one mesh per combination of corner AO levels, indexed by `ao_mesh_index`.
 #[derive(Resource)]
 struct IsometricMeshCache {
-    cube_mesh: Handle<Mesh>,
+    ao_meshes: Vec<Handle<Mesh>>,
+}
+
+/// Index into `IsometricMeshCache::ao_meshes` for four corner AO levels (2 bits each)
+fn ao_mesh_index(ao: [u8; 4]) -> usize {
+    ao.iter().enumerate().map(|(i, &level)| (level as usize & 3) << (i * 2)).sum()
+}
+
+/// Height-based fog for the isometric view
+/// Voxels at or above `fog_start` are unaffected and blend fully into
+/// `fog_color` by the time they reach `fog_end` (which lies below `fog_start`).
+#[derive(Resource, Clone)]
+pub struct IsometricFogSettings {
+    pub enabled: bool,
+    pub fog_color: Color,
+    /// Height where fog begins
+    pub fog_start: f32,
+    /// Height where fog is fully opaque
+    pub fog_end: f32,
+    /// How strongly emissive materials (fire) resist the fog (0 = not at all, 1 = fully)
+    pub emissive_resistance: f32,
+}
+
+impl Default for IsometricFogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fog_color: Color::srgb(0.55, 0.6, 0.7),
+            fog_start: 32.0,
+            fog_end: -32.0,
+            emissive_resistance: 0.8,
+        }
+    }
+}
+
+impl IsometricFogSettings {
+    /// Fog blend factor (0 = clear, 1 = fully fogged) for a voxel at the given height
+    pub fn fog_factor(&self, height: f32, material: MaterialType) -> f32 {
+        if !self.enabled || self.fog_start == self.fog_end {
+            return 0.0;
+        }
+
+        let factor = ((self.fog_start - height) / (self.fog_start - self.fog_end)).clamp(0.0, 1.0);
+
+        if material == MaterialType::Fire {
+            factor * (1.0 - self.emissive_resistance.clamp(0.0, 1.0))
+        } else {
+            factor
+        }
+    }
+
+    /// Blend a color toward the fog color, keeping its alpha
+    pub fn apply(&self, color: Color, height: f32, material: MaterialType) -> Color {
+        let factor = self.fog_factor(height, material);
+        if factor <= 0.0 {
+            return color;
+        }
+
+        let base = color.to_srgba();
+        let fog = self.fog_color.to_srgba();
+        Color::srgba(
+            base.red + (fog.red - base.red) * factor,
+            base.green + (fog.green - base.green) * factor,
+            base.blue + (fog.blue - base.blue) * factor,
+            base.alpha,
+        )
+    }
 }
 
 /// Plugin for rendering voxels in isometric projection
@@ -12,8 +86,10 @@ pub struct IsometricVoxelRendererPlugin;
 
 impl Plugin for IsometricVoxelRendererPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_mesh_cache)
-           .add_systems(Update, render_voxels_isometric);
+        app.init_resource::<IsometricFogSettings>()
+           .init_resource::<IsometricProjectionSettings>()
+           .add_systems(Startup, setup_mesh_cache)
+           .add_systems(Update, render_voxels_isometric.after(emit_chunk_dirty_events));
     }
 }
 
@@ -22,56 +98,117 @@ fn setup_mesh_cache(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
-    let cube_mesh = meshes.add(create_isometric_cube_mesh());
-    commands.insert_resource(IsometricMeshCache { cube_mesh });
+    let ao_meshes = (0..256usize)
+        .map(|index| {
+            let ao = [0, 1, 2, 3].map(|corner| ((index >> (corner * 2)) & 3) as u8);
+            meshes.add(create_isometric_cube_mesh(ao))
+        })
+        .collect();
+    commands.insert_resource(IsometricMeshCache { ao_meshes });
 }
 
 /// Marker component for isometric voxel sprites
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct IsometricVoxelSprite {
     pub chunk_entity: Entity,
     pub voxel_pos: UVec3,
 }
 
 /// Render voxels in isometric projection
-/// Uses diamond/cube sprites with depth sorting
+/// Uses diamond/cube sprites with depth sorting. Only the regions reported by
+/// `ChunkDirty` events are rebuilt.
+#[allow(clippy::too_many_arguments)]
 fn render_voxels_isometric(
     mut commands: Commands,
-    chunks: Query<(Entity, &WorldChunk), Changed<WorldChunk>>,
-    existing_sprites: Query<Entity, With<IsometricVoxelSprite>>,
+    mut dirty_events: EventReader<ChunkDirty>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&WorldChunk>,
+    existing_sprites: Query<(Entity, &IsometricVoxelSprite)>,
     mesh_cache: Res<IsometricMeshCache>,
+    fog: Res<IsometricFogSettings>,
+    projection: Res<IsometricProjectionSettings>,
+    registry: Res<MaterialRegistry>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut oit_materials: ResMut<Assets<OitVoxelMaterial>>,
 ) {
+    // Merge this frame's events into one local region per chunk
+    let mut regions: std::collections::HashMap<Entity, (IVec3, UVec3, UVec3)> = Default::default();
+    for event in dirty_events.read() {
+        let Some(entity) = chunk_manager.get_chunk_entity(event.chunk_pos) else {
+            continue;
+        };
+        // Corner AO reads one voxel past the edit, so neighbouring sprites are redone too
+        let (min, max) = event.local_range();
+        let min = min.saturating_sub(UVec3::ONE);
+        let max = (max + UVec3::ONE).min(UVec3::splat(CHUNK_SIZE - 1));
+        regions
+            .entry(entity)
+            .and_modify(|region| {
+                region.1 = region.1.min(min);
+                region.2 = region.2.max(max);
+            })
+            .or_insert((event.chunk_pos, min, max));
+    }
+
     // Only rebuild when chunks change
-    if chunks.is_empty() {
+    if regions.is_empty() {
         return;
     }
     
-    // Clear old sprites
-    for entity in existing_sprites.iter() {
-        commands.entity(entity).despawn();
+    // Clear old sprites inside the changed regions
+    for (entity, sprite) in existing_sprites.iter() {
+        let Some(&(_, min, max)) = regions.get(&sprite.chunk_entity) else {
+            continue;
+        };
+        if sprite.voxel_pos.cmpge(min).all() && sprite.voxel_pos.cmple(max).all() {
+            commands.entity(entity).despawn();
+        }
     }
     
-    // Render all chunks
-    for (chunk_entity, chunk) in chunks.iter() {
+    // Render all regions in a stable spatial order so draw order doesn't vary between runs
+    let mut sorted_regions: Vec<(Entity, IVec3, UVec3, UVec3)> = regions
+        .into_iter()
+        .map(|(entity, (chunk_pos, min, max))| (entity, chunk_pos, min, max))
+        .collect();
+    sorted_regions.sort_unstable_by_key(|(_, chunk_pos, _, _)| spatial_order_key(*chunk_pos));
+    for (chunk_entity, _, min, max) in sorted_regions {
+        let Ok(chunk) = chunks.get(chunk_entity) else {
+            continue;
+        };
         render_chunk_isometric(
             &mut commands,
             chunk_entity,
             chunk,
-            &mesh_cache.cube_mesh,
+            (min, max),
+            &mesh_cache,
+            &fog,
+            &projection,
+            &registry,
             &mut materials,
+            &mut oit_materials,
         );
     }
 }
 
-/// Render a single chunk in isometric view
+/// Render the voxels of a chunk within an inclusive local region in isometric view
+#[allow(clippy::too_many_arguments)]
 fn render_chunk_isometric(
     commands: &mut Commands,
     chunk_entity: Entity,
     chunk: &WorldChunk,
-    cube_mesh: &Handle<Mesh>,
+    (min, max): (UVec3, UVec3),
+    mesh_cache: &IsometricMeshCache,
+    fog: &IsometricFogSettings,
+    projection: &IsometricProjectionSettings,
+    registry: &MaterialRegistry,
     materials: &mut Assets<ColorMaterial>,
+    oit_materials: &mut Assets<OitVoxelMaterial>,
 ) {
+    // Frozen chunks are drawn by their baked mesh
+    if chunk.frozen {
+        return;
+    }
+
     let chunk_world_pos = chunk.chunk_position.as_vec3() * CHUNK_SIZE as f32;
     
     // Dynamic sample rate: render more detail for chunks with dynamic elements
@@ -81,9 +218,11 @@ fn render_chunk_isometric(
         4  // Skip most voxels for static chunks
     };
     
-    for z in (0..CHUNK_SIZE).step_by(sample_rate) {
-        for y in (0..CHUNK_SIZE).step_by(sample_rate) {
-            for x in (0..CHUNK_SIZE).step_by(sample_rate) {
+    // Start on the sample grid so partial rebuilds line up with earlier ones
+    let start = min.map(|v| v.next_multiple_of(sample_rate as u32));
+    for z in (start.z..=max.z).step_by(sample_rate) {
+        for y in (start.y..=max.y).step_by(sample_rate) {
+            for x in (start.x..=max.x).step_by(sample_rate) {
                 if let Some(voxel) = chunk.get_voxel(x, y, z) {
                     let material = voxel.material();
                     
@@ -95,51 +234,63 @@ fn render_chunk_isometric(
                     let world_pos = chunk_world_pos + Vec3::new(x as f32, y as f32, z as f32);
                     
                     // Get base color with height-based shading
-                    let color = get_material_color_with_shading(material, world_pos.y);
+                    let color = get_material_color_with_shading(voxel, world_pos.y, registry);
+                    let color = fog.apply(color, world_pos.y, material);
+                    let strength = emission_strength(chunk, UVec3::new(x, y, z), material, registry);
+                    let color = apply_emission(color, strength);
                     
+                    // Soft contact shadows where solids meet; liquids and gases stay flat
+                    let ao = if material.is_solid() {
+                        top_face_ao(chunk, UVec3::new(x, y, z))
+                    } else {
+                        [3; 4]
+                    };
+                    let mesh = mesh_cache.ao_meshes[ao_mesh_index(ao)].clone();
+
                     // Convert 3D position to isometric 2D coordinates
-                    let iso_pos = world_to_isometric(world_pos);
-                    
-                    // Spawn isometric sprite
-                    commands.spawn((
-                        Mesh2d(cube_mesh.clone()),
-                        MeshMaterial2d(materials.add(ColorMaterial { color, ..default() })),
+                    let iso_pos = projection.world_to_isometric(world_pos);
+                    let sprite = IsometricVoxelSprite {
+                        chunk_entity,
+                        voxel_pos: UVec3::new(x, y, z),
+                    };
+
+                    // Smoke goes through the OIT buffers so stacked voxels blend in any order
+                    if blends_order_independent(material) {
+                        for bundle in oit_voxel_bundles(mesh, color, oit_materials) {
+                            commands.spawn((bundle, Transform::from_translation(iso_pos), sprite));
+                        }
+                        continue;
+                    }
+
+                    // Spawn isometric sprite; fully opaque ones write depth, which hides the
+                    // resolved smoke behind them
+                    let mut entity = commands.spawn((
+                        Mesh2d(mesh),
+                        MeshMaterial2d(materials.add(ColorMaterial::from(color))),
                         Transform::from_translation(Vec3::new(iso_pos.x, iso_pos.y, iso_pos.z)),
-                        IsometricVoxelSprite {
-                            chunk_entity,
-                            voxel_pos: UVec3::new(x, y, z),
-                        },
+                        sprite,
                     ));
+                    // Glowing voxels shimmer like the fire they are
+                    if strength > 1.0 {
+                        entity.insert(FireFlicker::new(color));
+                    }
                 }
             }
         }
     }
 }
 
-/// Convert 3D world position to 2D isometric screen position
-/// Uses classic isometric projection (Diablo/SimCity style)
-fn world_to_isometric(world_pos: Vec3) -> Vec3 {
-    // Isometric projection: 
-    // Looking from above-right, so positive X goes right, positive Z goes up-left
-    // This matches a 2:1 pixel ratio isometric view
-    
-    let iso_x = world_pos.x - world_pos.z;
-    let iso_y = (world_pos.x + world_pos.z) * 0.5 - world_pos.y;
-    
-    // Z coordinate for depth sorting (further back = lower z)
-    let depth = world_pos.y - world_pos.x * 0.01 - world_pos.z * 0.01;
-    
-    Vec3::new(iso_x, iso_y, depth)
-}
+/// Width and height of the diamond drawn for each voxel, in screen units
+pub const VOXEL_SPRITE_SIZE: f32 = 4.0;
 
 /// Create a small diamond/cube shape for isometric voxels
-fn create_isometric_cube_mesh() -> Mesh {
+/// `ao` darkens each vertex through its color, which `ColorMaterial` multiplies in.
+fn create_isometric_cube_mesh(ao: [u8; 4]) -> Mesh {
     // Create an isometric diamond/rhombus shape
     // This represents a cube viewed from 45° angle
     
     // Diamond points (rhombus for isometric view)
-    let size = 4.0;
-    let half = size / 2.0;
+    let half = VOXEL_SPRITE_SIZE / 2.0;
     
     // Isometric diamond vertices
     let vertices = vec![
@@ -170,6 +321,15 @@ fn create_isometric_cube_mesh() -> Mesh {
         [0.0, 0.0, 1.0],
         [0.0, 0.0, 1.0],
     ];
+
+    // Vertex order matches `top_face_ao`
+    let colors: Vec<[f32; 4]> = ao
+        .iter()
+        .map(|&level| {
+            let brightness = AO_BRIGHTNESS[level as usize];
+            [brightness, brightness, brightness, 1.0]
+        })
+        .collect();
     
     Mesh::new(
         bevy::render::render_resource::PrimitiveTopology::TriangleList,
@@ -178,31 +338,76 @@ fn create_isometric_cube_mesh() -> Mesh {
     .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
     .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
     .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
     .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
 }
 
-/// Get color for material type
-fn get_material_color(material: MaterialType) -> Color {
-    match material {
+/// Get color for a voxel (registry palette, fire tinted by temperature)
+fn get_material_color(voxel: VoxelData, registry: &MaterialRegistry) -> Color {
+    match voxel.material() {
         MaterialType::Air => Color::NONE,
-        MaterialType::Rock => Color::srgb(0.5, 0.5, 0.5),
-        MaterialType::Dirt => Color::srgb(0.6, 0.4, 0.2),
-        MaterialType::Wood => Color::srgb(0.6, 0.4, 0.1),
-        MaterialType::Metal => Color::srgb(0.7, 0.7, 0.8),
-        MaterialType::Fire => Color::srgb(1.0, 0.5, 0.0),
-        MaterialType::Smoke => Color::srgba(0.3, 0.3, 0.3, 0.6),
-        MaterialType::Water => Color::srgb(0.2, 0.4, 0.8),
-        MaterialType::Debris => Color::srgb(0.6, 0.5, 0.4),
+        MaterialType::Fire => fire_color(voxel.heat()),
+        material => registry.color(material),
     }
 }
 
+/// Emissive multiplier for a voxel, dimmed to 1.0 when smoke covers it from the viewer
+/// Smoke is drawn translucent over fire, so a glowing voxel beneath it would bloom through.
+fn emission_strength(chunk: &WorldChunk, local: UVec3, material: MaterialType, registry: &MaterialRegistry) -> f32 {
+    let strength = registry.emissive_strength(material);
+    if strength <= 1.0 {
+        return strength;
+    }
+
+    // The camera looks down from +X/+Y/+Z, so those neighbours are drawn on top
+    let covered = [UVec3::X, UVec3::Y, UVec3::Z].into_iter().any(|offset| {
+        let neighbour = local + offset;
+        chunk
+            .get_voxel(neighbour.x, neighbour.y, neighbour.z)
+            .is_some_and(|voxel| voxel.material() == MaterialType::Smoke)
+    });
+
+    if covered { 1.0 } else { strength }
+}
+
+/// Scale a color's linear RGB into the HDR range, keeping its alpha
+pub fn apply_emission(color: Color, strength: f32) -> Color {
+    if strength == 1.0 {
+        return color;
+    }
+
+    let linear = color.to_linear();
+    Color::LinearRgba(LinearRgba::new(
+        linear.red * strength,
+        linear.green * strength,
+        linear.blue * strength,
+        linear.alpha,
+    ))
+}
+
+/// Blackbody-style fire ramp: dull red when cool, orange, yellow, then near white when hottest
+fn fire_color(temperature: u8) -> Color {
+    const RAMP: [Vec3; 4] = [
+        Vec3::new(0.5, 0.05, 0.0),  // Dull red
+        Vec3::new(1.0, 0.45, 0.0),  // Orange
+        Vec3::new(1.0, 0.85, 0.2),  // Yellow
+        Vec3::new(1.0, 1.0, 0.9),   // Yellow-white
+    ];
+
+    let t = temperature as f32 / 255.0 * (RAMP.len() - 1) as f32;
+    let segment = (t as usize).min(RAMP.len() - 2);
+    let rgb = RAMP[segment].lerp(RAMP[segment + 1], t - segment as f32);
+    Color::srgb(rgb.x, rgb.y, rgb.z)
+}
+
 /// Get color with height-based shading for depth perception
-fn get_material_color_with_shading(material: MaterialType, height: f32) -> Color {
-    let mut base_color = get_material_color(material);
+pub fn get_material_color_with_shading(voxel: VoxelData, height: f32, registry: &MaterialRegistry) -> Color {
+    let material = voxel.material();
+    let mut base_color = get_material_color(voxel, registry);
     
     // Skip shading for emissive/transparent materials
     match material {
-        MaterialType::Fire | MaterialType::Smoke | MaterialType::Water => return base_color,
+        MaterialType::Fire | MaterialType::Smoke | MaterialType::Water | MaterialType::Glass => return base_color,
         _ => {}
     }
     
@@ -210,11 +415,11 @@ fn get_material_color_with_shading(material: MaterialType, height: f32) -> Color
     let shade_factor = 0.8 + (height / 64.0) * 0.4; // 0.8 to 1.2 range
     let shade_factor = shade_factor.clamp(0.7, 1.3);
     
-    // Apply shading to RGB channels
+    // Apply shading to RGB channels, capped so only emissive materials can bloom
     if let Color::Srgba(srgba) = &mut base_color {
-        srgba.red *= shade_factor;
-        srgba.green *= shade_factor;
-        srgba.blue *= shade_factor;
+        srgba.red = (srgba.red * shade_factor).min(1.0);
+        srgba.green = (srgba.green * shade_factor).min(1.0);
+        srgba.blue = (srgba.blue * shade_factor).min(1.0);
     }
     
     base_color
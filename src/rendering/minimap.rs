@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy::image::ImageSampler;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::HashMap;
+use crate::world::{ChunkDirty, WorldChunk, ChunkManager, MaterialType};
+use super::isometric_projection::isometric_to_world_plane;
+use super::oit::OitCamera;
+
+/// Resource holding the minimap image and its layout
+#[derive(Resource)]
+pub struct Minimap {
+    pub image: Handle<Image>,
+    /// Number of chunks shown in each direction around the camera
+    pub radius: i32,
+    /// Pixels per chunk cell
+    pub cell_size: u32,
+    /// Camera chunk the minimap was last drawn around
+    last_camera_chunk: Option<IVec2>,
+}
+
+impl Minimap {
+    /// Width/height of the minimap image in pixels
+    pub fn image_size(&self) -> u32 {
+        (self.radius as u32 * 2 + 1) * self.cell_size
+    }
+}
+
+/// Marker for the minimap UI node
+#[derive(Component)]
+pub struct MinimapDisplay;
+
+/// Plugin that draws loaded chunks as a small corner map
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_minimap)
+           .add_systems(Update, update_minimap);
+    }
+}
+
+const BACKGROUND_COLOR: [u8; 4] = [10, 10, 14, 200];
+const EMPTY_CHUNK_COLOR: [u8; 4] = [40, 40, 50, 255];
+const DYNAMIC_CHUNK_COLOR: [u8; 4] = [255, 120, 20, 255];
+const CAMERA_COLOR: [u8; 4] = [255, 255, 255, 255];
+
+fn setup_minimap(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let radius = 4;
+    let cell_size = 8;
+    let size = (radius as u32 * 2 + 1) * cell_size;
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &BACKGROUND_COLOR,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    let handle = images.add(image);
+
+    commands.spawn((
+        ImageNode::new(handle.clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            right: Val::Px(12.0),
+            width: Val::Px(size as f32 * 2.0),
+            height: Val::Px(size as f32 * 2.0),
+            ..default()
+        },
+        MinimapDisplay,
+    ));
+
+    commands.insert_resource(Minimap {
+        image: handle,
+        radius,
+        cell_size,
+        last_camera_chunk: None,
+    });
+}
+
+/// Redraw the minimap when chunks load/unload/change or the camera enters a new chunk
+fn update_minimap(
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&WorldChunk>,
+    mut dirty_events: EventReader<ChunkDirty>,
+    camera: Query<&Transform, (With<Camera2d>, Without<OitCamera>)>,
+) {
+    // The camera sits in isometric screen space; find the ground point it looks at
+    let camera_pos = camera.single().map(|t| t.translation.truncate()).unwrap_or(Vec2::ZERO);
+    let camera_chunk = ChunkManager::world_to_chunk_pos(isometric_to_world_plane(camera_pos, 0.0)).xz();
+
+    let camera_moved = minimap.last_camera_chunk != Some(camera_chunk);
+    let chunks_dirty = dirty_events.read().count() > 0;
+    if !camera_moved && !chunk_manager.is_changed() && !chunks_dirty {
+        return;
+    }
+    minimap.last_camera_chunk = Some(camera_chunk);
+
+    // Collapse loaded chunks into columns, keeping the highest chunk with a visible surface
+    let mut columns: HashMap<IVec2, (i32, [u8; 4], bool)> = HashMap::new();
+    for (chunk_pos, entity) in chunk_manager.chunks_in_order() {
+        let Ok(chunk) = chunks.get(entity) else {
+            continue;
+        };
+
+        let surface = chunk.dominant_surface_material();
+        let column = columns
+            .entry(chunk_pos.xz())
+            .or_insert((i32::MIN, EMPTY_CHUNK_COLOR, false));
+
+        column.2 |= chunk.has_dynamic_elements;
+        if surface != MaterialType::Air && chunk_pos.y >= column.0 {
+            column.0 = chunk_pos.y;
+            column.1 = surface.default_color().to_srgba().to_u8_array();
+        }
+    }
+
+    let size = minimap.image_size();
+    let cell_size = minimap.cell_size;
+    let radius = minimap.radius;
+    let Some(image) = images.get_mut(&minimap.image) else {
+        return;
+    };
+    let Some(data) = image.data.as_mut() else {
+        return;
+    };
+
+    let mut fill_cell = |cell: IVec2, inset: u32, color: [u8; 4]| {
+        // Image rows go down, world +Z goes up
+        let px0 = cell.x as u32 * cell_size;
+        let py0 = (radius * 2 - cell.y) as u32 * cell_size;
+        for py in (py0 + inset)..(py0 + cell_size - inset) {
+            for px in (px0 + inset)..(px0 + cell_size - inset) {
+                let idx = ((py * size + px) * 4) as usize;
+                data[idx..idx + 4].copy_from_slice(&color);
+            }
+        }
+    };
+
+    for cy in -radius..=radius {
+        for cx in -radius..=radius {
+            let cell = IVec2::new(cx + radius, cy + radius);
+            let color = match columns.get(&(camera_chunk + IVec2::new(cx, cy))) {
+                Some((_, _, true)) => DYNAMIC_CHUNK_COLOR,
+                Some((_, color, false)) => *color,
+                None => BACKGROUND_COLOR,
+            };
+            // Leave a one pixel gap around each cell so chunk borders stay readable
+            fill_cell(cell, 0, BACKGROUND_COLOR);
+            fill_cell(cell, 1, color);
+        }
+    }
+
+    // Mark the camera's chunk in the center
+    let inset = cell_size / 2 - 1;
+    fill_cell(IVec2::splat(radius), inset, CAMERA_COLOR);
+}
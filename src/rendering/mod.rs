@@ -6,8 +6,28 @@ pub mod isometric_projection;
 pub mod isometric_voxel_renderer;
 pub mod gpu_renderer;
 pub mod material;
+pub mod fire_sparks;
+pub mod minimap;
+pub mod chunk_freeze;
+pub mod bloom;
+pub mod voxel_ao;
+pub mod world_maps;
+pub mod voxel_outline;
+pub mod screen_recorder;
+pub mod oit;
+pub mod fire_flicker;
+pub mod chunk_mesh;
 
 pub use isometric_projection::*;
 pub use isometric_voxel_renderer::*;
 pub use gpu_renderer::*;
 pub use material::*;
+pub use fire_sparks::*;
+pub use minimap::*;
+pub use chunk_freeze::*;
+pub use bloom::*;
+pub use world_maps::*;
+pub use voxel_outline::*;
+pub use screen_recorder::*;
+pub use oit::*;
+pub use fire_flicker::*;
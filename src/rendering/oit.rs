@@ -0,0 +1,305 @@
+use bevy::core_pipeline::tonemapping::DebandDither;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::camera::RenderTarget;
+use bevy::render::mesh::MeshVertexBufferLayoutRef;
+use bevy::render::render_resource::{
+    AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState, Extent3d,
+    RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError, TextureDimension,
+    TextureFormat, TextureUsages,
+};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin};
+use bevy::window::PrimaryWindow;
+use crate::world::MaterialType;
+
+/// Render layer of the weighted color sums
+pub const OIT_ACCUM_LAYER: usize = 1;
+/// Render layer of the revealage product
+pub const OIT_REVEAL_LAYER: usize = 2;
+/// Draw depth of the resolved layer: in front of every voxel sprite, behind the world map display
+/// The resolve shader swaps in the depth of each pixel's nearest smoke for the depth test.
+const OIT_RESOLVE_Z: f32 = 800.0;
+/// Cleared depth of the revealage buffer, behind anything a voxel can be drawn at
+const OIT_NO_DEPTH: f32 = -1.0e4;
+
+/// Whether voxels of this material are drawn with order-independent transparency
+/// Only gases qualify. The buffers keep one depth per pixel, that of the nearest smoke,
+/// so an opaque voxel hides a pixel's smoke only when it is in front of all of it. That
+/// is close enough for diffuse clouds, but water and glass lie right against opaque
+/// voxels and need the sorted alpha path to be occluded correctly.
+pub fn blends_order_independent(material: MaterialType) -> bool {
+    matches!(material, MaterialType::Smoke)
+}
+
+/// Which of the two weighted-blended OIT buffers a draw goes into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OitPass {
+    /// Adds up color * alpha * weight (rgb) and alpha * weight (a)
+    Accumulate,
+    /// Multiplies in (1 - alpha) (rgb), leaving the share of the background still visible,
+    /// and keeps the largest draw depth (a), that of the nearest smoke
+    Revealage,
+}
+
+impl OitPass {
+    pub fn layer(self) -> RenderLayers {
+        match self {
+            OitPass::Accumulate => RenderLayers::layer(OIT_ACCUM_LAYER),
+            OitPass::Revealage => RenderLayers::layer(OIT_REVEAL_LAYER),
+        }
+    }
+
+    /// Blending that makes the result independent of draw order
+    fn blend_state(self) -> BlendState {
+        let add = BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        };
+        match self {
+            OitPass::Accumulate => BlendState {
+                color: add,
+                alpha: add,
+            },
+            OitPass::Revealage => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::OneMinusSrc,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    operation: BlendOperation::Max,
+                    ..add
+                },
+            },
+        }
+    }
+}
+
+/// One draw of a transparent voxel into an OIT buffer
+/// Each voxel is spawned once per pass, see `oit_voxel_bundles`.
+#[derive(AsBindGroup, Debug, Clone, Asset, TypePath)]
+#[bind_group_data(OitPass)]
+pub struct OitVoxelMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    pub pass: OitPass,
+}
+
+impl From<&OitVoxelMaterial> for OitPass {
+    fn from(material: &OitVoxelMaterial) -> Self {
+        material.pass
+    }
+}
+
+impl Material2d for OitVoxelMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/voxel_oit.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let Some(fragment) = descriptor.fragment.as_mut() else {
+            return Ok(());
+        };
+        if key.bind_group_data == OitPass::Revealage {
+            fragment.shader_defs.push("OIT_REVEALAGE".into());
+        }
+        for target in fragment.targets.iter_mut().flatten() {
+            target.blend = Some(key.bind_group_data.blend_state());
+        }
+        Ok(())
+    }
+}
+
+/// Composites the two OIT buffers over the scene
+/// Each pixel is depth tested at its nearest smoke, so opaque voxels in front hide it.
+#[derive(AsBindGroup, Debug, Clone, Asset, TypePath)]
+pub struct OitResolveMaterial {
+    #[texture(0)]
+    pub accum_texture: Handle<Image>,
+    #[texture(1)]
+    pub revealage_texture: Handle<Image>,
+}
+
+impl Material2d for OitResolveMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/voxel_oit_resolve.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// Screen-sized buffers the OIT cameras render into
+#[derive(Resource)]
+pub struct OitTargets {
+    pub accum: Handle<Image>,
+    pub revealage: Handle<Image>,
+    size: UVec2,
+}
+
+/// Marker for the cameras that render the OIT buffers
+/// Systems looking for the main camera should filter these out.
+#[derive(Component)]
+pub struct OitCamera;
+
+/// Marker for the screen quad that resolves the OIT buffers
+#[derive(Component)]
+struct OitResolveQuad;
+
+/// Both draws of one transparent voxel, each to be spawned as its own entity
+pub fn oit_voxel_bundles(
+    mesh: Handle<Mesh>,
+    color: Color,
+    materials: &mut Assets<OitVoxelMaterial>,
+) -> [(Mesh2d, MeshMaterial2d<OitVoxelMaterial>, RenderLayers); 2] {
+    [OitPass::Accumulate, OitPass::Revealage].map(|pass| {
+        (
+            Mesh2d(mesh.clone()),
+            MeshMaterial2d(materials.add(OitVoxelMaterial {
+                color: color.to_linear(),
+                pass,
+            })),
+            pass.layer(),
+        )
+    })
+}
+
+/// Plugin for weighted-blended order-independent transparency
+/// Overlapping smoke sprites are summed into two buffers by their own cameras and
+/// composited in one screen pass, so stacked smoke looks the same whatever order
+/// it's drawn in. The composite is depth tested against the opaque voxels, which
+/// write depth. The OIT cameras follow the main camera every frame.
+pub struct OitPlugin;
+
+impl Plugin for OitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+               Material2dPlugin::<OitVoxelMaterial>::default(),
+               Material2dPlugin::<OitResolveMaterial>::default(),
+           ))
+           .add_systems(Startup, setup_oit)
+           .add_systems(PostUpdate, follow_main_camera.before(TransformSystem::TransformPropagate));
+    }
+}
+
+/// Blank float render target for one OIT buffer
+fn oit_target(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x.max(1),
+            height: size.y.max(1),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 8],
+        TextureFormat::Rgba16Float,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
+    image
+}
+
+/// Create the OIT buffers, their cameras and the resolve quad
+fn setup_oit(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<OitResolveMaterial>>,
+) {
+    let size = windows.single().map(|window| window.physical_size()).unwrap_or(UVec2::ONE);
+    let targets = OitTargets {
+        accum: images.add(oit_target(size)),
+        revealage: images.add(oit_target(size)),
+        size,
+    };
+
+    // Rendered before the main camera so the resolve sees this frame's smoke
+    for (order, pass, target, clear) in [
+        (-2, OitPass::Accumulate, &targets.accum, Color::NONE),
+        (-1, OitPass::Revealage, &targets.revealage, Color::linear_rgba(1.0, 1.0, 1.0, OIT_NO_DEPTH)),
+    ] {
+        commands.spawn((
+            Camera2d,
+            Camera {
+                order,
+                target: RenderTarget::Image(target.clone().into()),
+                clear_color: ClearColorConfig::Custom(clear),
+                hdr: true,
+                ..default()
+            },
+            Msaa::Off,
+            DebandDither::Disabled,
+            pass.layer(),
+            OitCamera,
+        ));
+    }
+
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(OitResolveMaterial {
+            accum_texture: targets.accum.clone(),
+            revealage_texture: targets.revealage.clone(),
+        })),
+        Transform::from_xyz(0.0, 0.0, OIT_RESOLVE_Z),
+        OitResolveQuad,
+    ));
+    commands.insert_resource(targets);
+}
+
+/// Keep the OIT cameras looking where the main camera does, at the window's resolution
+#[allow(clippy::type_complexity)]
+fn follow_main_camera(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    main_camera: Query<(&Transform, &Projection), (With<Camera2d>, Without<OitCamera>)>,
+    mut oit_cameras: Query<(&mut Transform, &mut Projection), (With<OitCamera>, Without<OitResolveQuad>)>,
+    mut resolve_quad: Query<&mut Transform, (With<OitResolveQuad>, Without<Camera2d>)>,
+    mut targets: ResMut<OitTargets>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Ok((camera_transform, projection)) = main_camera.single() else {
+        return;
+    };
+    for (mut transform, mut oit_projection) in oit_cameras.iter_mut() {
+        *transform = *camera_transform;
+        *oit_projection = projection.clone();
+    }
+
+    // Stretch the resolve quad over the view; it reads the buffers per pixel
+    if let (Projection::Orthographic(ortho), Ok(mut quad)) = (projection, resolve_quad.single_mut()) {
+        let center = camera_transform.translation.truncate() + ortho.area.center();
+        quad.translation = center.extend(OIT_RESOLVE_Z);
+        quad.scale = ortho.area.size().extend(1.0);
+    }
+
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let size = window.physical_size().max(UVec2::ONE);
+    if size == targets.size {
+        return;
+    }
+    targets.size = size;
+    for handle in [&targets.accum, &targets.revealage] {
+        if let Some(image) = images.get_mut(handle) {
+            image.resize(Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            });
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where screenshots and recorded sequences are written
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Numbered PNG sequence capture of the primary window
+/// While recording, a frame is saved every `interval` seconds (0 = every rendered frame)
+/// into a fresh directory, until stopped or `max_frames` is reached.
+#[derive(Resource, Clone, Debug)]
+pub struct ScreenRecorder {
+    /// Seconds between captured frames; 0 captures every frame
+    pub interval: f32,
+    /// Recording stops by itself after this many frames, to cap disk usage
+    pub max_frames: u32,
+    /// Directory of the sequence being recorded, `None` when not recording
+    recording: Option<PathBuf>,
+    /// Frames saved so far in the current sequence
+    frames: u32,
+    /// Seconds since the last captured frame
+    since_capture: f32,
+}
+
+impl Default for ScreenRecorder {
+    fn default() -> Self {
+        Self {
+            interval: 1.0 / 30.0,
+            max_frames: 900,
+            recording: None,
+            frames: 0,
+            since_capture: 0.0,
+        }
+    }
+}
+
+impl ScreenRecorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Start a new sequence in `directory`; the first frame is captured on the next update
+    pub fn start(&mut self, directory: PathBuf) {
+        self.recording = Some(directory);
+        self.frames = 0;
+        self.since_capture = self.interval;
+    }
+
+    /// Stop recording, returning the sequence directory and how many frames it holds
+    pub fn stop(&mut self) -> Option<(PathBuf, u32)> {
+        self.recording.take().map(|directory| (directory, self.frames))
+    }
+
+    /// Advance by `dt` seconds, returning the path of the frame to capture now, if any
+    pub fn advance(&mut self, dt: f32) -> Option<PathBuf> {
+        let directory = self.recording.as_ref()?;
+        if self.frames >= self.max_frames {
+            return None;
+        }
+        self.since_capture += dt;
+        if self.since_capture < self.interval {
+            return None;
+        }
+        // Keep the remainder so the average rate holds even when frames are uneven
+        self.since_capture = if self.interval > 0.0 { self.since_capture % self.interval } else { 0.0 };
+
+        let path = frame_path(directory, self.frames);
+        self.frames += 1;
+        Some(path)
+    }
+
+    /// Whether the current sequence has hit `max_frames`
+    pub fn is_full(&self) -> bool {
+        self.is_recording() && self.frames >= self.max_frames
+    }
+}
+
+/// File name of frame `index` in a recorded sequence; zero-padded so they sort in order
+pub fn frame_path(directory: &Path, index: u32) -> PathBuf {
+    directory.join(format!("frame_{:05}.png", index))
+}
+
+/// Plugin for saving the primary window to disk
+/// F12 saves a single screenshot; F11 starts and stops recording a PNG sequence.
+pub struct ScreenRecorderPlugin;
+
+impl Plugin for ScreenRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenRecorder>()
+           .add_systems(Update, (take_screenshot, toggle_recording, record_frames).chain());
+    }
+}
+
+/// Seconds since the Unix epoch, to keep file names from separate runs apart
+fn timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Capture the primary window to `path` once the frame has rendered
+fn capture(commands: &mut Commands, path: PathBuf) {
+    commands.spawn(Screenshot::primary_window()).observe(save_to_disk(path));
+}
+
+/// Save a single screenshot on F12
+fn take_screenshot(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>) {
+    if !keyboard.just_pressed(KeyCode::F12) {
+        return;
+    }
+    if let Err(err) = std::fs::create_dir_all(SCREENSHOT_DIR) {
+        warn!("Can't save screenshot, {}: {}", SCREENSHOT_DIR, err);
+        return;
+    }
+    let path = Path::new(SCREENSHOT_DIR).join(format!("screenshot_{}.png", timestamp()));
+    info!("Saving screenshot to {}", path.display());
+    capture(&mut commands, path);
+}
+
+/// Start or stop recording on F11
+fn toggle_recording(keyboard: Res<ButtonInput<KeyCode>>, mut recorder: ResMut<ScreenRecorder>) {
+    if !keyboard.just_pressed(KeyCode::F11) {
+        return;
+    }
+    if let Some((directory, frames)) = recorder.stop() {
+        info!("Recorded {} frames to {}", frames, directory.display());
+        return;
+    }
+
+    let directory = Path::new(SCREENSHOT_DIR).join(format!("recording_{}", timestamp()));
+    if let Err(err) = std::fs::create_dir_all(&directory) {
+        warn!("Can't record, {}: {}", directory.display(), err);
+        return;
+    }
+    info!("Recording to {} (F11 to stop)", directory.display());
+    recorder.start(directory);
+}
+
+/// Capture the next frame of the sequence while recording
+fn record_frames(mut commands: Commands, time: Res<Time>, mut recorder: ResMut<ScreenRecorder>) {
+    if !recorder.is_recording() {
+        return;
+    }
+    if let Some(path) = recorder.advance(time.delta_secs()) {
+        capture(&mut commands, path);
+    }
+    if recorder.is_full() {
+        let max_frames = recorder.max_frames;
+        if let Some((directory, frames)) = recorder.stop() {
+            info!("Recording reached its {} frame limit: {} frames in {}", max_frames, frames, directory.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_paces_and_caps_frames() {
+        let mut recorder = ScreenRecorder {
+            interval: 0.1,
+            max_frames: 3,
+            ..default()
+        };
+        assert!(recorder.advance(1.0).is_none(), "not recording yet");
+
+        recorder.start(PathBuf::from("rec"));
+        assert_eq!(recorder.advance(0.016), Some(PathBuf::from("rec/frame_00000.png")));
+        assert!(recorder.advance(0.05).is_none());
+        assert_eq!(recorder.advance(0.06), Some(PathBuf::from("rec/frame_00001.png")));
+        assert_eq!(recorder.advance(0.1), Some(PathBuf::from("rec/frame_00002.png")));
+        assert!(recorder.is_full());
+        assert!(recorder.advance(1.0).is_none());
+        assert_eq!(recorder.stop(), Some((PathBuf::from("rec"), 3)));
+        assert!(!recorder.is_recording());
+    }
+}
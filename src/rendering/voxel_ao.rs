@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use crate::world::{WorldChunk, CHUNK_SIZE};
+
+/// Brightness per AO level, from a corner boxed in by solids (0) to a fully open one (3)
+pub const AO_BRIGHTNESS: [f32; 4] = [0.55, 0.7, 0.85, 1.0];
+
+/// Classic voxel corner occlusion level (0 = darkest, 3 = unoccluded)
+/// `side1` and `side2` are the solids along the two edges meeting at the corner,
+/// `corner` the diagonal one. Two sides already close the corner off completely.
+pub fn vertex_ao(side1: bool, side2: bool, corner: bool) -> u8 {
+    if side1 && side2 {
+        return 0;
+    }
+    3 - side1 as u8 - side2 as u8 - corner as u8
+}
+
+/// AO level of one corner of a voxel's top (+Y) face
+/// `dx`/`dz` (each ±1) pick the corner; the layer above the voxel is checked for solids.
+pub fn top_corner_ao(is_solid: impl Fn(IVec3) -> bool, voxel: IVec3, dx: i32, dz: i32) -> u8 {
+    let above = voxel + IVec3::Y;
+    vertex_ao(
+        is_solid(above + IVec3::new(dx, 0, 0)),
+        is_solid(above + IVec3::new(0, 0, dz)),
+        is_solid(above + IVec3::new(dx, 0, dz)),
+    )
+}
+
+/// AO levels for the four corners of a voxel's top face, in the isometric diamond's
+/// vertex order: top (-X-Z), right (+X-Z), bottom (+X+Z), left (-X+Z)
+pub fn top_face_ao(chunk: &WorldChunk, local: UVec3) -> [u8; 4] {
+    let is_solid = |pos: IVec3| chunk_solid(chunk, pos);
+    let voxel = local.as_ivec3();
+    [
+        top_corner_ao(is_solid, voxel, -1, -1),
+        top_corner_ao(is_solid, voxel, 1, -1),
+        top_corner_ao(is_solid, voxel, 1, 1),
+        top_corner_ao(is_solid, voxel, -1, 1),
+    ]
+}
+
+/// Whether a local position inside the chunk holds a voxel that blocks light
+/// Positions outside the chunk count as open, so seams stay unshaded, and
+/// transparent solids like glass don't darken their neighbours.
+pub fn chunk_solid(chunk: &WorldChunk, local: IVec3) -> bool {
+    if local.cmplt(IVec3::ZERO).any() || local.cmpge(IVec3::splat(CHUNK_SIZE as i32)).any() {
+        return false;
+    }
+    let local = local.as_uvec3();
+    chunk
+        .get_voxel(local.x, local.y, local.z)
+        .is_some_and(|voxel| voxel.blocks_light())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::VoxelData;
+
+    #[test]
+    fn test_top_face_ao_darkens_against_walls() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(0, 0, 0), UVec3::new(16, 1, 16), VoxelData::rock(255));
+
+        // An open floor is unshaded
+        assert_eq!(top_face_ao(&chunk, UVec3::new(8, 0, 8)), [3, 3, 3, 3]);
+
+        // A wall along +X one layer up darkens the two +X corners
+        chunk.fill_region(UVec3::new(9, 1, 0), UVec3::new(10, 2, 16), VoxelData::rock(255));
+        assert_eq!(top_face_ao(&chunk, UVec3::new(8, 0, 8)), [3, 1, 1, 3]);
+
+        // A second wall along +Z closes off the corner between them
+        chunk.fill_region(UVec3::new(0, 1, 9), UVec3::new(16, 2, 10), VoxelData::rock(255));
+        assert_eq!(top_face_ao(&chunk, UVec3::new(8, 0, 8)), [3, 1, 0, 1]);
+    }
+}
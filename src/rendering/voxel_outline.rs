@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use super::isometric_projection::isometric_screen_position;
+use super::isometric_voxel_renderer::VOXEL_SPRITE_SIZE;
+
+/// World voxel under the cursor, refreshed every frame; `None` when nothing is hovered
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedVoxel(pub Option<IVec3>);
+
+/// Color of the selection outline
+const OUTLINE_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+/// How far the outline sits outside the voxel's diamond, in screen units
+const OUTLINE_MARGIN: f32 = 0.75;
+
+/// Plugin that outlines the `SelectedVoxel`
+/// Whatever does the picking writes the resource; this only draws it.
+pub struct VoxelOutlinePlugin;
+
+impl Plugin for VoxelOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedVoxel>()
+           .add_systems(Update, draw_voxel_outline);
+    }
+}
+
+/// Trace a diamond slightly larger than the selected voxel's sprite around it
+fn draw_voxel_outline(selected: Res<SelectedVoxel>, mut gizmos: Gizmos) {
+    let Some(voxel) = selected.0 else {
+        return;
+    };
+
+    // Voxel sprites are centered on the projection of the voxel's min corner
+    let center = isometric_screen_position(voxel.as_vec3());
+    let half = VOXEL_SPRITE_SIZE / 2.0 + OUTLINE_MARGIN;
+    gizmos.linestrip_2d(
+        [Vec2::Y, Vec2::X, Vec2::NEG_Y, Vec2::NEG_X, Vec2::Y].map(|corner| center + corner * half),
+        OUTLINE_COLOR,
+    );
+}
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::Extent3d;
+use crate::world::{
+    emit_chunk_dirty_events, ChunkDirty, ChunkManager, MaterialRegistry, MaterialType, RaycastHit,
+    SpatialIndex, VoxelData, WorldChunk,
+};
+use super::gpu_renderer::{create_render_targets, RenderTargets};
+use super::isometric_projection::{isometric_screen_position, isometric_to_world_plane};
+use super::isometric_voxel_renderer::get_material_color_with_shading;
+use super::material::VoxelWorldMaterial;
+
+/// Map pixels per isometric screen unit
+const WORLD_MAP_PIXELS_PER_UNIT: f32 = 1.0;
+/// Minimum seconds between re-exports while chunks keep changing
+const WORLD_MAP_REFRESH_SECS: f32 = 0.5;
+/// Draw depth of the map display, in front of every voxel sprite
+const WORLD_MAP_DISPLAY_Z: f32 = 900.0;
+
+/// Region of the isometric screen covered by the world maps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldMapView {
+    /// Isometric screen position of the maps' top-left corner
+    pub top_left: Vec2,
+    pub pixels_per_unit: f32,
+    /// Map size in pixels
+    pub size: UVec2,
+}
+
+impl WorldMapView {
+    /// View framing everything inside a world-space box
+    pub fn fit(world_min: Vec3, world_max: Vec3, pixels_per_unit: f32) -> Self {
+        let (mut lo, mut hi) = (Vec2::INFINITY, Vec2::NEG_INFINITY);
+        for corner in 0..8 {
+            let pick_max = BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0);
+            let iso = isometric_screen_position(Vec3::select(pick_max, world_max, world_min));
+            lo = lo.min(iso);
+            hi = hi.max(iso);
+        }
+
+        Self {
+            top_left: Vec2::new(lo.x, hi.y),
+            pixels_per_unit,
+            size: ((hi - lo) * pixels_per_unit).ceil().as_uvec2().max(UVec2::ONE),
+        }
+    }
+
+    /// Size of the covered region in isometric screen units
+    pub fn extent(&self) -> Vec2 {
+        self.size.as_vec2() / self.pixels_per_unit
+    }
+
+    /// Isometric screen position of the middle of the maps
+    pub fn center(&self) -> Vec2 {
+        self.top_left + self.extent() * Vec2::new(0.5, -0.5)
+    }
+
+    /// Isometric screen position at the center of a pixel (rows run down the screen)
+    pub fn pixel_to_isometric(&self, pixel: UVec2) -> Vec2 {
+        let offset = (pixel.as_vec2() + 0.5) / self.pixels_per_unit;
+        Vec2::new(self.top_left.x + offset.x, self.top_left.y - offset.y)
+    }
+}
+
+/// What the isometric camera sees through one map pixel
+#[derive(Debug, Clone, Copy)]
+pub struct WorldMapTexel {
+    /// World position where the view ray meets the voxel's face
+    pub position: Vec3,
+    /// Axis-aligned normal of that face
+    pub normal: Vec3,
+    pub voxel: VoxelData,
+}
+
+/// First voxel along the camera ray through an isometric screen position
+/// The ray runs from the viewer along -(1, 1, 1) and is clipped to the world box
+/// first, so the empty space around the world costs nothing.
+pub fn raymarch_isometric(
+    spatial_index: &SpatialIndex,
+    screen: Vec2,
+    (world_min, world_max): (Vec3, Vec3),
+    is_visible: impl Fn(IVec3) -> bool,
+) -> Option<RaycastHit> {
+    // Points on the ray are `base - s * (1, 1, 1)`; find the span of `s` inside the box
+    let base = isometric_to_world_plane(screen, 0.0);
+    let enter = (base - world_max).max_element();
+    let exit = (base - world_min).min_element();
+    if enter >= exit {
+        return None;
+    }
+
+    let origin = base - Vec3::splat(enter);
+    let max_distance = (exit - enter) * 3.0_f32.sqrt();
+    spatial_index.raycast_until(origin, -Vec3::ONE, max_distance, is_visible)
+}
+
+/// Raymarch every pixel of `view`, row by row; `None` where the ray leaves the world
+pub fn raymarch_world_maps(
+    spatial_index: &SpatialIndex,
+    view: &WorldMapView,
+    world_bounds: (Vec3, Vec3),
+    voxel_at: impl Fn(IVec3) -> Option<VoxelData>,
+) -> Vec<Option<WorldMapTexel>> {
+    let is_visible = |voxel: IVec3| voxel_at(voxel).is_some_and(|v| v.material() != MaterialType::Air);
+
+    let mut texels = Vec::with_capacity((view.size.x * view.size.y) as usize);
+    for y in 0..view.size.y {
+        for x in 0..view.size.x {
+            let screen = view.pixel_to_isometric(UVec2::new(x, y));
+            let texel = raymarch_isometric(spatial_index, screen, world_bounds, is_visible)
+                .and_then(|hit| {
+                    Some(WorldMapTexel {
+                        position: hit.position,
+                        normal: hit.normal.as_vec3(),
+                        voxel: voxel_at(hit.voxel_coord)?,
+                    })
+                });
+            texels.push(texel);
+        }
+    }
+    texels
+}
+
+/// Resize a map image to `size` and encode one color per texel; empty pixels are transparent
+fn write_world_map(
+    image: &mut Image,
+    size: UVec2,
+    texels: &[Option<WorldMapTexel>],
+    encode: impl Fn(&WorldMapTexel) -> Color,
+) {
+    let extent = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+    if image.texture_descriptor.size != extent {
+        image.resize(extent);
+    }
+
+    for (i, texel) in texels.iter().enumerate() {
+        let color = texel.as_ref().map_or(Color::NONE, &encode);
+        if let Err(err) = image.set_color_at(i as u32 % size.x, i as u32 / size.x, color) {
+            warn!("Could not write world map: {:?}", err);
+            return;
+        }
+    }
+}
+
+/// State of the world-scale position/normal/diffuse export
+/// The maps themselves live in the shared `RenderTargets`.
+#[derive(Resource)]
+pub struct WorldMaps {
+    /// Whether the maps are displayed and kept up to date
+    pub enabled: bool,
+    /// Screen region of the last export
+    pub view: Option<WorldMapView>,
+    /// Chunks changed since the last export
+    stale: bool,
+    refresh: Timer,
+}
+
+impl Default for WorldMaps {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            view: None,
+            stale: true,
+            refresh: Timer::from_seconds(WORLD_MAP_REFRESH_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// Marker for the quad that shows the lit world maps
+#[derive(Component)]
+pub struct WorldMapDisplay;
+
+/// Plugin that raymarches all loaded chunks into one set of screen-space maps
+/// `M` toggles the maps; while shown they replace the sprite view and follow chunk edits.
+pub struct WorldMapPlugin;
+
+impl Plugin for WorldMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldMaps>()
+           .add_systems(Startup, setup_world_maps)
+           .add_systems(Update, (
+               toggle_world_maps,
+               export_world_maps.after(emit_chunk_dirty_events),
+           ).chain());
+    }
+}
+
+/// Create the shared render targets and the hidden quad that displays them
+fn setup_world_maps(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<VoxelWorldMaterial>>,
+) {
+    // Sized to the world on the first export
+    let targets = create_render_targets(1, 1, &mut images);
+
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(VoxelWorldMaterial {
+            position_texture: targets.position.clone(),
+            normal_texture: targets.normal.clone(),
+            diffuse_texture: targets.diffuse.clone(),
+        })),
+        Transform::from_xyz(0.0, 0.0, WORLD_MAP_DISPLAY_Z),
+        Visibility::Hidden,
+        WorldMapDisplay,
+    ));
+    commands.insert_resource(targets);
+}
+
+/// Show or hide the world maps
+fn toggle_world_maps(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut world_maps: ResMut<WorldMaps>,
+    mut display: Query<&mut Visibility, With<WorldMapDisplay>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+
+    world_maps.enabled = !world_maps.enabled;
+    for mut visibility in display.iter_mut() {
+        *visibility = if world_maps.enabled { Visibility::Visible } else { Visibility::Hidden };
+    }
+    info!("World maps {}", if world_maps.enabled { "shown" } else { "hidden" });
+}
+
+/// Raymarch the loaded chunks into the render targets when they've changed
+/// Exports are throttled since every pixel marches through the world on the CPU.
+#[allow(clippy::too_many_arguments)]
+fn export_world_maps(
+    mut dirty_events: EventReader<ChunkDirty>,
+    mut world_maps: ResMut<WorldMaps>,
+    time: Res<Time>,
+    targets: Res<RenderTargets>,
+    chunk_manager: Res<ChunkManager>,
+    spatial_index: Res<SpatialIndex>,
+    registry: Res<MaterialRegistry>,
+    chunks: Query<&WorldChunk>,
+    mut images: ResMut<Assets<Image>>,
+    mut display: Query<&mut Transform, With<WorldMapDisplay>>,
+) {
+    if dirty_events.read().count() > 0 {
+        world_maps.stale = true;
+    }
+    world_maps.refresh.tick(time.delta());
+    if !world_maps.enabled || !world_maps.stale || !world_maps.refresh.finished() {
+        return;
+    }
+    let Some(world_bounds) = chunk_manager.world_bounds() else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let view = WorldMapView::fit(world_bounds.0, world_bounds.1, WORLD_MAP_PIXELS_PER_UNIT);
+    let texels = raymarch_world_maps(&spatial_index, &view, world_bounds, |voxel| {
+        chunk_manager.get_voxel_world(voxel, &chunks)
+    });
+
+    if let Some(image) = images.get_mut(&targets.position) {
+        write_world_map(image, view.size, &texels, |texel| {
+            Color::linear_rgba(texel.position.x, texel.position.y, texel.position.z, 1.0)
+        });
+    }
+    if let Some(image) = images.get_mut(&targets.normal) {
+        write_world_map(image, view.size, &texels, |texel| {
+            let encoded = texel.normal * 0.5 + 0.5;
+            Color::linear_rgba(encoded.x, encoded.y, encoded.z, 1.0)
+        });
+    }
+    if let Some(image) = images.get_mut(&targets.diffuse) {
+        write_world_map(image, view.size, &texels, |texel| {
+            get_material_color_with_shading(texel.voxel, texel.position.y, &registry)
+        });
+    }
+
+    for mut transform in display.iter_mut() {
+        let center = view.center();
+        transform.translation = center.extend(WORLD_MAP_DISPLAY_Z);
+        transform.scale = view.extent().extend(1.0);
+    }
+
+    world_maps.view = Some(view);
+    world_maps.stale = false;
+    world_maps.refresh.reset();
+    debug!(
+        "Exported {}x{} world maps in {:.1}ms",
+        view.size.x,
+        view.size.y,
+        start.elapsed().as_secs_f32() * 1000.0,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raymarch_world_maps_sees_top_faces() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(0, 0, 0), UVec3::new(16, 1, 16), VoxelData::rock(255));
+        let voxel_at = |voxel: IVec3| {
+            let local = UVec3::try_from(voxel).ok()?;
+            chunk.get_voxel(local.x, local.y, local.z)
+        };
+        let bounds = (Vec3::ZERO, Vec3::new(16.0, 1.0, 16.0));
+        let index = SpatialIndex::new();
+
+        // Looking down onto the middle of the floor lands on the top face
+        let screen = isometric_screen_position(Vec3::new(8.5, 1.0, 8.5));
+        let hit = raymarch_isometric(&index, screen, bounds, |v| voxel_at(v).is_some_and(|v| v.is_solid())).unwrap();
+        assert_eq!(hit.voxel_coord, IVec3::new(8, 0, 8));
+        assert_eq!(hit.normal, IVec3::Y);
+        assert!(hit.position.distance(Vec3::new(8.5, 1.0, 8.5)) < 1e-3);
+
+        // Rays that never cross the world box miss
+        assert!(raymarch_isometric(&index, Vec2::new(100.0, 0.0), bounds, |_| true).is_none());
+
+        // Every exported pixel faces the camera, and the floor fills part of the view
+        let view = WorldMapView::fit(bounds.0, bounds.1, 2.0);
+        let texels = raymarch_world_maps(&index, &view, bounds, voxel_at);
+        assert_eq!(texels.len(), (view.size.x * view.size.y) as usize);
+        let hits: Vec<_> = texels.iter().flatten().collect();
+        assert!(!hits.is_empty() && hits.len() < texels.len());
+        assert!(hits.iter().all(|t| [Vec3::X, Vec3::Y, Vec3::Z].contains(&t.normal)));
+        assert!(hits.iter().all(|t| t.voxel.material() == MaterialType::Rock));
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+/// Which implementation steps the voxel simulation
+/// Picked once at startup by `ComputeSimulationPlugin` so the CPU and GPU paths never both
+/// run on the same world.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationBackend {
+    /// Compute shader dispatch in the render world
+    /// Never chosen yet: the compute path doesn't step chunks, so every app runs on the CPU.
+    Gpu,
+    /// `simulate_chunk` in the main world's `FixedUpdate`
+    Cpu,
+}
+
+impl SimulationBackend {
+    /// GPU when there's a render world, its adapter runs compute shaders and the compute path
+    /// can step chunks; CPU otherwise. Also returns why, for the startup log.
+    pub fn choose(has_render_world: bool, supports_compute: bool, dispatch_ready: bool) -> (Self, &'static str) {
+        if !has_render_world {
+            (SimulationBackend::Cpu, "no render world")
+        } else if !supports_compute {
+            (SimulationBackend::Cpu, "adapter has no compute shader support")
+        } else if !dispatch_ready {
+            (SimulationBackend::Cpu, "compute dispatch not implemented yet")
+        } else {
+            (SimulationBackend::Gpu, "compute shaders available")
+        }
+    }
+
+    /// Label for the simulation status text
+    pub fn label(&self) -> &'static str {
+        match self {
+            SimulationBackend::Gpu => "GPU",
+            SimulationBackend::Cpu => "CPU",
+        }
+    }
+}
+
+/// Run condition for the CPU simulation systems
+/// Apps that never choose a backend, like tests adding only `CpuSimulationPlugin`, simulate on the CPU.
+pub fn cpu_backend_active(backend: Option<Res<SimulationBackend>>) -> bool {
+    backend.is_none_or(|backend| *backend == SimulationBackend::Cpu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_needs_render_world_and_compute() {
+        assert_eq!(SimulationBackend::choose(true, true, true).0, SimulationBackend::Gpu);
+        assert_eq!(SimulationBackend::choose(false, true, true).0, SimulationBackend::Cpu);
+        assert_eq!(SimulationBackend::choose(true, false, true).0, SimulationBackend::Cpu);
+        assert_eq!(SimulationBackend::choose(true, true, false).0, SimulationBackend::Cpu);
+    }
+}
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
 use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
 use bevy::render::render_resource::*;
-use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::renderer::{RenderAdapter, RenderDevice, RenderQueue};
 use bevy::render::{Render, RenderApp, RenderSet};
 use bevy::render::render_asset::RenderAssets;
 use bevy::render::texture::GpuImage;
 use crate::world::chunk::CHUNK_SIZE;
+use super::SimulationBackend;
 use std::collections::HashMap;
 
 /// Uniform data for simulation compute shader
@@ -21,7 +22,7 @@ struct SimulationParams {
 #[derive(Resource, Clone, ExtractResource)]
 pub struct SimulationSettings {
     pub enabled: bool,
-    pub fixed_timestep: f32, // Simulate at fixed rate (e.g., 1/60)
+    pub fixed_timestep: f32, // Simulate at fixed rate (e.g., 1/15)
     pub time_accumulator: f32,
     pub time_elapsed: f32,
 }
@@ -30,14 +31,21 @@ impl Default for SimulationSettings {
     fn default() -> Self {
         Self {
             enabled: true,
-            fixed_timestep: 1.0 / 60.0, // 60Hz simulation
+            fixed_timestep: 0.066, // ~15Hz keeps fire animation readable
             time_accumulator: 0.0,
             time_elapsed: 0.0,
         }
     }
 }
 
+/// Whether `prepare_simulation_pipeline` dispatches chunk steps yet
+/// Until it does, choosing the GPU backend would freeze the world, so the CPU is used.
+/// Flip this once the dispatch TODO there is done; nothing else gates the GPU backend.
+const GPU_DISPATCH_READY: bool = false;
+
 /// Plugin for GPU compute simulation
+/// Also decides the `SimulationBackend`: the render world systems are only added when the
+/// GPU is chosen, and the CPU simulation only runs otherwise.
 pub struct ComputeSimulationPlugin;
 
 impl Plugin for ComputeSimulationPlugin {
@@ -46,14 +54,27 @@ impl Plugin for ComputeSimulationPlugin {
            .add_plugins(ExtractResourcePlugin::<SimulationSettings>::default())
            .add_systems(Update, update_simulation_time);
 
-        // Add render world systems (will initialize pipeline when render world is ready)
+        info!("Compute simulation plugin initialized");
+    }
+
+    fn finish(&self, app: &mut App) {
+        // The render device and adapter are only available once `RenderPlugin` has finished
+        let supports_compute = app.world().get_resource::<RenderAdapter>().is_some_and(|adapter| {
+            adapter.get_downlevel_capabilities().flags.contains(DownlevelFlags::COMPUTE_SHADERS)
+        });
+        let has_render_world = app.get_sub_app(RenderApp).is_some();
+        let (backend, reason) = SimulationBackend::choose(has_render_world, supports_compute, GPU_DISPATCH_READY);
+        info!("Simulation backend: {} ({})", backend.label(), reason);
+        app.insert_resource(backend);
+
+        if backend != SimulationBackend::Gpu {
+            return;
+        }
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<SimulationPipeline>()
                 .add_systems(Render, prepare_simulation_pipeline.in_set(RenderSet::Prepare));
         }
-
-        info!("Compute simulation plugin initialized");
     }
 }
 
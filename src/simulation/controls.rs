@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use super::{SimPerf, SimulationBackend, SimulationSettings};
+
+/// Shortest allowed simulation step (240Hz)
+const MIN_TIMESTEP: f32 = 1.0 / 240.0;
+/// Longest allowed simulation step (1Hz)
+const MAX_TIMESTEP: f32 = 1.0;
+/// Factor applied to the timestep per key press
+const TIMESTEP_STEP: f32 = 1.25;
+
+/// Marker for the simulation status text
+#[derive(Component)]
+pub struct SimulationStatusText;
+
+/// Plugin for runtime simulation controls
+/// P toggles the simulation, `=`/`-` speed it up/slow it down.
+pub struct SimulationControlsPlugin;
+
+impl Plugin for SimulationControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_simulation_status)
+           .add_systems(Update, (control_simulation_settings, update_simulation_status).chain());
+    }
+}
+
+fn setup_simulation_status(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        SimulationStatusText,
+    ));
+}
+
+/// Adjust simulation settings from the keyboard
+fn control_simulation_settings(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<SimulationSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        settings.enabled = !settings.enabled;
+    }
+
+    // Faster simulation = shorter timestep
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        settings.fixed_timestep = (settings.fixed_timestep / TIMESTEP_STEP).max(MIN_TIMESTEP);
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        settings.fixed_timestep = (settings.fixed_timestep * TIMESTEP_STEP).min(MAX_TIMESTEP);
+    }
+}
+
+/// Show the current simulation rate and the last tick's cost
+fn update_simulation_status(
+    settings: Res<SimulationSettings>,
+    perf: Res<SimPerf>,
+    backend: Option<Res<SimulationBackend>>,
+    mut status_query: Query<&mut Text, With<SimulationStatusText>>,
+) {
+    if !settings.is_changed() && !perf.is_changed() {
+        return;
+    }
+
+    if let Ok(mut text) = status_query.single_mut() {
+        let state = if settings.enabled {
+            format!("{:.1} Hz", 1.0 / settings.fixed_timestep)
+        } else {
+            "paused".to_string()
+        };
+        let backend = backend.map_or(SimulationBackend::Cpu, |backend| *backend);
+        **text = format!(
+            "Simulation ({}): {}\n\
+             Sim: {} chunks ({} asleep), {} voxels, {:.2} ms\n\
+             Upload: {} chunks, {:.2} ms\n\
+             [P] pause  [-/=] slower/faster",
+            backend.label(),
+            state,
+            perf.chunks_simulated,
+            perf.chunks_sleeping,
+            perf.voxels_changed,
+            perf.sim_time_ms,
+            perf.chunks_uploaded,
+            perf.render_time_ms,
+        );
+    }
+}
@@ -1,60 +1,191 @@
 use bevy::prelude::*;
-use crate::world::{WorldChunk, ChunkManager, VoxelData, MaterialType};
+use std::collections::HashSet;
+use crate::world::{
+    emit_chunk_dirty_events, ChunkDirty, WorldChunk, ChunkManager, MaterialRegistry, VoxelData, MaterialType,
+    voxel_flags, CHUNK_SIZE,
+};
+use super::{cpu_backend_active, Gravity, SimPerf, SimulationRng, SimulationSettings};
 
-// Simple random number generator for simulation
-fn simple_random() -> f32 {
-    static mut SEED: u32 = 12345;
-    unsafe {
-        SEED = (SEED * 1664525 + 1013904223) & 0xFFFFFFFF;
-        (SEED as f32) / (u32::MAX as f32)
+/// Roll a per-tick probability; certain and impossible outcomes don't consume a random number
+fn chance(rng: &mut SimulationRng, probability: f32) -> bool {
+    probability >= 1.0 || (probability > 0.0 && rng.next_f32() < probability)
+}
+
+/// One of the four directions across gravity, picked at random
+fn random_lateral(rng: &mut SimulationRng, gravity: Gravity) -> IVec3 {
+    // `next_f32` stays below 1.0, so this is always a valid index
+    gravity.lateral()[(rng.next_f32() * 4.0) as usize]
+}
+
+/// Cell one step along `offset`, if it's inside the chunk
+fn neighbor(x: u32, y: u32, z: u32, offset: IVec3) -> Option<(u32, u32, u32)> {
+    let pos = IVec3::new(x as i32, y as i32, z as i32) + offset;
+    if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(IVec3::splat(CHUNK_SIZE as i32)).any() {
+        return None;
     }
+    Some((pos.x as u32, pos.y as u32, pos.z as u32))
 }
 
 /// Plugin for simple CPU-based voxel simulation (temporary, will move to GPU)
+/// Runs in `FixedUpdate` so the simulation ticks at the same rate regardless of frame rate.
+/// The fixed rate follows `SimulationSettings::fixed_timestep`. Stands down when the GPU
+/// `SimulationBackend` is active.
 pub struct CpuSimulationPlugin;
 
 impl Plugin for CpuSimulationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, simulate_fire_cpu);
+        app.init_resource::<SimulationRng>()
+           .init_resource::<SimPerf>()
+           .init_resource::<Gravity>()
+           .add_systems(PreUpdate, sync_fixed_timestep)
+           .add_systems(FixedUpdate, simulate_fire_cpu.run_if(cpu_backend_active))
+           .add_systems(
+               Update,
+               wake_chunks_near_activity.after(emit_chunk_dirty_events).run_if(cpu_backend_active),
+           );
+    }
+}
+
+/// Ticks without any change before a chunk stops being simulated
+const SLEEP_AFTER_IDLE_TICKS: u32 = 30;
+
+/// Keep Bevy's fixed timestep in step with the simulation settings
+fn sync_fixed_timestep(
+    settings: Res<SimulationSettings>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    if settings.is_changed() {
+        fixed_time.set_timestep_seconds(settings.fixed_timestep as f64);
     }
 }
 
 /// Simple CPU simulation: make fire spread, rise, and turn to smoke
 fn simulate_fire_cpu(
-    time: Res<Time>,
+    settings: Res<SimulationSettings>,
     manager: Res<ChunkManager>,
+    materials: Res<MaterialRegistry>,
+    gravity: Res<Gravity>,
+    mut rng: ResMut<SimulationRng>,
     mut chunks: Query<&mut WorldChunk>,
+    mut perf: ResMut<SimPerf>,
 ) {
-    // Run simulation at ~15Hz (every 0.066 seconds) for smoother animation
-    static mut ACCUMULATOR: f32 = 0.0;
-    const SIM_RATE: f32 = 0.066;
-    
-    unsafe {
-        ACCUMULATOR += time.delta_secs();
-        if ACCUMULATOR < SIM_RATE {
-            return;
-        }
-        ACCUMULATOR -= SIM_RATE;
+    if !settings.enabled {
+        return;
     }
-    
-    // Simulate each chunk with dynamic elements
-    for (_chunk_pos, &entity) in manager.chunks.iter() {
+
+    let start = std::time::Instant::now();
+    let mut chunks_simulated = 0;
+    let mut chunks_sleeping = 0;
+    let mut voxels_changed = 0;
+
+    // Simulate each chunk with dynamic elements, in a fixed order so runs are reproducible
+    for (_chunk_pos, entity) in manager.chunks_in_order() {
         if let Ok(mut chunk) = chunks.get_mut(entity) {
             if !chunk.has_dynamic_elements {
                 continue;
             }
+            // Read through the shared borrow so sleeping chunks aren't flagged as changed
+            if !chunk.active {
+                chunks_sleeping += 1;
+                continue;
+            }
             
-            simulate_chunk(&mut chunk);
+            let changed = simulate_chunk(&mut chunk, &materials, *gravity, &mut rng);
+            chunk.record_sim_tick(changed, SLEEP_AFTER_IDLE_TICKS);
+            voxels_changed += changed;
+            chunks_simulated += 1;
+        }
+    }
+
+    // Only touch the resource when something happened so the status panel isn't redrawn every tick
+    if chunks_simulated > 0 || perf.chunks_simulated > 0 || chunks_sleeping != perf.chunks_sleeping {
+        perf.chunks_simulated = chunks_simulated;
+        perf.chunks_sleeping = chunks_sleeping;
+        perf.voxels_changed = voxels_changed;
+        perf.sim_time_ms = start.elapsed().as_secs_f32() * 1000.0;
+    }
+}
+
+/// Wake sleeping chunks next to edits that reach their shared face
+/// Edited chunks wake themselves in `set_voxel`; this covers activity spilling over a seam,
+/// such as water piling up against the boundary of a settled pool.
+fn wake_chunks_near_activity(
+    mut dirty_events: EventReader<ChunkDirty>,
+    manager: Res<ChunkManager>,
+    mut chunks: Query<&mut WorldChunk>,
+) {
+    let chunk_size = CHUNK_SIZE as f32;
+    for event in dirty_events.read() {
+        let chunk_min = event.chunk_pos.as_vec3() * chunk_size;
+        let near_min = event.dirty_aabb.min.cmple(chunk_min + Vec3::ONE);
+        let near_max = event.dirty_aabb.max.cmpge(chunk_min + Vec3::splat(chunk_size - 1.0));
+
+        for axis in 0..3 {
+            let offset = IVec3::AXES[axis];
+            let neighbours = [(near_min.test(axis), -offset), (near_max.test(axis), offset)];
+            for (_, offset) in neighbours.into_iter().filter(|(near, _)| *near) {
+                let Some(entity) = manager.get_chunk_entity(event.chunk_pos + offset) else {
+                    continue;
+                };
+                let Ok(mut chunk) = chunks.get_mut(entity) else {
+                    continue;
+                };
+                if !chunk.active {
+                    chunk.wake();
+                }
+            }
         }
     }
 }
 
-/// Simulate a single chunk
-fn simulate_chunk(chunk: &mut WorldChunk) {
+/// Voxel writes collected during a tick, applied once every voxel has been visited
+/// Each cell can be written at most once per tick: the first voxel (in iteration order)
+/// to claim a cell wins and later writes to it are dropped. Moves claim both their source
+/// and target, so a voxel is never duplicated into two cells or lost when two movers collide.
+/// Conflicts are resolved by iteration order, not independently of it: results are
+/// deterministic for a given chunk and seed, but an interaction between two voxels must be
+/// handled by both rules (as fire and water are) or it only happens when one goes first.
+#[derive(Default)]
+struct ChunkChanges {
+    writes: Vec<(u32, u32, u32, VoxelData)>,
+    claimed: HashSet<UVec3>,
+}
+
+impl ChunkChanges {
+    /// Write a voxel unless the cell was already claimed this tick
+    fn set(&mut self, x: u32, y: u32, z: u32, voxel: VoxelData) {
+        if self.claimed.insert(UVec3::new(x, y, z)) {
+            self.writes.push((x, y, z, voxel));
+        }
+    }
+
+    /// Move a voxel, leaving air behind, only if neither cell was claimed this tick
+    fn move_voxel(&mut self, from: (u32, u32, u32), to: (u32, u32, u32), voxel: VoxelData) -> bool {
+        let from_key = UVec3::new(from.0, from.1, from.2);
+        let to_key = UVec3::new(to.0, to.1, to.2);
+        if self.claimed.contains(&from_key) || self.claimed.contains(&to_key) {
+            return false;
+        }
+        self.set(from.0, from.1, from.2, VoxelData::air());
+        self.set(to.0, to.1, to.2, voxel);
+        true
+    }
+}
+
+/// Simulate a single chunk for one tick, returning the number of voxel writes
+/// Doesn't touch the ECS, so it can be driven directly (e.g. from tests) without an `App`;
+/// given the same chunk and `rng` state the outcome is always the same. Voxels are visited
+/// x fastest, then y, then z, and conflicting writes go to whichever came first.
+pub fn simulate_chunk(
+    chunk: &mut WorldChunk,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    rng: &mut SimulationRng,
+) -> u32 {
     let chunk_size = 64u32;
     
     // Build a list of changes to apply (can't modify while iterating)
-    let mut changes: Vec<(u32, u32, u32, VoxelData)> = Vec::new();
+    let mut changes = ChunkChanges::default();
     
     // Iterate through all voxels
     for z in 0..chunk_size {
@@ -63,13 +194,22 @@ fn simulate_chunk(chunk: &mut WorldChunk) {
                 if let Some(voxel) = chunk.get_voxel(x, y, z) {
                     match voxel.material() {
                         MaterialType::Fire => {
-                            simulate_fire_voxel(chunk, x, y, z, voxel, &mut changes);
+                            simulate_fire_voxel(chunk, x, y, z, voxel, materials, gravity, rng, &mut changes);
                         }
                         MaterialType::Smoke => {
-                            simulate_smoke_voxel(chunk, x, y, z, voxel, &mut changes);
+                            simulate_smoke_voxel(chunk, x, y, z, voxel, materials, gravity, rng, &mut changes);
+                        }
+                        MaterialType::Water if voxel.has_flag(voxel_flags::SOURCE) => {
+                            simulate_water_source(chunk, x, y, z, materials, gravity, &mut changes);
+                        }
+                        MaterialType::Water if voxel.has_flag(voxel_flags::DRAIN) => {
+                            simulate_water_drain(chunk, x, y, z, &mut changes);
                         }
                         MaterialType::Water => {
-                            simulate_water_voxel(chunk, x, y, z, voxel, &mut changes);
+                            simulate_water_voxel(chunk, x, y, z, voxel, materials, gravity, rng, &mut changes);
+                        }
+                        MaterialType::Debris => {
+                            simulate_debris_voxel(chunk, x, y, z, voxel, materials, gravity, rng, &mut changes);
                         }
                         _ => {}
                     }
@@ -79,60 +219,88 @@ fn simulate_chunk(chunk: &mut WorldChunk) {
     }
     
     // Apply all changes
-    for (x, y, z, new_voxel) in changes {
-        chunk.set_voxel(x, y, z, new_voxel);
-    }
+    let changed = changes.writes.len() as u32;
+    chunk.edit(|editor| {
+        for (x, y, z, new_voxel) in changes.writes {
+            editor.set(x, y, z, new_voxel);
+        }
+    });
+    changed
 }
 
-/// Simulate fire: spread to neighbors, rise, convert to smoke
+/// Temperature a fire voxel loses every tick; its temperature is its remaining lifetime
+const FIRE_COOLING_PER_TICK: u8 = 4;
+/// Extra temperature lost when fire jumps into air, so chains through open space die out
+const FIRE_SPREAD_COOLING: u8 = 48;
+/// Fire below this temperature burns out into smoke
+const FIRE_MIN_TEMPERATURE: u8 = 40;
+
+/// Simulate fire: cool down, spread to fuel, rise, convert to smoke
+#[allow(clippy::too_many_arguments)]
 fn simulate_fire_voxel(
     chunk: &WorldChunk,
     x: u32,
     y: u32,
     z: u32,
     voxel: VoxelData,
-    changes: &mut Vec<(u32, u32, u32, VoxelData)>,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    rng: &mut SimulationRng,
+    changes: &mut ChunkChanges,
 ) {
-    // Fire has a chance to turn into smoke
-    if simple_random() < 0.05 { // 5% chance per tick
-        let smoke = VoxelData::new(MaterialType::Smoke, 200, 150, 0);
-        changes.push((x, y, z, smoke));
+    // Water resting on fire puts it out. Checked here as well as in the water rule because
+    // fire visited first claims its own cell, which would block the water from falling in.
+    if let Some(up) = neighbor(x, y, z, gravity.up()) {
+        let water_above = chunk.get_voxel(up.0, up.1, up.2).is_some_and(|above| {
+            above.material() == MaterialType::Water && !above.has_flag(voxel_flags::SOURCE | voxel_flags::DRAIN)
+        });
+        if water_above && changes.move_voxel(up, (x, y, z), extinguished_fire(materials)) {
+            return;
+        }
+    }
+
+    // Fire has a chance to turn into smoke, and always does once it has burnt out
+    let temperature = voxel.heat().saturating_sub(FIRE_COOLING_PER_TICK);
+    if temperature < FIRE_MIN_TEMPERATURE || rng.next_f32() < 0.05 { // 5% chance per tick
+        changes.set(x, y, z, materials.voxel(MaterialType::Smoke, 200, 150));
         return;
     }
+    let mut voxel = voxel;
+    voxel.set_heat(temperature);
     
     // Try to rise (fire is buoyant)
-    if y < 63 {
-        if let Some(above) = chunk.get_voxel(x, y + 1, z) {
+    if let Some(up) = neighbor(x, y, z, gravity.up()).filter(|_| chance(rng, materials.buoyancy(MaterialType::Fire))) {
+        if let Some(above) = chunk.get_voxel(up.0, up.1, up.2) {
             if above.material() == MaterialType::Air {
                 // Move fire up
-                changes.push((x, y, z, VoxelData::air()));
-                changes.push((x, y + 1, z, voxel));
-                return;
+                if changes.move_voxel((x, y, z), up, voxel) {
+                    return;
+                }
             }
         }
     }
+
+    // Stay in place, cooler than before
+    changes.set(x, y, z, voxel);
     
     // Try to spread horizontally (25% chance)
-    if simple_random() < 0.25 {
-        let dirs = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-        let (dx, dz) = dirs[(simple_random() * 4.0) as usize];
+    if rng.next_f32() < 0.25 {
+        let dir = random_lateral(rng, gravity);
         
-        let nx = (x as i32 + dx) as u32;
-        let nz = (z as i32 + dz) as u32;
-        
-        if nx < 64 && nz < 64 {
-            if let Some(neighbor) = chunk.get_voxel(nx, y, nz) {
+        if let Some((nx, ny, nz)) = neighbor(x, y, z, dir) {
+            if let Some(neighbor) = chunk.get_voxel(nx, ny, nz) {
                 // Spread to flammable materials
                 match neighbor.material() {
-                    MaterialType::Air => {
-                        // Spread fire to air
-                        let new_fire = VoxelData::new(MaterialType::Fire, 255, 200, 0);
-                        changes.push((nx, y, nz, new_fire));
+                    MaterialType::Air if touches_fuel(chunk, nx, ny, nz, materials) => {
+                        // Spread fire along fuel, with less lifetime than its parent
+                        let new_temperature = temperature.saturating_sub(FIRE_SPREAD_COOLING);
+                        if new_temperature >= FIRE_MIN_TEMPERATURE {
+                            changes.set(nx, ny, nz, materials.voxel(MaterialType::Fire, 255, new_temperature));
+                        }
                     }
-                    MaterialType::Wood => {
-                        // Ignite wood
-                        let new_fire = VoxelData::new(MaterialType::Fire, 255, 250, 0);
-                        changes.push((nx, y, nz, new_fire));
+                    material if materials.is_flammable(material) => {
+                        // Ignite fuel
+                        changes.set(nx, ny, nz, materials.voxel(MaterialType::Fire, 255, 250));
                     }
                     _ => {}
                 }
@@ -141,78 +309,419 @@ fn simulate_fire_voxel(
     }
 }
 
+/// Smoke left where water put out a fire; the water is used up
+fn extinguished_fire(materials: &MaterialRegistry) -> VoxelData {
+    materials.voxel(MaterialType::Smoke, 150, 50)
+}
+
+/// Whether any face neighbour of a cell is flammable
+fn touches_fuel(chunk: &WorldChunk, x: u32, y: u32, z: u32, materials: &MaterialRegistry) -> bool {
+    let dirs = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+    dirs.into_iter().any(|(dx, dy, dz)| {
+        let nx = (x as i32 + dx) as u32;
+        let ny = (y as i32 + dy) as u32;
+        let nz = (z as i32 + dz) as u32;
+        chunk
+            .get_voxel(nx, ny, nz)
+            .is_some_and(|neighbor| materials.is_flammable(neighbor.material()))
+    })
+}
+
 /// Simulate smoke: rise slowly
+#[allow(clippy::too_many_arguments)]
 fn simulate_smoke_voxel(
     chunk: &WorldChunk,
     x: u32,
     y: u32,
     z: u32,
     voxel: VoxelData,
-    changes: &mut Vec<(u32, u32, u32, VoxelData)>,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    rng: &mut SimulationRng,
+    changes: &mut ChunkChanges,
 ) {
     // Smoke dissipates over time
-    if simple_random() < 0.02 { // 2% chance to disappear
-        changes.push((x, y, z, VoxelData::air()));
+    if rng.next_f32() < 0.02 { // 2% chance to disappear
+        changes.set(x, y, z, VoxelData::air());
         return;
     }
     
     // Try to rise (smoke is buoyant but slower than fire)
-    if y < 63 && simple_random() < 0.3 { // 30% chance to rise
-        if let Some(above) = chunk.get_voxel(x, y + 1, z) {
+    if let Some(up) = neighbor(x, y, z, gravity.up()).filter(|_| chance(rng, materials.buoyancy(MaterialType::Smoke))) {
+        if let Some(above) = chunk.get_voxel(up.0, up.1, up.2) {
             if above.material() == MaterialType::Air {
                 // Move smoke up
-                changes.push((x, y, z, VoxelData::air()));
-                changes.push((x, y + 1, z, voxel));
+                changes.move_voxel((x, y, z), up, voxel);
             }
         }
     }
 }
 
 /// Simulate water: fall down
+#[allow(clippy::too_many_arguments)]
 fn simulate_water_voxel(
     chunk: &WorldChunk,
     x: u32,
     y: u32,
     z: u32,
     voxel: VoxelData,
-    changes: &mut Vec<(u32, u32, u32, VoxelData)>,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    rng: &mut SimulationRng,
+    changes: &mut ChunkChanges,
 ) {
     // Try to fall down
-    if y > 0 {
-        if let Some(below) = chunk.get_voxel(x, y - 1, z) {
-            match below.material() {
-                MaterialType::Air => {
-                    // Fall down
-                    changes.push((x, y, z, VoxelData::air()));
-                    changes.push((x, y - 1, z, voxel));
-                    return;
-                }
-                MaterialType::Fire => {
-                    // Extinguish fire
-                    changes.push((x, y, z, VoxelData::air()));
-                    changes.push((x, y - 1, z, VoxelData::new(MaterialType::Smoke, 150, 50, 0)));
-                    return;
-                }
-                _ => {}
+    if let Some(down) = neighbor(x, y, z, gravity.down()).filter(|_| chance(rng, -materials.buoyancy(MaterialType::Water))) {
+        if let Some(below) = chunk.get_voxel(down.0, down.1, down.2) {
+            let fallen = match below.material() {
+                // Fall down
+                MaterialType::Air => Some(voxel),
+                // Extinguish fire, when the water is visited before the fire is
+                MaterialType::Fire => Some(extinguished_fire(materials)),
+                _ => None,
+            };
+            if fallen.is_some_and(|fallen| changes.move_voxel((x, y, z), down, fallen)) {
+                return;
             }
         }
     }
     
     // Try to spread horizontally if can't fall
-    if simple_random() < 0.5 {
-        let dirs = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-        let (dx, dz) = dirs[(simple_random() * 4.0) as usize];
+    if rng.next_f32() < 0.5 {
+        let dir = random_lateral(rng, gravity);
         
+        if let Some(side) = neighbor(x, y, z, dir) {
+            if let Some(neighbor) = chunk.get_voxel(side.0, side.1, side.2) {
+                if neighbor.material() == MaterialType::Air {
+                    // Flow sideways: the water leaves its cell rather than copying itself
+                    changes.move_voxel((x, y, z), side, voxel);
+                }
+            }
+        }
+    }
+}
+
+/// Ticks debris has to rest on solid ground before it compacts into dirt
+const DEBRIS_SETTLE_TICKS: u8 = 20;
+
+/// Simulate debris: fall like sand, slide off the sides of piles, and compact into dirt
+/// Time spent at rest is counted in the voxel's `lifetime` and reset whenever it moves.
+/// Debris over fire, smoke or water waits for them to clear instead of settling on them.
+#[allow(clippy::too_many_arguments)]
+fn simulate_debris_voxel(
+    chunk: &WorldChunk,
+    x: u32,
+    y: u32,
+    z: u32,
+    voxel: VoxelData,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    rng: &mut SimulationRng,
+    changes: &mut ChunkChanges,
+) {
+    let material_at = |cell: (u32, u32, u32)| chunk.get_voxel(cell.0, cell.1, cell.2).map(|v| v.material());
+    // Nothing to land on inside this chunk
+    let Some(down) = neighbor(x, y, z, gravity.down()) else {
+        return;
+    };
+    let mut falling = voxel;
+    falling.set_lifetime(0);
+
+    let below = material_at(down);
+    if below == Some(MaterialType::Air) {
+        if chance(rng, -materials.buoyancy(MaterialType::Debris)) {
+            changes.move_voxel((x, y, z), down, falling);
+        }
+        return;
+    }
+    if !below.is_some_and(|below| below.is_solid() || below == MaterialType::Debris) {
+        return;
+    }
+
+    // Slide down one side of a pile when both the side and the cell under it are open
+    let side = random_lateral(rng, gravity);
+    let slide = neighbor(x, y, z, side)
+        .filter(|&cell| material_at(cell) == Some(MaterialType::Air))
+        .and_then(|_| neighbor(x, y, z, side + gravity.down()))
+        .filter(|&cell| material_at(cell) == Some(MaterialType::Air));
+    if slide.is_some_and(|target| changes.move_voxel((x, y, z), target, falling)) {
+        return;
+    }
+
+    let rested = voxel.lifetime().saturating_add(1);
+    if rested >= DEBRIS_SETTLE_TICKS {
+        changes.set(x, y, z, materials.voxel(MaterialType::Dirt, voxel.density(), voxel.heat()));
+    } else {
+        let mut resting = voxel;
+        resting.set_lifetime(rested);
+        changes.set(x, y, z, resting);
+    }
+}
+
+/// Simulate a water source: fill empty neighbors (except above) with plain water
+fn simulate_water_source(
+    chunk: &WorldChunk,
+    x: u32,
+    y: u32,
+    z: u32,
+    materials: &MaterialRegistry,
+    gravity: Gravity,
+    changes: &mut ChunkChanges,
+) {
+    let water = materials.voxel(MaterialType::Water, 255, 20);
+    let [a, b, c, d] = gravity.lateral();
+
+    for dir in [gravity.down(), a, b, c, d] {
+        let Some((nx, ny, nz)) = neighbor(x, y, z, dir) else {
+            continue;
+        };
+
+        if chunk.get_voxel(nx, ny, nz).is_some_and(|neighbor| neighbor.material() == MaterialType::Air) {
+            changes.set(nx, ny, nz, water);
+        }
+    }
+}
+
+/// Simulate a water drain: delete adjacent (non-source, non-drain) water
+fn simulate_water_drain(
+    chunk: &WorldChunk,
+    x: u32,
+    y: u32,
+    z: u32,
+    changes: &mut ChunkChanges,
+) {
+    let dirs = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+    for (dx, dy, dz) in dirs {
         let nx = (x as i32 + dx) as u32;
+        let ny = (y as i32 + dy) as u32;
         let nz = (z as i32 + dz) as u32;
-        
-        if nx < 64 && nz < 64 {
-            if let Some(neighbor) = chunk.get_voxel(nx, y, nz) {
-                if neighbor.material() == MaterialType::Air {
-                    // Spread water horizontally
-                    changes.push((nx, y, nz, voxel));
+
+        if chunk.get_voxel(nx, ny, nz).is_some_and(|neighbor| {
+            neighbor.material() == MaterialType::Water
+                && !neighbor.has_flag(voxel_flags::SOURCE | voxel_flags::DRAIN)
+        }) {
+            changes.set(nx, ny, nz, VoxelData::air());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_water(chunk: &WorldChunk) -> usize {
+        chunk.voxels.iter().filter(|v| v.material() == MaterialType::Water).count()
+    }
+
+    #[test]
+    fn test_water_mass_conserved_in_sealed_container() {
+        // A rock box with a 6x6 interior, half filled with water stacked in one corner
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(10, 10, 10), UVec3::new(18, 20, 18), VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(11, 11, 11), UVec3::new(17, 20, 17), VoxelData::air());
+        let water = VoxelData::new(MaterialType::Water, 255, 20, voxel_flags::TRANSPARENT);
+        chunk.fill_region(UVec3::new(11, 14, 11), UVec3::new(14, 19, 14), water);
+
+        let initial = count_water(&chunk);
+        assert_eq!(initial, 3 * 5 * 3);
+
+        let mut rng = SimulationRng::default();
+        for tick in 0..300 {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+            assert_eq!(count_water(&chunk), initial, "water count changed on tick {}", tick);
+        }
+
+        // The water settled onto the floor instead of hanging where it started
+        let floor = (11..17)
+            .flat_map(|z| (11..17).map(move |x| (x, z)))
+            .filter(|&(x, z)| chunk.get_voxel(x, 11, z).unwrap().material() == MaterialType::Water)
+            .count();
+        assert!(floor > 9, "only {} floor cells hold water", floor);
+    }
+
+    #[test]
+    fn test_drain_empties_sealed_container() {
+        // The same half-filled rock box, with a drain sunk into one corner of the floor
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(10, 10, 10), UVec3::new(18, 20, 18), VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(11, 11, 11), UVec3::new(17, 20, 17), VoxelData::air());
+        let water = VoxelData::new(MaterialType::Water, 255, 20, voxel_flags::TRANSPARENT);
+        chunk.fill_region(UVec3::new(11, 14, 11), UVec3::new(14, 19, 14), water);
+        chunk.set_voxel(16, 11, 16, VoxelData::water_drain());
+
+        let initial = count_water(&chunk);
+        let mut rng = SimulationRng::default();
+        for _ in 0..300 {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+        }
+
+        // Water that spread to the drain was removed; the drain itself stays
+        assert!(chunk.get_voxel(16, 11, 16).unwrap().has_flag(voxel_flags::DRAIN));
+        let remaining = count_water(&chunk);
+        assert!(remaining < initial / 2, "{} of {} water voxels left", remaining, initial);
+    }
+
+    #[test]
+    fn test_settled_water_goes_to_sleep() {
+        // A full layer of water on a rock floor has nowhere to flow
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        let water = VoxelData::new(MaterialType::Water, 255, 20, voxel_flags::TRANSPARENT);
+        chunk.fill_region(UVec3::ZERO, UVec3::new(CHUNK_SIZE, 1, CHUNK_SIZE), VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(0, 1, 0), UVec3::new(CHUNK_SIZE, 2, CHUNK_SIZE), water);
+
+        let materials = MaterialRegistry::default();
+        let mut rng = SimulationRng::default();
+        for _ in 0..SLEEP_AFTER_IDLE_TICKS {
+            assert!(chunk.active);
+            let changed = simulate_chunk(&mut chunk, &materials, Gravity::default(), &mut rng);
+            chunk.record_sim_tick(changed, SLEEP_AFTER_IDLE_TICKS);
+        }
+        assert!(!chunk.active);
+
+        // Any edit wakes it again
+        chunk.set_voxel(5, 10, 5, water);
+        assert!(chunk.active);
+        assert_eq!(chunk.idle_ticks, 0);
+    }
+
+    #[test]
+    fn test_lone_fire_burns_out() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        let fire = VoxelData::new(MaterialType::Fire, 255, 255, voxel_flags::EMITS_LIGHT);
+        chunk.set_voxel(32, 10, 32, fire);
+
+        // At most 255 / FIRE_COOLING_PER_TICK ticks of lifetime, and no fuel to spread into
+        let max_ticks = 255 / FIRE_COOLING_PER_TICK as usize + 1;
+        let mut rng = SimulationRng::default();
+        for _ in 0..max_ticks {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+            let fire_count = chunk.voxels.iter().filter(|v| v.material() == MaterialType::Fire).count();
+            assert!(fire_count <= 1, "fire spread into open air: {} voxels", fire_count);
+        }
+
+        assert!(chunk.voxels.iter().all(|v| v.material() != MaterialType::Fire));
+    }
+
+    #[test]
+    fn test_water_puts_out_fire() {
+        // A sheet of water resting on a sheet of fire, which rests on the floor
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_floor(4, VoxelData::rock(255));
+        let fire = VoxelData::new(MaterialType::Fire, 255, 255, voxel_flags::EMITS_LIGHT);
+        let water = VoxelData::new(MaterialType::Water, 255, 20, voxel_flags::TRANSPARENT);
+        chunk.fill_region(UVec3::new(20, 4, 20), UVec3::new(30, 5, 30), fire);
+        chunk.fill_region(UVec3::new(20, 5, 20), UVec3::new(30, 6, 30), water);
+        let count = |chunk: &WorldChunk, material| chunk.voxels.iter().filter(|v| v.material() == material).count();
+
+        // Fresh fire lasts far longer than one tick, so smoke now can only come from the water
+        let mut rng = SimulationRng::default();
+        simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+        assert_eq!(count(&chunk, MaterialType::Fire), 0);
+        assert_eq!(count(&chunk, MaterialType::Water), 0);
+        assert_eq!(count(&chunk, MaterialType::Smoke), 100);
+    }
+
+    #[test]
+    fn test_water_falls_along_gravity() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        let water = VoxelData::new(MaterialType::Water, 255, 20, voxel_flags::TRANSPARENT);
+        chunk.set_voxel(32, 32, 32, water);
+
+        // Sideways gravity: the water ends up against the chunk's +X face, not the floor
+        let gravity = Gravity(IVec3::X);
+        assert_eq!(gravity.lateral(), [IVec3::Y, IVec3::NEG_Y, IVec3::Z, IVec3::NEG_Z]);
+        let mut rng = SimulationRng::default();
+        for _ in 0..40 {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), gravity, &mut rng);
+        }
+
+        let index = chunk.voxels.iter().position(|v| v.material() == MaterialType::Water).unwrap();
+        assert_eq!(count_water(&chunk), 1);
+        assert_eq!(index as u32 % CHUNK_SIZE, CHUNK_SIZE - 1);
+    }
+
+    #[test]
+    fn test_fire_rises_away_from_floor() {
+        // Same floor as the test world's ground chunks, with fire two cells above it
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_floor(4, VoxelData::rock(255));
+        let fire = VoxelData::new(MaterialType::Fire, 255, 255, voxel_flags::EMITS_LIGHT);
+        chunk.set_voxel(32, 5, 32, fire);
+
+        // Default gravity points into the floor
+        let gravity = Gravity::default();
+        let start = IVec3::new(32, 5, 32);
+        let floor = (start + gravity.down() * 2).as_uvec3();
+        assert_eq!(chunk.get_voxel(floor.x, floor.y, floor.z).unwrap().material(), MaterialType::Rock);
+
+        // After one tick the fire has risen, unless it burnt out into smoke in place
+        let mut rng = SimulationRng::default();
+        simulate_chunk(&mut chunk, &MaterialRegistry::default(), gravity, &mut rng);
+        let risen = (start + gravity.up()).as_uvec3();
+        let risen = chunk.get_voxel(risen.x, risen.y, risen.z).unwrap().material();
+        let stayed = chunk.get_voxel(32, 5, 32).unwrap().material();
+        assert!(
+            risen == MaterialType::Fire || stayed == MaterialType::Smoke,
+            "fire moved sideways or down: above {:?}, start {:?}", risen, stayed,
+        );
+
+        // Neither the fire nor its smoke ever sinks toward the floor
+        for _ in 0..20 {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), gravity, &mut rng);
+            for (i, voxel) in chunk.voxels.iter().enumerate() {
+                if matches!(voxel.material(), MaterialType::Fire | MaterialType::Smoke) {
+                    let y = i as u32 / CHUNK_SIZE % CHUNK_SIZE;
+                    assert!(y >= 5, "{:?} sank to y = {}", voxel.material(), y);
                 }
             }
         }
     }
+
+    #[test]
+    fn test_debris_piles_up_and_compacts() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_floor(4, VoxelData::rock(255));
+        let debris = VoxelData::new(MaterialType::Debris, 180, 100, voxel_flags::TEMPORARY);
+        chunk.fill_sphere(Vec3::new(32.0, 24.0, 32.0), 4.0, debris);
+        let count = |chunk: &WorldChunk, material| chunk.voxels.iter().filter(|v| v.material() == material).count();
+        let initial = count(&chunk, MaterialType::Debris);
+
+        let mut rng = SimulationRng::default();
+        for _ in 0..200 {
+            simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+        }
+
+        // Every piece landed and turned to dirt resting on the floor or on other rubble
+        assert_eq!(count(&chunk, MaterialType::Debris), 0);
+        assert_eq!(count(&chunk, MaterialType::Dirt), initial);
+        for (i, voxel) in chunk.voxels.iter().enumerate() {
+            if voxel.material() == MaterialType::Dirt {
+                let (x, y, z) = (i as u32 % CHUNK_SIZE, i as u32 / CHUNK_SIZE % CHUNK_SIZE, i as u32 / (CHUNK_SIZE * CHUNK_SIZE));
+                assert!(chunk.get_voxel(x, y - 1, z).unwrap().is_solid(), "rubble floating at {:?}", (x, y, z));
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_result() {
+        // Water poured over fire on a floor exercises every random roll in the simulation
+        let mut start = WorldChunk::new(IVec3::ZERO);
+        start.fill_floor(4, VoxelData::rock(255));
+        let fire = VoxelData::new(MaterialType::Fire, 255, 255, voxel_flags::EMITS_LIGHT);
+        start.fill_region(UVec3::new(20, 4, 20), UVec3::new(30, 6, 30), fire);
+        start.set_voxel(25, 20, 25, VoxelData::water_source());
+
+        let run = |seed: u32| {
+            let mut chunk = start.clone();
+            let mut rng = SimulationRng::new(seed);
+            for _ in 0..50 {
+                simulate_chunk(&mut chunk, &MaterialRegistry::default(), Gravity::default(), &mut rng);
+            }
+            chunk.voxels.iter().map(|voxel| voxel.as_u64()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
 }
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
-use crate::world::{WorldChunk, VoxelData, MaterialType, voxel_flags, ChunkManager, CHUNK_SIZE};
+use crate::world::{spatial_order_key, WorldChunk, VoxelData, MaterialType, voxel_flags, ChunkManager, CHUNK_SIZE};
+use super::SimulationRng;
 
 /// High-level API for spawning dynamic elements in the world
+/// Randomized spawners take a `SimulationRng` so results are reproducible for a given seed.
 pub struct ElementSpawner;
 
 impl ElementSpawner {
@@ -107,7 +109,7 @@ impl ElementSpawner {
         Self::spawn_explosion_debris(world_pos, radius * 1.2, chunks, chunk_manager);
     }
 
-    /// Generic sphere spawner
+    /// Generic sphere spawner, a batch of one
     fn spawn_element_sphere(
         world_pos: Vec3,
         radius: f32,
@@ -115,33 +117,91 @@ impl ElementSpawner {
         chunks: &mut Query<&mut WorldChunk>,
         chunk_manager: &ChunkManager,
     ) {
-        let radius_sq = radius * radius;
-        
-        // Calculate affected chunk range
-        let min_chunk = ChunkManager::world_to_chunk_pos(world_pos - Vec3::splat(radius));
-        let max_chunk = ChunkManager::world_to_chunk_pos(world_pos + Vec3::splat(radius));
-        
-        // Iterate through all potentially affected chunks
-        for cx in min_chunk.x..=max_chunk.x {
-            for cy in min_chunk.y..=max_chunk.y {
-                for cz in min_chunk.z..=max_chunk.z {
-                    let chunk_pos = IVec3::new(cx, cy, cz);
-                    
-                    // Get the chunk entity
-                    if let Some(entity) = chunk_manager.get_chunk_entity(chunk_pos) {
-                        if let Ok(mut chunk) = chunks.get_mut(entity) {
-                            // Fill sphere within this chunk
-                            chunk.fill_sphere(world_pos, radius, voxel);
-                            
-                            // Mark as having dynamic elements
-                            if voxel.material().is_dynamic() {
-                                chunk.has_dynamic_elements = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        Self::spawn_element_spheres(&[world_pos], radius, voxel, chunks, chunk_manager);
+    }
+
+    /// Spawn a torus of elements around `axis` (shockwave rings, portals)
+    pub fn spawn_ring(
+        center: Vec3,
+        major_radius: f32,
+        minor_radius: f32,
+        axis: Vec3,
+        voxel: VoxelData,
+        chunks: &mut Query<&mut WorldChunk>,
+        chunk_manager: &ChunkManager,
+    ) {
+        // The torus lies inside the sphere through its outer edge
+        chunk_manager.edit_chunks_in_sphere(center, major_radius + minor_radius, chunks, |chunk| {
+            chunk.fill_torus(center, major_radius, minor_radius, axis, voxel);
+        });
+    }
+
+    /// Spawn a fire sphere with a ragged, organic boundary
+    /// `jitter` (0..1) controls how much density varies and how many edge voxels are skipped.
+    pub fn spawn_fire_sphere_jittered(
+        world_pos: Vec3,
+        radius: f32,
+        jitter: f32,
+        rng: &mut SimulationRng,
+        chunks: &mut Query<&mut WorldChunk>,
+        chunk_manager: &ChunkManager,
+    ) {
+        Self::spawn_element_sphere_jittered(
+            world_pos,
+            radius,
+            VoxelData::new(
+                MaterialType::Fire,
+                255,
+                255,
+                voxel_flags::EMITS_LIGHT | voxel_flags::TEMPORARY,
+            ),
+            jitter,
+            rng,
+            chunks,
+            chunk_manager,
+        );
+    }
+
+    /// Spawn a water sphere with a ragged, organic boundary
+    pub fn spawn_water_sphere_jittered(
+        world_pos: Vec3,
+        radius: f32,
+        jitter: f32,
+        rng: &mut SimulationRng,
+        chunks: &mut Query<&mut WorldChunk>,
+        chunk_manager: &ChunkManager,
+    ) {
+        Self::spawn_element_sphere_jittered(
+            world_pos,
+            radius,
+            VoxelData::new(
+                MaterialType::Water,
+                255,
+                20,
+                voxel_flags::TRANSPARENT,
+            ),
+            jitter,
+            rng,
+            chunks,
+            chunk_manager,
+        );
+    }
+
+    /// Sphere spawner with per-voxel density jitter and a ragged edge
+    /// A jitter of 0 produces the same solid sphere as the regular spawners.
+    pub fn spawn_element_sphere_jittered(
+        world_pos: Vec3,
+        radius: f32,
+        voxel: VoxelData,
+        jitter: f32,
+        rng: &mut SimulationRng,
+        chunks: &mut Query<&mut WorldChunk>,
+        chunk_manager: &ChunkManager,
+    ) {
+        let jitter = jitter.clamp(0.0, 1.0);
+        chunk_manager.edit_chunks_in_sphere(world_pos, radius, chunks, |chunk| {
+            stamp_jittered_sphere(chunk, world_pos, radius, voxel, jitter, rng);
+        });
     }
 
     /// Spawn a line of elements (useful for testing)
@@ -156,23 +216,145 @@ impl ElementSpawner {
         let length = start.distance(end);
         let steps = (length / thickness).ceil() as i32;
         
-        for i in 0..steps {
-            let t = i as f32 / steps as f32;
-            let pos = start + direction * length * t;
-            Self::spawn_fire_sphere(pos, thickness, chunks, chunk_manager);
+        let centers: Vec<Vec3> = (0..steps)
+            .map(|i| start + direction * length * (i as f32 / steps as f32))
+            .collect();
+        Self::spawn_element_spheres(
+            &centers,
+            thickness,
+            VoxelData::new(
+                MaterialType::Fire,
+                255,
+                255,
+                voxel_flags::EMITS_LIGHT | voxel_flags::TEMPORARY,
+            ),
+            chunks,
+            chunk_manager,
+        );
+    }
+
+    /// Stamp many spheres of the same element in one pass
+    /// Chunks touched by any sphere are looked up once and each is borrowed once for all the
+    /// spheres overlapping it, instead of once per sphere. The result matches stamping the
+    /// spheres one by one in order.
+    pub fn spawn_element_spheres(
+        centers: &[Vec3],
+        radius: f32,
+        voxel: VoxelData,
+        chunks: &mut Query<&mut WorldChunk>,
+        chunk_manager: &ChunkManager,
+    ) {
+        let mut affected: Vec<(IVec3, Entity)> = centers
+            .iter()
+            .flat_map(|&center| chunk_manager.chunks_in_sphere(center, radius))
+            .collect();
+        affected.sort_unstable_by_key(|&(chunk_pos, _)| spatial_order_key(chunk_pos));
+        affected.dedup();
+
+        for (_, entity) in affected {
+            let Ok(mut chunk) = chunks.get_mut(entity) else {
+                continue;
+            };
+            for &center in centers {
+                // Spheres missing this chunk are skipped inside `fill_sphere`
+                chunk.fill_sphere(center, radius, voxel);
+            }
         }
     }
 }
 
+/// Write a jittered sphere into a single chunk
+fn stamp_jittered_sphere(
+    chunk: &mut WorldChunk,
+    center: Vec3,
+    radius: f32,
+    voxel: VoxelData,
+    jitter: f32,
+    rng: &mut SimulationRng,
+) {
+    // Local voxel range covered by the sphere, clamped to this chunk
+    let Some((min, max)) = chunk.local_range(center - Vec3::splat(radius), center + Vec3::splat(radius)) else {
+        return;
+    };
+
+    // Edge voxels in the outer `jitter` fraction of the radius may be skipped
+    let edge_start = 1.0 - jitter;
+
+    chunk.edit(|editor| {
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let dist = center.distance(editor.chunk().local_to_world(x, y, z)) / radius.max(f32::EPSILON);
+                    if dist > 1.0 {
+                        continue;
+                    }
+
+                    let mut new_voxel = voxel;
+                    if jitter > 0.0 {
+                        if dist > edge_start {
+                            let edge_t = (dist - edge_start) / jitter;
+                            if rng.next_f32() < edge_t {
+                                continue;
+                            }
+                        }
+
+                        let scale = 1.0 - jitter * rng.next_f32();
+                        new_voxel.set_density((voxel.density() as f32 * scale) as u8);
+                    }
+
+                    editor.set(x, y, z, new_voxel);
+                }
+            }
+        }
+    });
+}
+
 /// Component to mark an entity as an element spawner with automatic spawning
 #[derive(Component)]
 pub struct AutoElementSpawner {
-    pub element_type: ElementType,
+    /// Elements to pick from, with relative weights (e.g. 0.7 fire, 0.3 smoke)
+    pub elements: Vec<(ElementType, f32)>,
     pub spawn_interval: f32,
     pub spawn_radius: f32,
+    /// Number of spawns per interval
+    pub burst_count: u32,
+    /// Max distance of each burst spawn from the spawner position
+    pub burst_spread: f32,
     pub timer: f32,
 }
 
+impl AutoElementSpawner {
+    /// Spawner that always emits one element type, once per interval
+    pub fn new(element_type: ElementType, spawn_interval: f32, spawn_radius: f32) -> Self {
+        Self {
+            elements: vec![(element_type, 1.0)],
+            spawn_interval,
+            spawn_radius,
+            burst_count: 1,
+            burst_spread: 0.0,
+            timer: 0.0,
+        }
+    }
+
+    /// Pick an element type by weight, given a roll in 0..1
+    pub fn pick_element(&self, roll: f32) -> Option<ElementType> {
+        let total: f32 = self.elements.iter().map(|(_, weight)| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = roll * total;
+        for &(element, weight) in &self.elements {
+            remaining -= weight.max(0.0);
+            if remaining < 0.0 {
+                return Some(element);
+            }
+        }
+        // Rounding can leave a tiny remainder; fall back to the last weighted entry
+        self.elements.iter().rev().find(|(_, weight)| *weight > 0.0).map(|(element, _)| *element)
+    }
+}
+
 /// Type of element to spawn
 #[derive(Clone, Copy)]
 pub enum ElementType {
@@ -188,6 +370,7 @@ pub fn update_auto_spawners(
     mut spawners: Query<(&mut AutoElementSpawner, &Transform)>,
     mut chunks: Query<&mut WorldChunk>,
     chunk_manager: Res<ChunkManager>,
+    mut rng: ResMut<SimulationRng>,
 ) {
     for (mut spawner, transform) in spawners.iter_mut() {
         spawner.timer += time.delta_secs();
@@ -195,42 +378,177 @@ pub fn update_auto_spawners(
         if spawner.timer >= spawner.spawn_interval {
             spawner.timer = 0.0;
             
-            let pos = transform.translation;
-            
-            match spawner.element_type {
-                ElementType::Fire => {
-                    ElementSpawner::spawn_fire_sphere(
-                        pos,
-                        spawner.spawn_radius,
-                        &mut chunks,
-                        &chunk_manager,
-                    );
-                }
-                ElementType::Smoke => {
-                    ElementSpawner::spawn_smoke_sphere(
-                        pos,
-                        spawner.spawn_radius,
-                        &mut chunks,
-                        &chunk_manager,
-                    );
-                }
-                ElementType::Water => {
-                    ElementSpawner::spawn_water_sphere(
-                        pos,
-                        spawner.spawn_radius,
-                        &mut chunks,
-                        &chunk_manager,
-                    );
+            for _ in 0..spawner.burst_count {
+                let Some(element_type) = spawner.pick_element(rng.next_f32()) else {
+                    break;
+                };
+
+                // Scatter burst spawns around the spawner
+                let offset = Vec3::new(
+                    rng.next_f32() * 2.0 - 1.0,
+                    rng.next_f32() * 2.0 - 1.0,
+                    rng.next_f32() * 2.0 - 1.0,
+                );
+                let pos = transform.translation + offset * spawner.burst_spread;
+
+                match element_type {
+                    ElementType::Fire => {
+                        ElementSpawner::spawn_fire_sphere(
+                            pos,
+                            spawner.spawn_radius,
+                            &mut chunks,
+                            &chunk_manager,
+                        );
+                    }
+                    ElementType::Smoke => {
+                        ElementSpawner::spawn_smoke_sphere(
+                            pos,
+                            spawner.spawn_radius,
+                            &mut chunks,
+                            &chunk_manager,
+                        );
+                    }
+                    ElementType::Water => {
+                        ElementSpawner::spawn_water_sphere(
+                            pos,
+                            spawner.spawn_radius,
+                            &mut chunks,
+                            &chunk_manager,
+                        );
+                    }
+                    ElementType::Explosion => {
+                        ElementSpawner::spawn_explosion(
+                            pos,
+                            spawner.spawn_radius,
+                            &mut chunks,
+                            &chunk_manager,
+                        );
+                    }
                 }
-                ElementType::Explosion => {
-                    ElementSpawner::spawn_explosion(
-                        pos,
-                        spawner.spawn_radius,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::world::VOXELS_PER_CHUNK;
+
+    /// Spawn a jittered fire sphere into a one-chunk world and return the indices of fire voxels
+    fn spawn_fire_with_seed(seed: u32, jitter: f32) -> Vec<usize> {
+        let mut world = World::new();
+        let entity = world.spawn(WorldChunk::new(IVec3::ZERO)).id();
+        let mut manager = ChunkManager::default();
+        manager.register_chunk(IVec3::ZERO, entity);
+        world.insert_resource(manager);
+        world.insert_resource(SimulationRng::new(seed));
+
+        world
+            .run_system_once(
+                move |mut chunks: Query<&mut WorldChunk>,
+                      manager: Res<ChunkManager>,
+                      mut rng: ResMut<SimulationRng>| {
+                    ElementSpawner::spawn_fire_sphere_jittered(
+                        Vec3::splat(32.0),
+                        6.0,
+                        jitter,
+                        &mut rng,
                         &mut chunks,
-                        &chunk_manager,
+                        &manager,
                     );
+                },
+            )
+            .unwrap();
+
+        let chunk = world.get::<WorldChunk>(entity).unwrap();
+        chunk.voxels
+            .iter()
+            .enumerate()
+            .filter(|(_, voxel)| voxel.material() == MaterialType::Fire)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    #[test]
+    fn test_spawner_is_deterministic_for_seed() {
+        // Without jitter the result is exactly the voxels whose centers lie in the sphere
+        let chunk = WorldChunk::new(IVec3::ZERO);
+        let expected: Vec<usize> = (0..VOXELS_PER_CHUNK)
+            .filter(|&idx| {
+                let idx = idx as u32;
+                let pos = chunk.local_to_world(
+                    idx % CHUNK_SIZE,
+                    (idx / CHUNK_SIZE) % CHUNK_SIZE,
+                    idx / (CHUNK_SIZE * CHUNK_SIZE),
+                );
+                pos.distance(Vec3::splat(32.0)) <= 6.0
+            })
+            .collect();
+        assert_eq!(spawn_fire_with_seed(1, 0.0), expected);
+
+        // With jitter, the same seed gives the same voxels and a different seed does not
+        let jittered = spawn_fire_with_seed(42, 0.5);
+        assert!(!jittered.is_empty() && jittered.len() < expected.len());
+        assert_eq!(jittered, spawn_fire_with_seed(42, 0.5));
+        assert_ne!(jittered, spawn_fire_with_seed(43, 0.5));
+    }
+
+    #[test]
+    fn test_fire_line_matches_individual_spheres() {
+        // Two chunks side by side, with a diagonal line crossing their shared face
+        let mut world = World::new();
+        let mut manager = ChunkManager::default();
+        for chunk_pos in [IVec3::ZERO, IVec3::X] {
+            let entity = world.spawn(WorldChunk::new(chunk_pos)).id();
+            manager.register_chunk(chunk_pos, entity);
+        }
+        world.insert_resource(manager);
+
+        let (start, end) = (Vec3::new(40.0, 20.0, 30.0), Vec3::new(90.0, 40.0, 34.0));
+        let snapshot = |world: &mut World| {
+            let mut chunks: Vec<_> = world
+                .query::<&WorldChunk>()
+                .iter(world)
+                .map(|chunk| (chunk.chunk_position.to_array(), chunk.voxels.clone()))
+                .collect();
+            chunks.sort_by_key(|(pos, _)| *pos);
+            chunks
+                .into_iter()
+                .flat_map(|(_, voxels)| voxels)
+                .map(|voxel| voxel.as_u64())
+                .collect::<Vec<_>>()
+        };
+
+        world
+            .run_system_once(move |mut chunks: Query<&mut WorldChunk>, manager: Res<ChunkManager>| {
+                let steps = (start.distance(end) / 3.0).ceil() as i32;
+                let fire = VoxelData::new(
+                    MaterialType::Fire,
+                    255,
+                    255,
+                    voxel_flags::EMITS_LIGHT | voxel_flags::TEMPORARY,
+                );
+                for i in 0..steps {
+                    let pos = start.lerp(end, i as f32 / steps as f32);
+                    manager.fill_sphere_world(pos, 3.0, fire, &mut chunks);
                 }
-            }
+            })
+            .unwrap();
+        let individual = snapshot(&mut world);
+
+        for mut chunk in world.query::<&mut WorldChunk>().iter_mut(&mut world) {
+            chunk.voxels.fill(VoxelData::air());
         }
+        world
+            .run_system_once(move |mut chunks: Query<&mut WorldChunk>, manager: Res<ChunkManager>| {
+                ElementSpawner::spawn_fire_line(start, end, 3.0, &mut chunks, &manager);
+            })
+            .unwrap();
+        let batched = snapshot(&mut world);
+
+        assert!(batched.iter().any(|&voxel| voxel != VoxelData::air().as_u64()));
+        assert_eq!(batched, individual);
     }
 }
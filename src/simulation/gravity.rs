@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+/// Direction the simulation treats as "down"
+/// Falling materials step one voxel along it, buoyant ones against it, and liquids spread
+/// in the plane across it. Must be a unit axis vector; defaults to -Y.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gravity(pub IVec3);
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self(IVec3::NEG_Y)
+    }
+}
+
+impl Gravity {
+    /// Offset to the cell below
+    pub fn down(&self) -> IVec3 {
+        self.0
+    }
+
+    /// Offset to the cell above
+    pub fn up(&self) -> IVec3 {
+        -self.0
+    }
+
+    /// The four offsets perpendicular to gravity, as (+a, -a, +b, -b) with a before b in XYZ order
+    pub fn lateral(&self) -> [IVec3; 4] {
+        let down = self.0.abs();
+        let (a, b) = if down.x >= down.y && down.x >= down.z {
+            (IVec3::Y, IVec3::Z)
+        } else if down.y >= down.z {
+            (IVec3::X, IVec3::Z)
+        } else {
+            (IVec3::X, IVec3::Y)
+        };
+        [a, -a, b, -b]
+    }
+}
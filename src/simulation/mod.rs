@@ -3,10 +3,20 @@
 /// This module handles compute shader-based cellular automata
 /// for simulating fire, smoke, liquids, and other dynamic elements.
 
+pub mod backend;
 pub mod compute_pipeline;
+pub mod controls;
 pub mod cpu_simulation;
 pub mod element_spawner;
+pub mod gravity;
+pub mod perf;
+pub mod rng;
 
+pub use backend::*;
 pub use compute_pipeline::*;
+pub use controls::*;
 pub use cpu_simulation::*;
 pub use element_spawner::*;
+pub use gravity::*;
+pub use perf::*;
+pub use rng::*;
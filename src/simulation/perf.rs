@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+/// Per-tick performance counters for the simulation and chunk uploads
+/// Counts describe the most recent simulation tick; times are wall-clock milliseconds.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SimPerf {
+    /// Chunks with dynamic elements that were simulated
+    pub chunks_simulated: u32,
+    /// Chunks with dynamic elements skipped because they have settled
+    pub chunks_sleeping: u32,
+    /// Voxel writes produced by the simulation
+    pub voxels_changed: u32,
+    /// Time spent in the simulation system
+    pub sim_time_ms: f32,
+    /// Time spent copying dirty chunks into their GPU textures this frame
+    pub render_time_ms: f32,
+    /// Chunk textures uploaded this frame
+    pub chunks_uploaded: u32,
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+/// Seedable random number generator shared by simulation and spawning code
+/// Using a resource instead of a global keeps results reproducible for a given seed.
+#[derive(Resource, Clone)]
+pub struct SimulationRng {
+    state: u32,
+}
+
+impl Default for SimulationRng {
+    fn default() -> Self {
+        Self::new(12345)
+    }
+}
+
+impl SimulationRng {
+    /// Create a generator from a seed
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next raw 32-bit value (LCG)
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(1664525).wrapping_add(1013904223);
+        self.state
+    }
+
+    /// Next value in 0..1
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        // Use the high bits, which have the longest period in an LCG
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
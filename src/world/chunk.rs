@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::world::voxel::VoxelData;
+use std::sync::OnceLock;
+use crate::world::voxel::{MaterialType, VoxelData};
 
 /// Size of a chunk in voxels (each dimension)
 pub const CHUNK_SIZE: u32 = 64;
@@ -14,21 +15,45 @@ pub struct WorldChunk {
     /// Position of this chunk in chunk coordinates (not voxel coordinates)
     pub chunk_position: IVec3,
     
-    /// Voxel data stored as packed u32 values
+    /// Voxel data (the low u32 word of each voxel is what gets uploaded to the GPU)
     /// Indexed as: z * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + x
     pub voxels: Vec<VoxelData>,
     
     /// GPU texture handle for this chunk (3D texture)
     pub gpu_texture: Option<Handle<Image>>,
+
+    /// Linearly filtered density texture for the volume raymarcher, kept in step with `gpu_texture`
+    pub gpu_density_texture: Option<Handle<Image>>,
     
     /// Whether this chunk has been modified and needs re-upload to GPU
     pub dirty: bool,
+
+    /// Inclusive local voxel range modified since the last `ChunkDirty` event
+    pub dirty_region: Option<(UVec3, UVec3)>,
+
+    /// Inclusive local range of reported edits not yet written to `gpu_texture`
+    /// Grows while the image isn't ready, so a later patch still covers the earlier edits.
+    pub texture_pending: Option<(UVec3, UVec3)>,
     
     /// Whether this chunk contains any dynamic elements that need simulation
     pub has_dynamic_elements: bool,
+
+    /// Whether the simulation visits this chunk
+    /// Chunks that stop changing go to sleep; edits to them or their neighbours wake them.
+    pub active: bool,
+
+    /// Consecutive simulation ticks that produced no changes
+    pub idle_ticks: u32,
     
     /// Bounding box in world space (for culling)
     pub world_bounds: BoundingBox,
+
+    /// Whether this chunk has been baked into a static mesh
+    /// The per-voxel renderer skips frozen chunks; any voxel edit clears this.
+    pub frozen: bool,
+
+    /// Cached `dominant_surface_material`, cleared by `set_voxel`
+    surface_material: OnceLock<MaterialType>,
 }
 
 /// Bounding box for spatial queries
@@ -54,6 +79,30 @@ impl BoundingBox {
         self.min.y <= other.max.y && self.max.y >= other.min.y &&
         self.min.z <= other.max.z && self.max.z >= other.min.z
     }
+
+    /// Ray distances at which the ray enters and leaves the box (slab method)
+    /// Distances are in units of `direction`'s length. `tmin` is negative when the origin is
+    /// inside the box; `None` if the line misses the box or the box is entirely behind the ray.
+    /// A ray grazing an edge or corner touches it for a single distance.
+    pub fn ray_intersect(&self, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+        let mut tmin = f32::NEG_INFINITY;
+        let mut tmax = f32::INFINITY;
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                // Parallel to this slab: inside it everywhere or nowhere
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.min[axis] - origin[axis]) / direction[axis];
+            let t2 = (self.max[axis] - origin[axis]) / direction[axis];
+            tmin = tmin.max(t1.min(t2));
+            tmax = tmax.min(t1.max(t2));
+        }
+
+        (tmin <= tmax && tmax >= 0.0).then_some((tmin, tmax))
+    }
 }
 
 impl WorldChunk {
@@ -69,9 +118,17 @@ impl WorldChunk {
             chunk_position,
             voxels,
             gpu_texture: None,
+            gpu_density_texture: None,
             dirty: true,
+            // A new chunk is reported whole so subscribers draw it for the first time
+            dirty_region: Some((UVec3::ZERO, UVec3::splat(CHUNK_SIZE - 1))),
+            texture_pending: None,
             has_dynamic_elements: false,
+            active: true,
+            idle_ticks: 0,
             world_bounds: BoundingBox::new(world_min, world_max),
+            frozen: false,
+            surface_material: OnceLock::new(),
         }
     }
 
@@ -90,24 +147,97 @@ impl WorldChunk {
     }
 
     /// Set voxel at local chunk coordinates
+    /// For many writes at once, `edit` does the bookkeeping once instead of per voxel.
     pub fn set_voxel(&mut self, x: u32, y: u32, z: u32, voxel: VoxelData) {
         if let Some(idx) = self.voxel_index(x, y, z) {
             self.voxels[idx] = voxel;
-            self.dirty = true;
-            
-            // Check if this adds a dynamic element
-            if voxel.material().is_dynamic() {
-                self.has_dynamic_elements = true;
-            }
+            let pos = UVec3::new(x, y, z);
+            self.record_edit(pos, pos, voxel.material().is_dynamic());
         }
     }
 
+    /// Make a batch of voxel writes through a `ChunkEditor`
+    /// Dirty flags, caches and the dirty region are updated once when `edits` returns, with a
+    /// single region covering every write, so large fills report one clean box.
+    pub fn edit<R>(&mut self, edits: impl FnOnce(&mut ChunkEditor) -> R) -> R {
+        let mut editor = ChunkEditor {
+            chunk: self,
+            written: None,
+            adds_dynamic: false,
+        };
+        let result = edits(&mut editor);
+        let (written, adds_dynamic) = (editor.written, editor.adds_dynamic);
+        if let Some((min, max)) = written {
+            self.record_edit(min, max, adds_dynamic);
+        }
+        result
+    }
+
+    /// Bookkeeping after voxels in the inclusive local range `min..=max` were written
+    fn record_edit(&mut self, min: UVec3, max: UVec3, adds_dynamic: bool) {
+        self.dirty = true;
+        self.frozen = false;
+        self.surface_material.take();
+        self.mark_region_dirty(min, max);
+        self.wake();
+
+        // Check if this adds a dynamic element
+        if adds_dynamic {
+            self.has_dynamic_elements = true;
+        }
+    }
+
+    /// Make the simulation visit this chunk again
+    pub fn wake(&mut self) {
+        self.active = true;
+        self.idle_ticks = 0;
+    }
+
+    /// Record how many voxels a simulation tick changed
+    /// After `sleep_after` ticks in a row without changes the chunk goes to sleep.
+    pub fn record_sim_tick(&mut self, changes: u32, sleep_after: u32) {
+        if changes > 0 {
+            self.idle_ticks = 0;
+            return;
+        }
+        self.idle_ticks += 1;
+        if self.idle_ticks >= sleep_after {
+            self.active = false;
+        }
+    }
+
+    /// Grow the region reported by the next `ChunkDirty` event (inclusive local bounds)
+    /// Doesn't flag a GPU re-upload, so it can also request a redraw of unchanged voxels.
+    pub fn mark_region_dirty(&mut self, min: UVec3, max: UVec3) {
+        let max = max.min(UVec3::splat(CHUNK_SIZE - 1));
+        self.dirty_region = Some(match self.dirty_region {
+            Some((old_min, old_max)) => (old_min.min(min), old_max.max(max)),
+            None => (min, max),
+        });
+    }
+
+    /// Take the modified region as a world-space box, leaving the chunk clean
+    pub fn take_dirty_region(&mut self) -> Option<BoundingBox> {
+        let (min, max) = self.dirty_region.take()?;
+        let chunk_min = self.world_bounds.min;
+        Some(BoundingBox::new(
+            chunk_min + min.as_vec3(),
+            chunk_min + (max + UVec3::ONE).as_vec3(),
+        ))
+    }
+
     /// Get voxel at world position (converts to local coordinates)
     pub fn get_voxel_world(&self, world_pos: Vec3) -> Option<VoxelData> {
         let local_pos = self.world_to_local(world_pos)?;
         self.get_voxel(local_pos.x, local_pos.y, local_pos.z)
     }
 
+    /// Whether the voxel containing a world position blocks movement
+    /// Positions outside this chunk are open.
+    pub fn is_position_solid(&self, world_pos: Vec3) -> bool {
+        self.get_voxel_world(world_pos).is_some_and(|voxel| voxel.is_solid())
+    }
+
     /// Set voxel at world position
     pub fn set_voxel_world(&mut self, world_pos: Vec3, voxel: VoxelData) {
         if let Some(local_pos) = self.world_to_local(world_pos) {
@@ -152,16 +282,42 @@ impl WorldChunk {
         let max_y = max.y.min(CHUNK_SIZE);
         let max_z = max.z.min(CHUNK_SIZE);
 
-        for z in min_z..max_z {
-            for y in min_y..max_y {
-                for x in min_x..max_x {
-                    self.set_voxel(x, y, z, voxel);
+        self.edit(|editor| {
+            for z in min_z..max_z {
+                for y in min_y..max_y {
+                    for x in min_x..max_x {
+                        editor.set(x, y, z, voxel);
+                    }
                 }
             }
+        });
+    }
+
+    /// Fill the bottom `height` layers of the chunk
+    /// The world is Y-up: the floor spans X and Z, matching the isometric projection and
+    /// the simulation's default `Gravity`.
+    pub fn fill_floor(&mut self, height: u32, voxel: VoxelData) {
+        self.fill_region(UVec3::ZERO, UVec3::new(CHUNK_SIZE, height, CHUNK_SIZE), voxel);
+    }
+
+    /// Local voxel range overlapped by a world-space box, clamped to this chunk
+    /// Returns `None` if the box doesn't touch the chunk.
+    pub fn local_range(&self, world_min: Vec3, world_max: Vec3) -> Option<(UVec3, UVec3)> {
+        let max_index = Vec3::splat((CHUNK_SIZE - 1) as f32);
+        let local_min = (world_min - self.world_bounds.min).floor();
+        let local_max = (world_max - self.world_bounds.min).floor();
+        if local_max.cmplt(Vec3::ZERO).any() || local_min.cmpgt(max_index).any() {
+            return None;
         }
+
+        Some((
+            local_min.clamp(Vec3::ZERO, max_index).as_uvec3(),
+            local_max.clamp(Vec3::ZERO, max_index).as_uvec3(),
+        ))
     }
 
     /// Fill a sphere with voxels (useful for spawning elements like fire)
+    /// Only the part of the sphere inside this chunk is written.
     pub fn fill_sphere(
         &mut self,
         center_world: Vec3,
@@ -171,33 +327,221 @@ impl WorldChunk {
         let radius_sq = radius * radius;
         
         // Calculate bounding box of sphere in local coordinates
-        let local_min = self.world_to_local(center_world - Vec3::splat(radius));
-        let local_max = self.world_to_local(center_world + Vec3::splat(radius));
-        
-        if local_min.is_none() && local_max.is_none() {
+        let Some((min, max)) = self.local_range(
+            center_world - Vec3::splat(radius),
+            center_world + Vec3::splat(radius),
+        ) else {
             return; // Sphere doesn't intersect this chunk
+        };
+
+        self.edit(|editor| {
+            for z in min.z..=max.z {
+                for y in min.y..=max.y {
+                    for x in min.x..=max.x {
+                        let voxel_world = editor.chunk().local_to_world(x, y, z);
+                        let dist_sq = center_world.distance_squared(voxel_world);
+
+                        if dist_sq <= radius_sq {
+                            editor.set(x, y, z, voxel);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Fill a torus with voxels (ring around `axis` through `center_world`)
+    pub fn fill_torus(
+        &mut self,
+        center_world: Vec3,
+        major_radius: f32,
+        minor_radius: f32,
+        axis: Vec3,
+        voxel: VoxelData,
+    ) {
+        let axis = axis.normalize_or(Vec3::Z);
+        let minor_sq = minor_radius * minor_radius;
+        let extent = Vec3::splat(major_radius + minor_radius);
+
+        // Clamp the torus bounding box to this chunk in local coordinates
+        let Some((min, max)) = self.local_range(center_world - extent, center_world + extent) else {
+            return; // Torus doesn't intersect this chunk
+        };
+
+        self.edit(|editor| {
+            for z in min.z..=max.z {
+                for y in min.y..=max.y {
+                    for x in min.x..=max.x {
+                        let offset = editor.chunk().local_to_world(x, y, z) - center_world;
+                        let height = offset.dot(axis);
+                        let ring_dist = (offset - axis * height).length() - major_radius;
+
+                        if ring_dist * ring_dist + height * height <= minor_sq {
+                            editor.set(x, y, z, voxel);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Merge solid voxels into a small set of world-space boxes (greedy meshing)
+    /// Runs are grown along X, then Y, then Z. A fully solid chunk yields one box.
+    #[cfg(test)]
+    pub fn extract_collision_boxes(&self) -> Vec<BoundingBox> {
+        let size = CHUNK_SIZE as usize;
+        let mut used = vec![false; VOXELS_PER_CHUNK];
+        let index = |x: usize, y: usize, z: usize| z * size * size + y * size + x;
+        let open = |used: &[bool], x: usize, y: usize, z: usize| {
+            let idx = index(x, y, z);
+            !used[idx] && self.voxels[idx].is_solid()
+        };
+
+        let mut boxes = Vec::new();
+        for z in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    if !open(&used, x, y, z) {
+                        continue;
+                    }
+
+                    // Grow along X
+                    let mut end_x = x + 1;
+                    while end_x < size && open(&used, end_x, y, z) {
+                        end_x += 1;
+                    }
+
+                    // Grow along Y while the whole row is free
+                    let mut end_y = y + 1;
+                    while end_y < size && (x..end_x).all(|ix| open(&used, ix, end_y, z)) {
+                        end_y += 1;
+                    }
+
+                    // Grow along Z while the whole slab is free
+                    let mut end_z = z + 1;
+                    while end_z < size
+                        && (y..end_y).all(|iy| (x..end_x).all(|ix| open(&used, ix, iy, end_z)))
+                    {
+                        end_z += 1;
+                    }
+
+                    for iz in z..end_z {
+                        for iy in y..end_y {
+                            for ix in x..end_x {
+                                used[index(ix, iy, iz)] = true;
+                            }
+                        }
+                    }
+
+                    let min = Vec3::new(x as f32, y as f32, z as f32);
+                    let max = Vec3::new(end_x as f32, end_y as f32, end_z as f32);
+                    boxes.push(BoundingBox::new(
+                        self.world_bounds.min + min,
+                        self.world_bounds.min + max,
+                    ));
+                }
+            }
+        }
+
+        boxes
+    }
+
+    /// Compute a surface normal for a voxel from which of its neighbors are air
+    /// Sums the outward direction of each air-facing neighbor and normalizes.
+    /// Interior voxels (and air voxels) return zero. Voxels whose exposed faces
+    /// cancel out (e.g. fully exposed) point away from the average of their solid
+    /// neighbors, or straight up if they have none.
+    /// Neighbors outside this chunk are treated as air.
+    #[cfg(test)]
+    pub fn surface_normal(&self, x: u32, y: u32, z: u32) -> Vec3 {
+        match self.get_voxel(x, y, z) {
+            Some(voxel) if !voxel.is_empty() => {}
+            _ => return Vec3::ZERO,
         }
 
-        let min = local_min.unwrap_or(UVec3::ZERO);
-        let max = local_max.unwrap_or(UVec3::splat(CHUNK_SIZE - 1));
-
-        for z in min.z..=max.z.min(CHUNK_SIZE - 1) {
-            for y in min.y..=max.y.min(CHUNK_SIZE - 1) {
-                for x in min.x..=max.x.min(CHUNK_SIZE - 1) {
-                    let voxel_world = self.local_to_world(x, y, z);
-                    let dist_sq = center_world.distance_squared(voxel_world);
-                    
-                    if dist_sq <= radius_sq {
-                        self.set_voxel(x, y, z, voxel);
+        let is_air = |dx: i32, dy: i32, dz: i32| -> bool {
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            let nz = z as i32 + dz;
+            if nx < 0 || ny < 0 || nz < 0 {
+                return true;
+            }
+            self.get_voxel(nx as u32, ny as u32, nz as u32)
+                .is_none_or(|v| v.is_empty())
+        };
+
+        // Sum outward directions of the six face neighbors
+        let faces = [
+            IVec3::X, IVec3::NEG_X,
+            IVec3::Y, IVec3::NEG_Y,
+            IVec3::Z, IVec3::NEG_Z,
+        ];
+        let mut exposed = false;
+        let mut sum = Vec3::ZERO;
+        for dir in faces {
+            if is_air(dir.x, dir.y, dir.z) {
+                exposed = true;
+                sum += dir.as_vec3();
+            }
+        }
+
+        if !exposed {
+            return Vec3::ZERO;
+        }
+        if sum.length_squared() > 0.0001 {
+            return sum.normalize();
+        }
+
+        // Exposed faces cancel out: point away from the local average of solid neighbors
+        let mut solid_sum = Vec3::ZERO;
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx, dy, dz) != (0, 0, 0) && !is_air(dx, dy, dz) {
+                        solid_sum += Vec3::new(dx as f32, dy as f32, dz as f32);
                     }
                 }
             }
         }
+
+        if solid_sum.length_squared() > 0.0001 {
+            -solid_sum.normalize()
+        } else {
+            Vec3::Y
+        }
     }
 
-    /// Get raw voxel data as u32 slice (for GPU upload)
-    pub fn as_u32_slice(&self) -> Vec<u32> {
-        self.voxels.iter().map(|v| v.as_u32()).collect()
+    /// Most common material among the topmost solid voxel of each column
+    /// A cheap "what does this chunk look like from above" summary for the minimap and
+    /// overworld views. Columns without a solid voxel don't vote, and a chunk with none at
+    /// all returns `Air`. Cached until the next `set_voxel`; writes straight to `voxels`
+    /// aren't seen.
+    pub fn dominant_surface_material(&self) -> MaterialType {
+        *self.surface_material.get_or_init(|| {
+            let mut counts = [0u32; 256];
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    let top = (0..CHUNK_SIZE)
+                        .rev()
+                        .filter_map(|y| self.get_voxel(x, y, z))
+                        .find(|voxel| voxel.is_solid());
+                    if let Some(voxel) = top {
+                        counts[voxel.material() as usize] += 1;
+                    }
+                }
+            }
+
+            // Ties go to the lower material id so the answer doesn't depend on iteration order
+            let (id, count) = counts
+                .iter()
+                .enumerate()
+                .fold((0, 0), |best, (id, &count)| if count > best.1 { (id, count) } else { best });
+            if count == 0 {
+                MaterialType::Air
+            } else {
+                MaterialType::from_u8(id as u8)
+            }
+        })
     }
 
     /// Check if this chunk needs dynamic simulation
@@ -212,6 +556,122 @@ impl WorldChunk {
     }
 }
 
+/// Sent when a chunk's voxels change, carrying the world-space box that changed
+/// Renderers, the texture uploader and (later) replication update only this region.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ChunkDirty {
+    pub chunk_pos: IVec3,
+    pub dirty_aabb: BoundingBox,
+}
+
+impl ChunkDirty {
+    /// The changed region as inclusive local voxel bounds within the chunk
+    pub fn local_range(&self) -> (UVec3, UVec3) {
+        let chunk_min = self.chunk_pos.as_vec3() * CHUNK_SIZE as f32;
+        (
+            (self.dirty_aabb.min - chunk_min).as_uvec3(),
+            (self.dirty_aabb.max - chunk_min - Vec3::ONE).as_uvec3(),
+        )
+    }
+}
+
+/// Batched writes into one chunk, handed out by `WorldChunk::edit`
+/// Writes go straight to the voxels; the chunk's bookkeeping waits until the batch ends.
+pub struct ChunkEditor<'a> {
+    chunk: &'a mut WorldChunk,
+    /// Inclusive local range covering every write so far
+    written: Option<(UVec3, UVec3)>,
+    adds_dynamic: bool,
+}
+
+impl ChunkEditor<'_> {
+    /// The chunk being edited, including writes made so far
+    pub fn chunk(&self) -> &WorldChunk {
+        self.chunk
+    }
+
+    /// Set voxel at local chunk coordinates; out-of-range positions are ignored
+    pub fn set(&mut self, x: u32, y: u32, z: u32, voxel: VoxelData) {
+        let Some(idx) = self.chunk.voxel_index(x, y, z) else {
+            return;
+        };
+        self.chunk.voxels[idx] = voxel;
+
+        let pos = UVec3::new(x, y, z);
+        self.written = Some(match self.written {
+            Some((min, max)) => (min.min(pos), max.max(pos)),
+            None => (pos, pos),
+        });
+        self.adds_dynamic |= voxel.material().is_dynamic();
+    }
+}
+
+/// Report every chunk edited since the last run as a `ChunkDirty` event
+/// Subscribers that want same-frame updates run after this system.
+pub fn emit_chunk_dirty_events(
+    mut chunks: Query<&mut WorldChunk>,
+    mut dirty_events: EventWriter<ChunkDirty>,
+) {
+    for mut chunk in chunks.iter_mut() {
+        // Check through a shared borrow so clean chunks aren't flagged as changed
+        if chunk.dirty_region.is_none() {
+            continue;
+        }
+        let chunk_pos = chunk.chunk_position;
+        if let Some(dirty_aabb) = chunk.take_dirty_region() {
+            dirty_events.write(ChunkDirty { chunk_pos, dirty_aabb });
+        }
+    }
+}
+
+/// Sort key giving chunks a stable (z, y, x) order
+pub fn spatial_order_key(chunk_pos: IVec3) -> (i32, i32, i32) {
+    (chunk_pos.z, chunk_pos.y, chunk_pos.x)
+}
+
+/// First blocking contact found by `ChunkManager::swept_aabb`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the move (0..=1) completed before contact
+    pub time: f32,
+    /// Box center at contact
+    pub position: Vec3,
+    /// Axis-aligned normal of the face that was hit
+    pub normal: IVec3,
+    /// World voxel coordinate that blocked the move
+    pub voxel: IVec3,
+}
+
+/// When a box moving by `delta` first enters a voxel, as a fraction of the move, and the face hit
+/// Touching counts as separate on every axis, so only real penetration registers.
+fn sweep_against_voxel(start: Vec3, delta: Vec3, half_extents: Vec3, voxel: IVec3) -> Option<(f32, IVec3)> {
+    // The voxel grown by the box's half extents, against which the box center moves as a point
+    let near = voxel.as_vec3() - half_extents;
+    let far = voxel.as_vec3() + Vec3::ONE + half_extents;
+
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = IVec3::ZERO;
+    for axis in 0..3 {
+        if delta[axis] == 0.0 {
+            if start[axis] <= near[axis] || start[axis] >= far[axis] {
+                return None;
+            }
+            continue;
+        }
+        let (enter_face, leave_face) = if delta[axis] > 0.0 { (near[axis], far[axis]) } else { (far[axis], near[axis]) };
+        let axis_entry = (enter_face - start[axis]) / delta[axis];
+        exit = exit.min((leave_face - start[axis]) / delta[axis]);
+        if axis_entry > entry {
+            entry = axis_entry;
+            normal = IVec3::ZERO;
+            normal[axis] = if delta[axis] > 0.0 { -1 } else { 1 };
+        }
+    }
+
+    (entry < exit && (0.0..=1.0).contains(&entry)).then_some((entry, normal))
+}
+
 /// Resource managing all active chunks in the world
 #[derive(Resource, Default)]
 pub struct ChunkManager {
@@ -260,6 +720,56 @@ impl ChunkManager {
         self.chunks.get(&chunk_pos).copied()
     }
 
+    /// All loaded chunks in a stable spatial order (by z, then y, then x)
+    /// Use this instead of iterating `chunks` directly wherever order affects results,
+    /// since `HashMap` order changes between runs.
+    pub fn chunks_in_order(&self) -> Vec<(IVec3, Entity)> {
+        let mut chunks: Vec<(IVec3, Entity)> =
+            self.chunks.iter().map(|(&pos, &entity)| (pos, entity)).collect();
+        chunks.sort_unstable_by_key(|(pos, _)| spatial_order_key(*pos));
+        chunks
+    }
+
+    /// Loaded chunks overlapping a world-space box, in spatial order
+    pub fn chunks_in_aabb(&self, world_min: Vec3, world_max: Vec3) -> Vec<(IVec3, Entity)> {
+        let min_chunk = Self::world_to_chunk_pos(world_min);
+        let max_chunk = Self::world_to_chunk_pos(world_max);
+
+        let mut chunks = Vec::new();
+        for cz in min_chunk.z..=max_chunk.z {
+            for cy in min_chunk.y..=max_chunk.y {
+                for cx in min_chunk.x..=max_chunk.x {
+                    let chunk_pos = IVec3::new(cx, cy, cz);
+                    if let Some(entity) = self.get_chunk_entity(chunk_pos) {
+                        chunks.push((chunk_pos, entity));
+                    }
+                }
+            }
+        }
+        chunks
+    }
+
+    /// Loaded chunks overlapping a world-space sphere, in spatial order
+    /// Chunks inside the sphere's bounding box but outside the sphere itself are skipped.
+    pub fn chunks_in_sphere(&self, center: Vec3, radius: f32) -> Vec<(IVec3, Entity)> {
+        let chunk_size = CHUNK_SIZE as f32;
+        let mut chunks = self.chunks_in_aabb(center - Vec3::splat(radius), center + Vec3::splat(radius));
+        chunks.retain(|(chunk_pos, _)| {
+            let chunk_min = chunk_pos.as_vec3() * chunk_size;
+            let closest = center.clamp(chunk_min, chunk_min + Vec3::splat(chunk_size));
+            closest.distance_squared(center) <= radius * radius
+        });
+        chunks
+    }
+
+    /// World-space box covering every loaded chunk, or `None` when nothing is loaded
+    pub fn world_bounds(&self) -> Option<(Vec3, Vec3)> {
+        let min = self.chunks.keys().copied().reduce(IVec3::min)?;
+        let max = self.chunks.keys().copied().reduce(IVec3::max)?;
+        let chunk_size = CHUNK_SIZE as f32;
+        Some((min.as_vec3() * chunk_size, (max + IVec3::ONE).as_vec3() * chunk_size))
+    }
+
     /// Register a new chunk
     pub fn register_chunk(&mut self, chunk_pos: IVec3, entity: Entity) {
         self.chunks.insert(chunk_pos, entity);
@@ -269,6 +779,91 @@ impl ChunkManager {
     pub fn unregister_chunk(&mut self, chunk_pos: IVec3) -> Option<Entity> {
         self.chunks.remove(&chunk_pos)
     }
+
+    /// Fill a world-space sphere across every loaded chunk it overlaps
+    /// Chunk boundaries are handled here so spheres straddling chunks have no seams.
+    pub fn fill_sphere_world(
+        &self,
+        world_pos: Vec3,
+        radius: f32,
+        voxel: VoxelData,
+        chunks: &mut Query<&mut WorldChunk>,
+    ) {
+        self.edit_chunks_in_sphere(world_pos, radius, chunks, |chunk| {
+            chunk.fill_sphere(world_pos, radius, voxel);
+        });
+    }
+
+    /// Run `edit` on every loaded chunk overlapping a world-space sphere, in spatial order
+    /// Shared by the world-space shape fills; each one writes only its part inside the chunk.
+    pub fn edit_chunks_in_sphere(
+        &self,
+        center: Vec3,
+        radius: f32,
+        chunks: &mut Query<&mut WorldChunk>,
+        mut edit: impl FnMut(&mut WorldChunk),
+    ) {
+        for (_, entity) in self.chunks_in_sphere(center, radius) {
+            if let Ok(mut chunk) = chunks.get_mut(entity) {
+                edit(&mut chunk);
+            }
+        }
+    }
+
+    /// Look up a voxel by its world voxel coordinate across loaded chunks
+    pub fn get_voxel_world(&self, world_voxel: IVec3, chunks: &Query<&WorldChunk>) -> Option<VoxelData> {
+        let chunk_pos = world_voxel.div_euclid(IVec3::splat(CHUNK_SIZE as i32));
+        let local = world_voxel.rem_euclid(IVec3::splat(CHUNK_SIZE as i32)).as_uvec3();
+        let chunk = chunks.get(self.get_chunk_entity(chunk_pos)?).ok()?;
+        chunk.get_voxel(local.x, local.y, local.z)
+    }
+
+    /// Whether the voxel containing a world position blocks movement
+    /// Unloaded chunks are open, like air.
+    pub fn is_position_solid(&self, world_pos: Vec3, chunks: &Query<&WorldChunk>) -> bool {
+        self.get_chunk_entity(Self::world_to_chunk_pos(world_pos))
+            .and_then(|entity| chunks.get(entity).ok())
+            .is_some_and(|chunk| chunk.is_position_solid(world_pos))
+    }
+
+    /// Sweep a box centered at `start` toward `end` and find the first solid voxel it hits
+    /// Surfaces the box only touches don't block it, so a box resting on the floor can slide
+    /// along it, and solids it already overlaps at `start` are ignored so it can move out.
+    pub fn swept_aabb(
+        &self,
+        start: Vec3,
+        end: Vec3,
+        half_extents: Vec3,
+        chunks: &Query<&WorldChunk>,
+    ) -> Option<SweepHit> {
+        let delta = end - start;
+        let min_voxel = (start.min(end) - half_extents).floor().as_ivec3();
+        let max_voxel = (start.max(end) + half_extents).ceil().as_ivec3() - IVec3::ONE;
+
+        let mut first: Option<SweepHit> = None;
+        for z in min_voxel.z..=max_voxel.z {
+            for y in min_voxel.y..=max_voxel.y {
+                for x in min_voxel.x..=max_voxel.x {
+                    let voxel = IVec3::new(x, y, z);
+                    let Some((time, normal)) = sweep_against_voxel(start, delta, half_extents, voxel) else {
+                        continue;
+                    };
+                    if first.as_ref().is_some_and(|hit| hit.time <= time) {
+                        continue;
+                    }
+                    if self.get_voxel_world(voxel, chunks).is_some_and(|v| v.is_solid()) {
+                        first = Some(SweepHit {
+                            time,
+                            position: start + delta * time,
+                            normal,
+                            voxel,
+                        });
+                    }
+                }
+            }
+        }
+        first
+    }
 }
 
 #[cfg(test)]
@@ -283,6 +878,112 @@ mod tests {
         assert!(chunk.dirty);
     }
 
+    #[test]
+    fn test_dominant_surface_material_follows_edits() {
+        use crate::world::voxel::voxel_flags;
+
+        let dirt = VoxelData::new(MaterialType::Dirt, 255, 0, voxel_flags::COLLISION);
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        assert_eq!(chunk.dominant_surface_material(), MaterialType::Air);
+
+        // Rock floor under a dirt patch covering a quarter of the columns
+        chunk.fill_floor(4, VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(0, 4, 0), UVec3::new(64, 5, 16), dirt);
+        assert_eq!(chunk.dominant_surface_material(), MaterialType::Rock);
+
+        // Dirt buried under the floor doesn't count; dirt on top of most columns does
+        chunk.fill_region(UVec3::ZERO, UVec3::new(64, 1, 64), dirt);
+        assert_eq!(chunk.dominant_surface_material(), MaterialType::Rock);
+        chunk.fill_region(UVec3::new(0, 4, 16), UVec3::new(64, 5, 48), dirt);
+        assert_eq!(chunk.dominant_surface_material(), MaterialType::Dirt);
+    }
+
+    #[test]
+    fn test_ray_intersect() {
+        let bounds = BoundingBox::new(Vec3::ZERO, Vec3::splat(10.0));
+
+        // Straight through along X, and diagonally from outside a corner
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 5.0, 5.0), Vec3::X), Some((5.0, 15.0)));
+        let (enter, exit) = bounds.ray_intersect(Vec3::splat(-1.0), Vec3::ONE).unwrap();
+        assert!((enter - 1.0).abs() < 1e-5 && (exit - 11.0).abs() < 1e-5);
+
+        // Starting inside enters behind the origin
+        assert_eq!(bounds.ray_intersect(Vec3::splat(5.0), Vec3::NEG_Y), Some((-5.0, 5.0)));
+
+        // Misses: parallel outside a slab, pointing away, and passing beside the box
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 11.0, 5.0), Vec3::X), None);
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 5.0, 5.0), Vec3::NEG_X), None);
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 0.0, 5.0), Vec3::new(1.0, -1.0, 0.0)), None);
+
+        // Grazing: sliding along a face, and touching only an edge
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 10.0, 5.0), Vec3::X), Some((5.0, 15.0)));
+        assert_eq!(bounds.ray_intersect(Vec3::new(-5.0, 5.0, 5.0), Vec3::new(1.0, 1.0, 0.0)), Some((5.0, 5.0)));
+    }
+
+    #[test]
+    fn test_edit_batches_bookkeeping() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.take_dirty_region();
+        chunk.dirty = false;
+        chunk.active = false;
+
+        // An empty batch leaves the chunk untouched
+        chunk.edit(|_| {});
+        assert!(!chunk.dirty && !chunk.active && chunk.dirty_region.is_none());
+
+        let written = chunk.edit(|editor| {
+            editor.set(3, 10, 7, VoxelData::rock(255));
+            editor.set(5, 2, 8, VoxelData::new(MaterialType::Water, 255, 0, 0));
+            editor.set(64, 0, 0, VoxelData::rock(255));
+            // Writes are visible inside the batch
+            editor.chunk().get_voxel(3, 10, 7).map(|v| v.material())
+        });
+        assert_eq!(written, Some(MaterialType::Rock));
+        assert!(chunk.dirty && chunk.active && chunk.has_dynamic_elements);
+        assert_eq!(chunk.dirty_region, Some((UVec3::new(3, 2, 7), UVec3::new(5, 10, 8))));
+    }
+
+    #[test]
+    fn test_swept_aabb_lands_on_floor_and_slides() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_floor(4, VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(20, 4, 0), UVec3::new(21, 8, 64), VoxelData::rock(255));
+        assert!(chunk.is_position_solid(Vec3::new(8.5, 3.9, 8.5)));
+        assert!(!chunk.is_position_solid(Vec3::new(8.5, 4.1, 8.5)));
+
+        let mut manager = ChunkManager::default();
+        manager.register_chunk(IVec3::ZERO, world.spawn(chunk).id());
+        world.insert_resource(manager);
+
+        world
+            .run_system_once(|chunks: Query<&WorldChunk>, manager: Res<ChunkManager>| {
+                let half = Vec3::new(0.4, 0.9, 0.4);
+                assert!(manager.is_position_solid(Vec3::new(8.5, 3.5, 8.5), &chunks));
+                assert!(!manager.is_position_solid(Vec3::new(8.5, 3.5, -8.5), &chunks));
+
+                // Falling lands with the box's bottom on the floor's top face
+                let hit = manager.swept_aabb(Vec3::new(8.5, 10.0, 8.5), Vec3::new(8.5, 0.0, 8.5), half, &chunks).unwrap();
+                assert_eq!(hit.normal, IVec3::Y);
+                assert!((hit.position.y - 4.9).abs() < 1e-4);
+                assert!((hit.time - 0.51).abs() < 1e-4);
+
+                // Resting on the floor it slides freely until the wall at x = 20
+                let resting = Vec3::new(8.5, 4.9, 8.5);
+                assert!(manager.swept_aabb(resting, resting + Vec3::new(-4.0, 0.0, 3.0), half, &chunks).is_none());
+                let hit = manager.swept_aabb(resting, resting + Vec3::X * 20.0, half, &chunks).unwrap();
+                assert_eq!((hit.normal, hit.voxel.x), (IVec3::NEG_X, 20));
+                assert!((hit.position.x - 19.6).abs() < 1e-4);
+
+                // but can't push down into it
+                let hit = manager.swept_aabb(resting, resting - Vec3::Y, half, &chunks).unwrap();
+                assert_eq!((hit.time, hit.normal), (0.0, IVec3::Y));
+            })
+            .unwrap();
+    }
+
     #[test]
     fn test_voxel_indexing() {
         let mut chunk = WorldChunk::new(IVec3::ZERO);
@@ -295,6 +996,196 @@ mod tests {
         assert_eq!(retrieved.density(), 255);
     }
 
+    #[test]
+    fn test_fill_sphere_world_across_chunk_corner() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        // Eight chunks meeting at the world origin
+        let mut world = World::new();
+        let mut manager = ChunkManager::default();
+        for z in -1..=0 {
+            for y in -1..=0 {
+                for x in -1..=0 {
+                    let pos = IVec3::new(x, y, z);
+                    manager.register_chunk(pos, world.spawn(WorldChunk::new(pos)).id());
+                }
+            }
+        }
+        world.insert_resource(manager);
+
+        let radius = 5.0;
+        world
+            .run_system_once(move |mut chunks: Query<&mut WorldChunk>, manager: Res<ChunkManager>| {
+                manager.fill_sphere_world(Vec3::ZERO, radius, VoxelData::rock(255), &mut chunks);
+            })
+            .unwrap();
+
+        // Voxels whose centers are inside the sphere, per octant
+        let per_octant = (0..8)
+            .flat_map(|z| (0..8).flat_map(move |y| (0..8).map(move |x| (x, y, z))))
+            .filter(|&(x, y, z)| Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5).length() <= radius)
+            .count();
+        assert!(per_octant > 0);
+
+        let mut query = world.query::<&WorldChunk>();
+        for chunk in query.iter(&world) {
+            let count = chunk.voxels.iter().filter(|v| v.material() == MaterialType::Rock).count();
+            assert_eq!(count, per_octant, "chunk {:?}", chunk.chunk_position);
+        }
+    }
+
+    #[test]
+    fn test_chunk_dirty_events() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<Events<ChunkDirty>>();
+        let chunk_pos = IVec3::new(1, 0, -1);
+        let entity = world.spawn(WorldChunk::new(chunk_pos)).id();
+
+        // A new chunk is reported whole
+        world.run_system_once(emit_chunk_dirty_events).unwrap();
+        let events: Vec<ChunkDirty> = world.resource_mut::<Events<ChunkDirty>>().drain().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].local_range(), (UVec3::ZERO, UVec3::splat(CHUNK_SIZE - 1)));
+
+        // Clean chunks send nothing
+        world.run_system_once(emit_chunk_dirty_events).unwrap();
+        assert!(world.resource::<Events<ChunkDirty>>().is_empty());
+
+        // Edits are merged into one box covering just the touched voxels
+        let mut chunk = world.get_mut::<WorldChunk>(entity).unwrap();
+        chunk.set_voxel(3, 10, 7, VoxelData::rock(255));
+        chunk.set_voxel(5, 2, 8, VoxelData::rock(255));
+        world.run_system_once(emit_chunk_dirty_events).unwrap();
+        let events: Vec<ChunkDirty> = world.resource_mut::<Events<ChunkDirty>>().drain().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].chunk_pos, chunk_pos);
+        assert_eq!(events[0].local_range(), (UVec3::new(3, 2, 7), UVec3::new(5, 10, 8)));
+        let chunk_min = chunk_pos.as_vec3() * CHUNK_SIZE as f32;
+        assert_eq!(events[0].dirty_aabb.min, chunk_min + Vec3::new(3.0, 2.0, 7.0));
+        assert_eq!(events[0].dirty_aabb.max, chunk_min + Vec3::new(6.0, 11.0, 9.0));
+    }
+
+    #[test]
+    fn test_chunks_in_order() {
+        let mut manager = ChunkManager::default();
+        let positions = [
+            IVec3::new(1, 0, 0),
+            IVec3::new(0, 0, 1),
+            IVec3::new(0, 1, 0),
+            IVec3::new(-1, 0, 0),
+            IVec3::new(0, -1, 1),
+        ];
+        for (i, &pos) in positions.iter().enumerate() {
+            manager.register_chunk(pos, Entity::from_raw(i as u32));
+        }
+
+        let order: Vec<IVec3> = manager.chunks_in_order().into_iter().map(|(pos, _)| pos).collect();
+        assert_eq!(
+            order,
+            vec![
+                IVec3::new(-1, 0, 0),
+                IVec3::new(1, 0, 0),
+                IVec3::new(0, 1, 0),
+                IVec3::new(0, -1, 1),
+                IVec3::new(0, 0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunks_in_aabb_and_sphere() {
+        let mut manager = ChunkManager::default();
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let index = ((z + 1) * 9 + (y + 1) * 3 + x + 1) as u32;
+                    manager.register_chunk(IVec3::new(x, y, z), Entity::from_raw(index));
+                }
+            }
+        }
+
+        // A box around the origin touches the 8 chunks meeting there, in spatial order
+        let corner = manager.chunks_in_aabb(Vec3::splat(-1.0), Vec3::splat(1.0));
+        assert_eq!(corner.len(), 8);
+        assert_eq!(corner.first().map(|(pos, _)| *pos), Some(IVec3::splat(-1)));
+        assert_eq!(corner.last().map(|(pos, _)| *pos), Some(IVec3::ZERO));
+
+        // Unloaded chunks are never returned
+        assert_eq!(manager.chunks_in_aabb(Vec3::splat(200.0), Vec3::splat(300.0)).len(), 0);
+
+        // A sphere in the middle of chunk 0 reaches its face neighbours but not the
+        // edge and corner chunks that only its bounding box overlaps
+        let half = CHUNK_SIZE as f32 / 2.0;
+        let sphere = manager.chunks_in_sphere(Vec3::splat(half), half * 1.25);
+        assert_eq!(sphere.len(), 7);
+        assert!(sphere.iter().all(|(pos, _)| pos.abs().element_sum() <= 1));
+    }
+
+    #[test]
+    fn test_fill_torus() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        let center = Vec3::new(32.0, 32.0, 32.0);
+        chunk.fill_torus(center, 10.0, 2.0, Vec3::Z, VoxelData::rock(255));
+
+        // Ring is filled, the hole and the space above the ring are not
+        assert_eq!(chunk.get_voxel(42, 32, 32).unwrap().material(), MaterialType::Rock);
+        assert_eq!(chunk.get_voxel(32, 22, 32).unwrap().material(), MaterialType::Rock);
+        assert_eq!(chunk.get_voxel(32, 32, 32).unwrap().material(), MaterialType::Air);
+        assert_eq!(chunk.get_voxel(42, 32, 37).unwrap().material(), MaterialType::Air);
+    }
+
+    #[test]
+    fn test_extract_collision_boxes() {
+        let mut chunk = WorldChunk::new(IVec3::new(1, 0, 0));
+        chunk.fill_region(UVec3::ZERO, UVec3::splat(CHUNK_SIZE), VoxelData::rock(255));
+        let boxes = chunk.extract_collision_boxes();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].min, chunk.world_bounds.min);
+        assert_eq!(boxes[0].max, chunk.world_bounds.max);
+
+        // A floor plus a separate pillar becomes two boxes
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::ZERO, UVec3::new(CHUNK_SIZE, CHUNK_SIZE, 4), VoxelData::rock(255));
+        chunk.fill_region(UVec3::new(10, 10, 8), UVec3::new(12, 12, 20), VoxelData::rock(255));
+        let boxes = chunk.extract_collision_boxes();
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[1].min, Vec3::new(10.0, 10.0, 8.0));
+        assert_eq!(boxes[1].max, Vec3::new(12.0, 12.0, 20.0));
+    }
+
+    #[test]
+    fn test_editing_frozen_chunk_unfreezes() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::ZERO, UVec3::new(CHUNK_SIZE, 4, CHUNK_SIZE), VoxelData::rock(255));
+        chunk.frozen = true;
+        chunk.set_voxel(1, 10, 1, VoxelData::rock(255));
+        assert!(!chunk.frozen);
+    }
+
+    #[test]
+    fn test_surface_normal() {
+        let mut chunk = WorldChunk::new(IVec3::ZERO);
+        chunk.fill_region(UVec3::new(0, 0, 0), UVec3::new(10, 4, 10), VoxelData::rock(255));
+
+        // Top of the floor faces up, buried voxels have no normal
+        assert_eq!(chunk.surface_normal(5, 3, 5), Vec3::Y);
+        assert_eq!(chunk.surface_normal(5, 1, 5), Vec3::ZERO);
+
+        // Edge voxel faces diagonally outward
+        let edge = chunk.surface_normal(9, 3, 5);
+        assert!((edge - Vec3::new(1.0, 1.0, 0.0).normalize()).length() < 1e-5);
+
+        // A lone voxel has every face exposed and no solid neighbours, so it points up
+        let mut lone = WorldChunk::new(IVec3::ZERO);
+        lone.set_voxel(5, 5, 5, VoxelData::rock(255));
+        assert_eq!(lone.surface_normal(5, 5, 5), Vec3::Y);
+
+        // Air has no normal
+        assert_eq!(chunk.surface_normal(20, 20, 20), Vec3::ZERO);
+    }
+
     #[test]
     fn test_world_to_chunk_pos() {
         assert_eq!(
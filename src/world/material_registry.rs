@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use crate::world::voxel::{voxel_flags, MaterialType, UnknownMaterial, VoxelData};
+
+/// Where the game looks for material overrides at startup
+pub const MATERIALS_FILE: &str = "assets/materials.ron";
+
+/// Tunable properties of one material, as written in the materials file
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaterialDefinition {
+    /// `MaterialType` id this definition applies to
+    pub id: u8,
+    pub name: String,
+    /// sRGB color with alpha
+    pub color: (f32, f32, f32, f32),
+    /// `voxel_flags` given to newly spawned voxels of this material
+    #[serde(default)]
+    pub flags: u8,
+    /// Whether fire can ignite it and spread along it
+    #[serde(default)]
+    pub flammable: bool,
+    /// Chance per tick to rise (positive) or fall (negative) through air, -1..=1
+    #[serde(default)]
+    pub buoyancy: f32,
+    /// Diffuse multiplier; above 1.0 the material blooms
+    #[serde(default = "default_emissive_strength")]
+    pub emissive_strength: f32,
+}
+
+fn default_emissive_strength() -> f32 {
+    1.0
+}
+
+impl MaterialDefinition {
+    /// Definition matching the compiled-in behaviour of a material
+    pub fn builtin(material: MaterialType) -> Self {
+        let color = material.default_color().to_srgba();
+        let (flags, buoyancy) = match material {
+            MaterialType::Rock | MaterialType::Dirt | MaterialType::Wood | MaterialType::Metal => {
+                (voxel_flags::COLLISION, 0.0)
+            }
+            MaterialType::Glass => (voxel_flags::COLLISION | voxel_flags::TRANSPARENT, 0.0),
+            MaterialType::Fire => (voxel_flags::EMITS_LIGHT | voxel_flags::TEMPORARY, 1.0),
+            MaterialType::Smoke => (voxel_flags::TRANSPARENT, 0.3),
+            MaterialType::Water => (voxel_flags::TRANSPARENT, -1.0),
+            MaterialType::Debris => (voxel_flags::NONE, -1.0),
+            MaterialType::Air => (voxel_flags::NONE, 0.0),
+        };
+
+        Self {
+            id: material as u8,
+            name: format!("{:?}", material),
+            color: (color.red, color.green, color.blue, color.alpha),
+            flags,
+            flammable: material.is_flammable(),
+            buoyancy,
+            emissive_strength: material.emissive_strength(),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        Color::srgba(self.color.0, self.color.1, self.color.2, self.color.3)
+    }
+}
+
+/// Why a materials file couldn't be loaded
+#[derive(Debug)]
+pub enum MaterialRegistryError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    UnknownMaterial(UnknownMaterial),
+}
+
+impl std::fmt::Display for MaterialRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialRegistryError::Io(err) => write!(f, "could not read materials file: {}", err),
+            MaterialRegistryError::Parse(err) => write!(f, "invalid materials file: {}", err),
+            MaterialRegistryError::UnknownMaterial(err) => write!(f, "invalid materials file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MaterialRegistryError {}
+
+/// Runtime material properties keyed by `MaterialType` id
+/// `MaterialType` stays the hot-path identity of a voxel; this resource supplies the
+/// tunable properties renderers and the simulation read. Materials missing from a
+/// loaded file keep their built-in definition.
+#[derive(Resource, Debug, Clone)]
+pub struct MaterialRegistry {
+    materials: HashMap<u8, MaterialDefinition>,
+}
+
+impl Default for MaterialRegistry {
+    fn default() -> Self {
+        let materials = (0..=u8::MAX)
+            .filter_map(|id| MaterialType::try_from(id).ok())
+            .map(|material| (u8::from(material), MaterialDefinition::builtin(material)))
+            .collect();
+        Self { materials }
+    }
+}
+
+impl MaterialRegistry {
+    /// Built-in definitions overridden by a RON list of `MaterialDefinition`s
+    pub fn from_ron(text: &str) -> Result<Self, MaterialRegistryError> {
+        let definitions: Vec<MaterialDefinition> =
+            ron::from_str(text).map_err(MaterialRegistryError::Parse)?;
+
+        let mut registry = Self::default();
+        for definition in definitions {
+            // A definition no `MaterialType` can refer to is a typo or a file from a newer build
+            MaterialType::try_from(definition.id).map_err(MaterialRegistryError::UnknownMaterial)?;
+            registry.materials.insert(definition.id, definition);
+        }
+        Ok(registry)
+    }
+
+    /// Load a materials file from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MaterialRegistryError> {
+        let text = std::fs::read_to_string(path).map_err(MaterialRegistryError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    /// Load a materials file, falling back to the built-in table if it's missing or invalid
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match Self::load(path) {
+            Ok(registry) => {
+                info!("Loaded {} materials from {}", registry.materials.len(), path.display());
+                registry
+            }
+            Err(err) => {
+                warn!("Using built-in materials, {}: {}", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    /// Properties of a material
+    pub fn get(&self, material: MaterialType) -> &MaterialDefinition {
+        // `default` fills in every material and loading only overrides entries
+        &self.materials[&(material as u8)]
+    }
+
+    pub fn color(&self, material: MaterialType) -> Color {
+        self.get(material).color()
+    }
+
+    /// A new voxel of `material` carrying the flags its definition gives it
+    pub fn voxel(&self, material: MaterialType, density: u8, temperature: u8) -> VoxelData {
+        VoxelData::new(material, density, temperature, self.get(material).flags)
+    }
+
+    pub fn is_flammable(&self, material: MaterialType) -> bool {
+        self.get(material).flammable
+    }
+
+    pub fn buoyancy(&self, material: MaterialType) -> f32 {
+        self.get(material).buoyancy
+    }
+
+    pub fn emissive_strength(&self, material: MaterialType) -> f32 {
+        self.get(material).emissive_strength
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_materials_file_matches_builtin_behaviour() {
+        let registry = MaterialRegistry::from_ron(include_str!("../../assets/materials.ron")).unwrap();
+        let builtin = MaterialRegistry::default();
+
+        for id in 0..=9 {
+            let material = MaterialType::from_u8(id);
+            let (loaded, expected) = (registry.get(material), builtin.get(material));
+            assert_eq!(loaded.id, id);
+            assert_eq!(loaded.flags, expected.flags, "{:?} flags", material);
+            assert_eq!(loaded.flammable, expected.flammable, "{:?} flammability", material);
+            assert_eq!(loaded.buoyancy, expected.buoyancy, "{:?} buoyancy", material);
+            assert_eq!(loaded.emissive_strength, expected.emissive_strength, "{:?} emission", material);
+        }
+    }
+
+    #[test]
+    fn test_partial_override_keeps_builtin_materials() {
+        let registry = MaterialRegistry::from_ron(
+            r#"[(id: 4, name: "Copper", color: (0.8, 0.5, 0.3, 1.0), flammable: true)]"#,
+        )
+        .unwrap();
+
+        assert_eq!(registry.get(MaterialType::Metal).name, "Copper");
+        assert!(registry.is_flammable(MaterialType::Metal));
+        assert_eq!(registry.voxel(MaterialType::Metal, 255, 0).flags(), voxel_flags::NONE);
+        assert!(registry.voxel(MaterialType::Fire, 255, 200).has_flag(voxel_flags::EMITS_LIGHT));
+        assert_eq!(registry.emissive_strength(MaterialType::Metal), 1.0);
+        assert!(registry.is_flammable(MaterialType::Wood));
+        assert_eq!(registry.buoyancy(MaterialType::Smoke), 0.3);
+
+        assert!(matches!(
+            MaterialRegistry::from_ron("[(id: 1)]"),
+            Err(MaterialRegistryError::Parse(_))
+        ));
+        assert!(matches!(
+            MaterialRegistry::from_ron(r#"[(id: 42, name: "Lava", color: (1.0, 0.3, 0.0, 1.0))]"#),
+            Err(MaterialRegistryError::UnknownMaterial(UnknownMaterial(42)))
+        ));
+    }
+}
@@ -2,11 +2,16 @@
 /// 
 /// This module handles the voxel-based world representation, chunking,
 /// and spatial indexing for efficient rendering and simulation.
+/// World space is Y-up: height is +Y and the ground spans X and Z.
 
 pub mod chunk;
 pub mod voxel;
 pub mod spatial_index;
+pub mod material_registry;
+pub mod voxel_world;
 
 pub use chunk::*;
 pub use voxel::*;
 pub use spatial_index::*;
+pub use material_registry::*;
+pub use voxel_world::*;
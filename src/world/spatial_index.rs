@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use super::VoxelData;
 
 /// Simple spatial index using a grid-based approach
 /// This will be used for fast spatial queries (raycasting, collision detection)
@@ -14,23 +15,131 @@ impl SpatialIndex {
     }
 
     /// Perform a raycast through the world
-    /// Returns the hit position and normal if a solid voxel is hit
+    /// Steps voxel by voxel (DDA) and returns the first voxel for which
+    /// `voxel_at` yields a solid voxel. The normal is the face that was entered,
+    /// so `hit.voxel_coord + hit.normal` is the empty cell in front of it.
     pub fn raycast(
         &self,
-        _origin: Vec3,
-        _direction: Vec3,
-        _max_distance: f32,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        voxel_at: impl Fn(IVec3) -> Option<VoxelData>,
     ) -> Option<RaycastHit> {
-        // TODO: Implement DDA (Digital Differential Analyzer) raycast
-        // This will be implemented in a future phase
-        None
+        self.raycast_until(origin, direction, max_distance, |voxel| {
+            voxel_at(voxel).is_some_and(|v| v.is_solid())
+        })
+    }
+
+    /// Raycast that stops at the first voxel coordinate for which `is_hit` returns true
+    /// Used when something other than solids should block the ray, e.g. visible fluids.
+    pub fn raycast_until(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        is_hit: impl Fn(IVec3) -> bool,
+    ) -> Option<RaycastHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return None;
+        }
+
+        let mut voxel = origin.floor().as_ivec3();
+        let mut step = IVec3::ZERO;
+        // Ray distance to the next voxel boundary on each axis, and per voxel crossed
+        let mut t_max = Vec3::INFINITY;
+        let mut t_delta = Vec3::INFINITY;
+        for axis in 0..3 {
+            if direction[axis] > 0.0 {
+                step[axis] = 1;
+                t_max[axis] = (voxel[axis] as f32 + 1.0 - origin[axis]) / direction[axis];
+                t_delta[axis] = 1.0 / direction[axis];
+            } else if direction[axis] < 0.0 {
+                step[axis] = -1;
+                t_max[axis] = (voxel[axis] as f32 - origin[axis]) / direction[axis];
+                t_delta[axis] = -1.0 / direction[axis];
+            }
+        }
+
+        let mut distance = 0.0;
+        let mut normal = IVec3::ZERO;
+        loop {
+            if is_hit(voxel) {
+                return Some(RaycastHit {
+                    position: origin + direction * distance,
+                    voxel_coord: voxel,
+                    normal,
+                    distance,
+                });
+            }
+
+            // Advance across the nearest voxel boundary
+            let axis = if t_max.x < t_max.y && t_max.x < t_max.z {
+                0
+            } else if t_max.y < t_max.z {
+                1
+            } else {
+                2
+            };
+
+            distance = t_max[axis];
+            if distance > max_distance {
+                return None;
+            }
+
+            voxel[axis] += step[axis];
+            t_max[axis] += t_delta[axis];
+            normal = IVec3::ZERO;
+            normal[axis] = -step[axis];
+        }
     }
 }
 
 /// Result of a raycast query
 #[derive(Debug, Clone)]
 pub struct RaycastHit {
+    /// Point where the ray entered the hit voxel
     pub position: Vec3,
-    pub normal: Vec3,
+    /// World voxel coordinate that was hit
+    pub voxel_coord: IVec3,
+    /// Axis-aligned normal of the face that was entered (zero if the ray started inside)
+    pub normal: IVec3,
     pub distance: f32,
 }
+
+impl RaycastHit {
+    /// Empty cell adjacent to the hit face, where a new voxel should be placed
+    pub fn placement_coord(&self) -> IVec3 {
+        self.voxel_coord + self.normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raycast_hit_face_and_placement() {
+        // A single solid voxel at (4, 2, -3)
+        let target = IVec3::new(4, 2, -3);
+        let voxel_at = |pos: IVec3| Some(if pos == target { VoxelData::rock(255) } else { VoxelData::air() });
+        let index = SpatialIndex::new();
+
+        // Straight down onto its top face
+        let hit = index
+            .raycast(Vec3::new(4.5, 10.0, -2.5), Vec3::NEG_Y, 32.0, voxel_at)
+            .expect("ray should hit");
+        assert_eq!(hit.voxel_coord, target);
+        assert_eq!(hit.normal, IVec3::Y);
+        assert_eq!(hit.placement_coord(), IVec3::new(4, 3, -3));
+        assert!((hit.distance - 7.0).abs() < 1e-4);
+
+        // Diagonally into its -X face
+        let hit = index
+            .raycast(Vec3::new(0.5, 2.5, -3.5), Vec3::new(1.0, 0.0, 0.3), 32.0, voxel_at)
+            .expect("ray should hit");
+        assert_eq!(hit.voxel_coord, target);
+        assert_eq!(hit.normal, IVec3::NEG_X);
+        assert_eq!(hit.placement_coord(), IVec3::new(3, 2, -3));
+    }
+}
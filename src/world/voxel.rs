@@ -13,24 +13,54 @@ pub enum MaterialType {
     Smoke = 6,
     Water = 7,
     Debris = 8,
+    Glass = 9,
     // Add more as needed
 }
 
-impl MaterialType {
-    /// Convert from u8 back to MaterialType
-    pub fn from_u8(value: u8) -> Self {
+/// A material id that doesn't name any `MaterialType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownMaterial(pub u8);
+
+impl std::fmt::Display for UnknownMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown material id {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMaterial {}
+
+impl From<MaterialType> for u8 {
+    fn from(material: MaterialType) -> Self {
+        material as u8
+    }
+}
+
+impl TryFrom<u8> for MaterialType {
+    type Error = UnknownMaterial;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            1 => MaterialType::Rock,
-            2 => MaterialType::Dirt,
-            3 => MaterialType::Wood,
-            4 => MaterialType::Metal,
-            5 => MaterialType::Fire,
-            6 => MaterialType::Smoke,
-            7 => MaterialType::Water,
-            8 => MaterialType::Debris,
-            _ => MaterialType::Air,
+            0 => Ok(MaterialType::Air),
+            1 => Ok(MaterialType::Rock),
+            2 => Ok(MaterialType::Dirt),
+            3 => Ok(MaterialType::Wood),
+            4 => Ok(MaterialType::Metal),
+            5 => Ok(MaterialType::Fire),
+            6 => Ok(MaterialType::Smoke),
+            7 => Ok(MaterialType::Water),
+            8 => Ok(MaterialType::Debris),
+            9 => Ok(MaterialType::Glass),
+            _ => Err(UnknownMaterial(value)),
         }
     }
+}
+
+impl MaterialType {
+    /// Convert from u8 back to MaterialType, reading unknown ids as Air
+    /// Use `MaterialType::try_from` where an unknown id means corrupted data.
+    pub fn from_u8(value: u8) -> Self {
+        Self::try_from(value).unwrap_or(MaterialType::Air)
+    }
 
     /// Check if this material is solid (for collision)
     pub fn is_solid(&self) -> bool {
@@ -38,7 +68,8 @@ impl MaterialType {
             MaterialType::Rock | 
             MaterialType::Dirt | 
             MaterialType::Wood | 
-            MaterialType::Metal
+            MaterialType::Metal |
+            MaterialType::Glass
         )
     }
 
@@ -52,6 +83,11 @@ impl MaterialType {
         )
     }
 
+    /// Check if fire can ignite this material
+    pub fn is_flammable(&self) -> bool {
+        matches!(self, MaterialType::Wood)
+    }
+
     /// Get the default color for this material (for diffuse rendering)
     pub fn default_color(&self) -> Color {
         match self {
@@ -64,15 +100,30 @@ impl MaterialType {
             MaterialType::Smoke => Color::srgba(0.2, 0.2, 0.2, 0.5),
             MaterialType::Water => Color::srgba(0.2, 0.4, 0.8, 0.6),
             MaterialType::Debris => Color::srgb(0.6, 0.5, 0.4),
+            MaterialType::Glass => Color::srgba(0.75, 0.9, 1.0, 0.3),
+        }
+    }
+
+    /// Multiplier applied to the diffuse color when rendering
+    /// Above 1.0 the color leaves the displayable range and blooms; 1.0 never glows.
+    pub fn emissive_strength(&self) -> f32 {
+        match self {
+            MaterialType::Fire => 3.0,
+            _ => 1.0,
         }
     }
 }
 
-/// Voxel data packed into 32 bits (4 bytes)
-/// Layout: [material_id: 8 bits][density: 8 bits][temperature: 8 bits][flags: 8 bits]
+/// Voxel data packed into 64 bits (8 bytes)
+/// Low word layout: [material_id: 8 bits][density: 8 bits][temperature: 8 bits][flags: 8 bits]
+/// High word (`data2`) layout: [velocity x: 8 bits][velocity y: 8 bits][velocity z: 8 bits][lifetime: 8 bits],
+/// velocity components signed. It stays CPU-side; the GPU texture only receives the low word.
+/// Simulation code should go through the purpose-named accessors (`heat`, `lifetime`,
+/// `velocity`) so a packing change only touches them.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct VoxelData {
     data: u32,
+    data2: u32,
 }
 
 /// Flags for voxel properties
@@ -83,6 +134,8 @@ pub mod voxel_flags {
     pub const TEMPORARY: u8 = 1 << 2;   // Will be removed after lifetime
     pub const STATIC: u8 = 1 << 3;      // Part of static geometry (no simulation)
     pub const TRANSPARENT: u8 = 1 << 4; // Allows light to pass through
+    pub const SOURCE: u8 = 1 << 5;      // Continuously emits its material into empty neighbors
+    pub const DRAIN: u8 = 1 << 6;       // Removes matching material from its neighbors
 }
 
 impl VoxelData {
@@ -92,7 +145,7 @@ impl VoxelData {
             | ((density as u32) << 8) 
             | ((temperature as u32) << 16) 
             | ((flags as u32) << 24);
-        Self { data }
+        Self { data, data2: 0 }
     }
 
     /// Create an empty (air) voxel
@@ -110,6 +163,53 @@ impl VoxelData {
         )
     }
 
+    /// Create a glass voxel: solid like rock, but lets light through
+    pub fn glass() -> Self {
+        Self::new(
+            MaterialType::Glass,
+            255,
+            0,
+            voxel_flags::COLLISION | voxel_flags::STATIC | voxel_flags::TRANSPARENT,
+        )
+    }
+
+    /// Create an infinite water source that fills empty neighbors every tick
+    pub fn water_source() -> Self {
+        Self::new(
+            MaterialType::Water,
+            255,
+            20,
+            voxel_flags::TRANSPARENT | voxel_flags::SOURCE,
+        )
+    }
+
+    /// Create a water drain that deletes adjacent water every tick
+    pub fn water_drain() -> Self {
+        Self::new(
+            MaterialType::Water,
+            255,
+            20,
+            voxel_flags::TRANSPARENT | voxel_flags::DRAIN,
+        )
+    }
+
+    /// Interpolate between two voxels
+    /// Density and temperature are lerped; material, flags and extended state switch from `a` to `b` at t = 0.5.
+    pub fn blend(a: VoxelData, b: VoxelData, t: f32) -> VoxelData {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+        let target = if t < 0.5 { a } else { b };
+
+        let mut blended = Self::new(
+            target.material(),
+            lerp(a.density(), b.density()),
+            lerp(a.temperature(), b.temperature()),
+            target.flags(),
+        );
+        blended.data2 = target.data2;
+        blended
+    }
+
     /// Get the raw packed u32 value (for GPU upload)
     #[inline]
     pub fn as_u32(&self) -> u32 {
@@ -117,9 +217,85 @@ impl VoxelData {
     }
 
     /// Create from raw u32 value (for GPU readback)
+    /// The extended state is cleared since the GPU only stores the low word.
     #[inline]
     pub fn from_u32(data: u32) -> Self {
-        Self { data }
+        Self { data, data2: 0 }
+    }
+
+    /// Get the full 64-bit packed value (low word = `as_u32`, high word = extended state)
+    #[inline]
+    pub fn as_u64(&self) -> u64 {
+        ((self.data2 as u64) << 32) | self.data as u64
+    }
+
+    /// Create from a full 64-bit packed value
+    #[inline]
+    pub fn from_u64(data: u64) -> Self {
+        Self {
+            data: data as u32,
+            data2: (data >> 32) as u32,
+        }
+    }
+
+    /// Get the extended per-material state word
+    #[inline]
+    pub fn data2(&self) -> u32 {
+        self.data2
+    }
+
+    /// Set the extended per-material state word
+    pub fn set_data2(&mut self, data2: u32) {
+        self.data2 = data2;
+    }
+
+    /// Heat of the voxel (0-255), stored in the temperature byte
+    /// Fire cools as it burns, so its heat doubles as how much burning it has left.
+    #[inline]
+    pub fn heat(&self) -> u8 {
+        self.temperature()
+    }
+
+    /// Set the heat
+    pub fn set_heat(&mut self, heat: u8) {
+        self.set_temperature(heat);
+    }
+
+    /// Per-voxel tick counter in the top byte of `data2`
+    /// Time left for temporary voxels; debris counts how long it has been at rest.
+    #[inline]
+    pub fn lifetime(&self) -> u8 {
+        (self.data2 >> 24) as u8
+    }
+
+    /// Set the remaining lifetime in ticks
+    pub fn set_lifetime(&mut self, lifetime: u8) {
+        self.data2 = (self.data2 & 0x00FFFFFF) | ((lifetime as u32) << 24);
+    }
+
+    /// Velocity in voxels per tick, one signed byte per axis in the low bytes of `data2`
+    #[inline]
+    pub fn velocity(&self) -> IVec3 {
+        let [x, y, z, _] = self.data2.to_le_bytes();
+        IVec3::new(x as i8 as i32, y as i8 as i32, z as i8 as i32)
+    }
+
+    /// Set the velocity; components are clamped to the signed byte range
+    pub fn set_velocity(&mut self, velocity: IVec3) {
+        let velocity = velocity.clamp(IVec3::splat(i8::MIN as i32), IVec3::splat(i8::MAX as i32));
+        let [_, _, _, lifetime] = self.data2.to_le_bytes();
+        self.data2 = u32::from_le_bytes([
+            velocity.x as i8 as u8,
+            velocity.y as i8 as u8,
+            velocity.z as i8 as u8,
+            lifetime,
+        ]);
+    }
+
+    /// Material together with its remaining lifetime, for temporary voxels that decay
+    #[inline]
+    pub fn material_and_age(&self) -> (MaterialType, u8) {
+        (self.material(), self.lifetime())
     }
 
     /// Get material type
@@ -195,6 +371,13 @@ impl VoxelData {
     pub fn is_solid(&self) -> bool {
         self.has_flag(voxel_flags::COLLISION) || self.material().is_solid()
     }
+
+    /// Check if this voxel is solid and stops light (casts shadows and occlusion)
+    /// Solids flagged `TRANSPARENT`, like glass, block movement but let light through.
+    #[inline]
+    pub fn blocks_light(&self) -> bool {
+        self.is_solid() && !self.has_flag(voxel_flags::TRANSPARENT)
+    }
 }
 
 #[cfg(test)]
@@ -227,5 +410,76 @@ mod tests {
         assert_eq!(unpacked.density(), 128);
         assert_eq!(unpacked.temperature(), 50);
         assert_eq!(unpacked.flags(), voxel_flags::COLLISION);
+
+        // The extended word survives the 64-bit round trip but not the GPU word
+        let mut extended = original;
+        extended.set_data2(0xDEAD_BEEF);
+        let unpacked = VoxelData::from_u64(extended.as_u64());
+        assert_eq!(unpacked.as_u32(), packed);
+        assert_eq!(unpacked.data2(), 0xDEAD_BEEF);
+        assert_eq!(VoxelData::from_u32(extended.as_u32()).data2(), 0);
+    }
+
+    #[test]
+    fn test_named_accessors_use_their_own_bits() {
+        let mut voxel = VoxelData::new(MaterialType::Smoke, 90, 40, voxel_flags::TRANSPARENT);
+        voxel.set_lifetime(200);
+        voxel.set_velocity(IVec3::new(-3, 1000, 0));
+        voxel.set_heat(120);
+
+        assert_eq!(voxel.material_and_age(), (MaterialType::Smoke, 200));
+        assert_eq!(voxel.velocity(), IVec3::new(-3, 127, 0));
+        assert_eq!(voxel.heat(), 120);
+        assert_eq!(voxel.temperature(), 120);
+        assert_eq!(voxel.density(), 90);
+        assert_eq!(voxel.flags(), voxel_flags::TRANSPARENT);
+
+        // Velocity and lifetime share `data2` without clobbering each other
+        voxel.set_lifetime(7);
+        assert_eq!(voxel.velocity(), IVec3::new(-3, 127, 0));
+        voxel.set_velocity(IVec3::ZERO);
+        assert_eq!(voxel.lifetime(), 7);
+    }
+
+    #[test]
+    fn test_glass_is_solid_but_lets_light_through() {
+        let glass = VoxelData::glass();
+        assert_eq!(MaterialType::from_u8(glass.material() as u8), MaterialType::Glass);
+        assert!(glass.is_solid());
+        assert!(!glass.blocks_light());
+        assert!(VoxelData::rock(255).blocks_light());
+        assert!(MaterialType::Glass.default_color().alpha() < 1.0);
+    }
+
+    #[test]
+    fn test_material_id_round_trip() {
+        for id in 0..=9u8 {
+            let material = MaterialType::try_from(id).unwrap();
+            assert_eq!(u8::from(material), id);
+            assert_eq!(MaterialType::from_u8(id), material);
+        }
+
+        // Air is a valid id; anything past the last material is an error, not Air
+        assert_eq!(MaterialType::try_from(0), Ok(MaterialType::Air));
+        assert_eq!(MaterialType::try_from(200), Err(UnknownMaterial(200)));
+        assert_eq!(MaterialType::from_u8(200), MaterialType::Air);
+    }
+
+    #[test]
+    fn test_voxel_blend() {
+        let fire = VoxelData::new(MaterialType::Fire, 200, 250, voxel_flags::EMITS_LIGHT);
+        let smoke = VoxelData::new(MaterialType::Smoke, 100, 50, voxel_flags::TRANSPARENT);
+
+        let start = VoxelData::blend(fire, smoke, 0.0);
+        assert_eq!(start.as_u32(), fire.as_u32());
+
+        let end = VoxelData::blend(fire, smoke, 1.0);
+        assert_eq!(end.as_u32(), smoke.as_u32());
+
+        let mid = VoxelData::blend(fire, smoke, 0.5);
+        assert_eq!(mid.material(), MaterialType::Smoke);
+        assert_eq!(mid.density(), 150);
+        assert_eq!(mid.temperature(), 150);
+        assert_eq!(mid.flags(), voxel_flags::TRANSPARENT);
     }
 }
@@ -0,0 +1,121 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use super::{BoundingBox, ChunkManager, RaycastHit, SpatialIndex, SweepHit, VoxelData, WorldChunk, CHUNK_SIZE};
+
+/// One handle to the voxel world for gameplay systems
+/// Bundles the chunk manager and chunk query so edits can be made in world
+/// coordinates without looking up chunks by hand. Positions are world voxel
+/// coordinates, the same ones `RaycastHit::voxel_coord` reports.
+#[derive(SystemParam)]
+pub struct VoxelWorld<'w, 's> {
+    pub manager: Res<'w, ChunkManager>,
+    pub chunks: Query<'w, 's, &'static mut WorldChunk>,
+    spatial_index: Res<'w, SpatialIndex>,
+}
+
+impl VoxelWorld<'_, '_> {
+    /// Voxel at a world voxel coordinate, or `None` if its chunk isn't loaded
+    pub fn get(&self, world_voxel: IVec3) -> Option<VoxelData> {
+        self.manager.get_voxel_world(world_voxel, &self.chunks.as_readonly())
+    }
+
+    /// Write a voxel at a world voxel coordinate
+    /// Returns false if its chunk isn't loaded, in which case nothing is written.
+    pub fn set(&mut self, world_voxel: IVec3, voxel: VoxelData) -> bool {
+        let chunk_pos = world_voxel.div_euclid(IVec3::splat(CHUNK_SIZE as i32));
+        let local = world_voxel.rem_euclid(IVec3::splat(CHUNK_SIZE as i32)).as_uvec3();
+        let Some(mut chunk) = self
+            .manager
+            .get_chunk_entity(chunk_pos)
+            .and_then(|entity| self.chunks.get_mut(entity).ok())
+        else {
+            return false;
+        };
+        chunk.set_voxel(local.x, local.y, local.z, voxel);
+        true
+    }
+
+    /// Fill a world-space sphere across every loaded chunk it overlaps
+    pub fn fill_sphere(&mut self, center: Vec3, radius: f32, voxel: VoxelData) {
+        self.manager.fill_sphere_world(center, radius, voxel, &mut self.chunks);
+    }
+
+    /// Whether the voxel containing a world position blocks movement; unloaded space is open
+    pub fn is_solid(&self, world_pos: Vec3) -> bool {
+        self.manager.is_position_solid(world_pos, &self.chunks.as_readonly())
+    }
+
+    /// Move a box from `start` toward `end` and report the first solid voxel that stops it
+    /// See `ChunkManager::swept_aabb` for how touching and overlapping solids are treated.
+    pub fn sweep(&self, start: Vec3, end: Vec3, half_extents: Vec3) -> Option<SweepHit> {
+        self.manager.swept_aabb(start, end, half_extents, &self.chunks.as_readonly())
+    }
+
+    /// First solid voxel along a ray, within `max_distance` of `origin`
+    /// Only the stretch of the ray inside the loaded world is stepped through.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RaycastHit> {
+        let direction = direction.normalize_or_zero();
+        let (world_min, world_max) = self.manager.world_bounds()?;
+        let (enter, exit) = BoundingBox::new(world_min, world_max).ray_intersect(origin, direction)?;
+
+        // Start a voxel outside the world so a hit on its boundary still gets a face normal
+        let start = (enter - 1.0).max(0.0);
+        let end = exit.min(max_distance);
+        let mut hit = self.spatial_index.raycast(
+            origin + direction * start,
+            direction,
+            end - start,
+            |voxel| self.get(voxel),
+        )?;
+        hit.distance += start;
+        Some(hit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::world::MaterialType;
+
+    #[test]
+    fn test_voxel_world_edits_across_chunks() {
+        let mut world = World::new();
+        let mut manager = ChunkManager::default();
+        for x in -1..=0 {
+            let pos = IVec3::new(x, 0, 0);
+            manager.register_chunk(pos, world.spawn(WorldChunk::new(pos)).id());
+        }
+        world.insert_resource(manager);
+        world.init_resource::<SpatialIndex>();
+
+        world
+            .run_system_once(|mut voxels: VoxelWorld| {
+                // Either side of the chunk seam, and outside the loaded chunks
+                assert!(voxels.set(IVec3::new(-1, 3, 5), VoxelData::rock(255)));
+                assert!(voxels.set(IVec3::new(0, 3, 5), VoxelData::rock(255)));
+                assert!(!voxels.set(IVec3::new(0, -1, 5), VoxelData::rock(255)));
+                assert_eq!(voxels.get(IVec3::new(-1, 3, 5)).map(|v| v.material()), Some(MaterialType::Rock));
+                assert!(voxels.get(IVec3::new(0, -1, 5)).is_none());
+
+                // A ray from outside the world along -X hits the +X face of the first block
+                let hit = voxels.raycast(Vec3::new(100.0, 3.5, 5.5), Vec3::NEG_X, 200.0).unwrap();
+                assert_eq!(hit.voxel_coord, IVec3::new(0, 3, 5));
+                assert_eq!(hit.normal, IVec3::X);
+                assert!((hit.distance - 99.0).abs() < 1e-4);
+                assert!(voxels.raycast(Vec3::new(100.0, 3.5, 5.5), Vec3::NEG_X, 50.0).is_none());
+
+                // Solidity and sweeps see the same voxels, on both sides of the seam
+                assert!(voxels.is_solid(Vec3::new(-0.5, 3.5, 5.5)));
+                assert!(!voxels.is_solid(Vec3::new(-0.5, 4.5, 5.5)));
+                let hit = voxels.sweep(Vec3::new(0.0, 8.0, 5.5), Vec3::new(0.0, 4.0, 5.5), Vec3::splat(0.5)).unwrap();
+                assert_eq!(hit.normal, IVec3::Y);
+                assert!((hit.position.y - 4.5).abs() < 1e-4);
+
+                // Carving a sphere across the seam clears both
+                voxels.fill_sphere(Vec3::new(0.0, 3.5, 5.5), 2.0, VoxelData::air());
+                assert!(voxels.raycast(Vec3::new(100.0, 3.5, 5.5), Vec3::NEG_X, 200.0).is_none());
+            })
+            .unwrap();
+    }
+}